@@ -68,6 +68,7 @@ That's all folks!
         open_links_in_new_tab: true,
         allow_raw_html: true,
         use_explicit_classes: false,
+        ..MarkdownOptions::default()
     };
 
     view! {
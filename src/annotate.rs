@@ -0,0 +1,193 @@
+use crate::error::MarkdownError;
+use crate::matching::{Events, MatchTable};
+use crate::renderer::MarkdownRenderer;
+use crate::slug::Slugger;
+use pulldown_cmark::{Event, Parser, Tag};
+use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+
+/// A problem an author should fix, reported per block in author mode
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Diagnostic {
+    /// An image without alt text
+    MissingAlt { src: String },
+    /// A link with an empty destination, or an `#anchor` no heading in the document has
+    BrokenLink { href: String },
+}
+
+impl Diagnostic {
+    /// Short label shown on the badge
+    pub fn label(&self) -> &'static str {
+        match self {
+            Diagnostic::MissingAlt { .. } => "Missing alt",
+            Diagnostic::BrokenLink { .. } => "Broken link",
+        }
+    }
+
+    /// Kebab-case identifier used in `data-diagnostic` attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Diagnostic::MissingAlt { .. } => "missing-alt",
+            Diagnostic::BrokenLink { .. } => "broken-link",
+        }
+    }
+
+    /// The image source or link destination at fault
+    pub fn detail(&self) -> &str {
+        match self {
+            Diagnostic::MissingAlt { src } => src,
+            Diagnostic::BrokenLink { href } => href,
+        }
+    }
+}
+
+/// Source position and diagnostics of one top-level block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockAnnotation {
+    /// 1-based source lines the block spans; `None` when event transforms reshaped the
+    /// document so blocks no longer line up with the source
+    pub lines: Option<RangeInclusive<usize>>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl MarkdownRenderer {
+    /// Source lines and diagnostics of each top-level block of `content`, in order, for
+    /// editors drawing their own gutter. Footnote definitions and frontmatter aren't
+    /// annotated.
+    pub fn annotate(&self, content: &str) -> Result<Vec<BlockAnnotation>, MarkdownError> {
+        let events = self.parse_within_limits(content, |c| self.parse_events(c))?;
+        Ok(self.block_annotations(content, &events))
+    }
+
+    pub(crate) fn block_annotations(
+        &self,
+        content: &str,
+        events: &[Event],
+    ) -> Vec<BlockAnnotation> {
+        let blocks = annotated_blocks(events);
+        let mut lines = self.source_lines(content).into_iter();
+        let aligned = lines.len() == blocks.len();
+        let anchors = self.anchor_ids(events);
+
+        blocks
+            .into_iter()
+            .map(|block| BlockAnnotation {
+                lines: lines.next().filter(|_| aligned),
+                diagnostics: block_diagnostics(&events[block], &anchors, self),
+            })
+            .collect()
+    }
+
+    /// Line ranges of the annotated blocks of the unmodified source
+    fn source_lines(&self, content: &str) -> Vec<RangeInclusive<usize>> {
+        let mut lines = Vec::new();
+        let mut depth = 0usize;
+        for (event, range) in Parser::new_ext(content, self.parser_options()).into_offset_iter() {
+            if depth == 0 && is_annotated(&event) {
+                let block = &content[range.start..range.end];
+                let first = line_at(content, range.start);
+                let last = first + block.trim_end_matches('\n').matches('\n').count();
+                lines.push(first..=last);
+            }
+            match event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        lines
+    }
+
+    /// Ids in-document `#anchor` links may target: explicit heading ids and slugs
+    fn anchor_ids(&self, events: &[Event]) -> HashSet<String> {
+        let mut slugger = Slugger::for_headings(events);
+        let mut ids = HashSet::new();
+        let table = MatchTable::new(events);
+        let indexed = Events::new(events, &table);
+        for (i, event) in events.iter().enumerate() {
+            if let Event::Start(Tag::Heading { id, .. }) = event {
+                let (end, _) = indexed.end_of(i);
+                ids.insert(match id {
+                    Some(id) => id.to_string(),
+                    None => slugger.slug(&self.extract_text_content(&events[i + 1..i + end])),
+                });
+            }
+        }
+        ids
+    }
+}
+
+/// Event ranges of the top-level blocks that get annotated
+pub(crate) fn annotated_blocks(events: &[Event]) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, event) in events.iter().enumerate() {
+        if depth == 0 {
+            start = i;
+        }
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 && is_annotated(&events[start]) {
+            blocks.push(start..i + 1);
+        }
+    }
+    blocks
+}
+
+/// Whether a top-level block starting with `event` is annotated. Footnote definitions are
+/// rendered at the end and frontmatter not at all.
+pub(crate) fn is_annotated(event: &Event) -> bool {
+    !matches!(
+        event,
+        Event::Start(Tag::FootnoteDefinition(_) | Tag::MetadataBlock(_))
+    )
+}
+
+fn block_diagnostics(
+    block: &[Event],
+    anchors: &HashSet<String>,
+    renderer: &MarkdownRenderer,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let table = MatchTable::new(block);
+    let indexed = Events::new(block, &table);
+    for (i, event) in block.iter().enumerate() {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                let (end, _) = indexed.end_of(i);
+                if renderer
+                    .extract_text_content(&block[i + 1..i + end])
+                    .trim()
+                    .is_empty()
+                {
+                    diagnostics.push(Diagnostic::MissingAlt {
+                        src: dest_url.to_string(),
+                    });
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let broken = match dest_url.strip_prefix('#') {
+                    Some(id) => !id.is_empty() && !anchors.contains(id),
+                    None => dest_url.trim().is_empty(),
+                };
+                if broken {
+                    diagnostics.push(Diagnostic::BrokenLink {
+                        href: dest_url.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// 1-based line number of byte `offset`
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
@@ -0,0 +1,333 @@
+use crate::components::AlertKind;
+use crate::error::MarkdownError;
+use crate::parsed::ParsedMarkdown;
+use crate::renderer::{alert_kind, heading_number, MarkdownRenderer};
+use pulldown_cmark::{CodeBlockKind, Event, Tag};
+
+/// A node of the typed document tree, for tools (linters, TOC builders, search indexers)
+/// that walk a document without handling raw pulldown-cmark events
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MdNode {
+    Heading {
+        /// 1–6, as written in the source
+        level: u8,
+        /// Explicit `{#id}` attribute
+        id: Option<String>,
+        children: Vec<MdNode>,
+    },
+    Paragraph(Vec<MdNode>),
+    BlockQuote {
+        /// Set for GFM `> [!KIND]` alerts
+        alert: Option<AlertKind>,
+        children: Vec<MdNode>,
+    },
+    CodeBlock {
+        /// First word of the fence info string
+        lang: Option<String>,
+        /// The whole fence info string, empty for indented blocks
+        info: String,
+        code: String,
+    },
+    List {
+        /// Number of the first item of an ordered list
+        start: Option<u64>,
+        items: Vec<MdNode>,
+    },
+    ListItem {
+        /// Task list items: whether the box is checked
+        checked: Option<bool>,
+        children: Vec<MdNode>,
+    },
+    /// Rows, the header row first
+    Table(Vec<MdNode>),
+    TableRow {
+        header: bool,
+        cells: Vec<MdNode>,
+    },
+    TableCell(Vec<MdNode>),
+    DefinitionList(Vec<MdNode>),
+    DefinitionTitle(Vec<MdNode>),
+    DefinitionDetails(Vec<MdNode>),
+    FootnoteDefinition {
+        label: String,
+        children: Vec<MdNode>,
+    },
+    /// Raw frontmatter text
+    Frontmatter(String),
+    Emphasis(Vec<MdNode>),
+    Strong(Vec<MdNode>),
+    Strikethrough(Vec<MdNode>),
+    Superscript(Vec<MdNode>),
+    Subscript(Vec<MdNode>),
+    Link {
+        url: String,
+        title: String,
+        children: Vec<MdNode>,
+    },
+    Image {
+        url: String,
+        title: String,
+        alt: String,
+    },
+    Text(String),
+    Code(String),
+    Math {
+        display: bool,
+        expr: String,
+    },
+    /// Raw HTML, block or inline
+    Html(String),
+    FootnoteReference(String),
+    SoftBreak,
+    HardBreak,
+    Rule,
+}
+
+impl MdNode {
+    /// Child nodes; empty for leaves such as text, code blocks and images
+    pub fn children(&self) -> &[MdNode] {
+        match self {
+            MdNode::Heading { children, .. }
+            | MdNode::BlockQuote { children, .. }
+            | MdNode::ListItem { children, .. }
+            | MdNode::FootnoteDefinition { children, .. }
+            | MdNode::Link { children, .. }
+            | MdNode::TableRow {
+                cells: children, ..
+            }
+            | MdNode::Paragraph(children)
+            | MdNode::Table(children)
+            | MdNode::TableCell(children)
+            | MdNode::DefinitionList(children)
+            | MdNode::DefinitionTitle(children)
+            | MdNode::DefinitionDetails(children)
+            | MdNode::Emphasis(children)
+            | MdNode::Strong(children)
+            | MdNode::Strikethrough(children)
+            | MdNode::Superscript(children)
+            | MdNode::Subscript(children) => children,
+            MdNode::List { items, .. } => items,
+            _ => &[],
+        }
+    }
+
+    /// Plain text of the node and its descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.push_text(&mut text);
+        text
+    }
+
+    fn push_text(&self, text: &mut String) {
+        match self {
+            MdNode::Text(t) | MdNode::Code(t) => text.push_str(t),
+            MdNode::CodeBlock { code, .. } => text.push_str(code),
+            MdNode::Math { expr, .. } => text.push_str(expr),
+            MdNode::Image { alt, .. } => text.push_str(alt),
+            MdNode::SoftBreak | MdNode::HardBreak => text.push(' '),
+            _ => {
+                for child in self.children() {
+                    child.push_text(text);
+                }
+            }
+        }
+    }
+
+    /// Walk the node and its descendants depth-first with `visitor`
+    pub fn walk<V: MdVisitor + ?Sized>(&self, visitor: &mut V) {
+        if visitor.enter(self) {
+            walk(self.children(), visitor);
+        }
+        visitor.leave(self);
+    }
+}
+
+/// Callbacks for a depth-first walk over [`MdNode`]s, see [`walk`]
+pub trait MdVisitor {
+    /// Called before a node's children; returning `false` skips them
+    fn enter(&mut self, node: &MdNode) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after a node's children (also when they were skipped)
+    fn leave(&mut self, node: &MdNode) {
+        let _ = node;
+    }
+}
+
+/// Walk `nodes` in order with `visitor`
+pub fn walk<V: MdVisitor + ?Sized>(nodes: &[MdNode], visitor: &mut V) {
+    for node in nodes {
+        node.walk(visitor);
+    }
+}
+
+impl MarkdownRenderer {
+    /// Top-level nodes of `content` as a typed tree, after the event transforms
+    pub fn parse_ast(&self, content: &str) -> Result<Vec<MdNode>, MarkdownError> {
+        let events = self.parse_within_limits(content, |c| self.parse_events(c))?;
+        Ok(build_ast(&events))
+    }
+}
+
+impl ParsedMarkdown {
+    /// Top-level nodes of the document as a typed tree
+    pub fn ast(&self) -> Vec<MdNode> {
+        build_ast(self.events())
+    }
+}
+
+/// Nest the flat event stream into nodes
+pub(crate) fn build_ast(events: &[Event]) -> Vec<MdNode> {
+    // Open tags with the nodes collected inside them so far
+    let mut open: Vec<(&Tag, Vec<MdNode>)> = Vec::new();
+    let mut root = Vec::new();
+    // Checkbox of the innermost open list item
+    let mut checked: Vec<Option<bool>> = Vec::new();
+
+    for event in events {
+        let node = match event {
+            Event::Start(tag) => {
+                if matches!(tag, Tag::Item) {
+                    checked.push(None);
+                }
+                open.push((tag, Vec::new()));
+                continue;
+            }
+            Event::End(_) => {
+                let Some((tag, children)) = open.pop() else {
+                    continue;
+                };
+                let item_checked = match tag {
+                    Tag::Item => checked.pop().flatten(),
+                    _ => None,
+                };
+                tag_node(tag, children, item_checked)
+            }
+            Event::TaskListMarker(is_checked) => {
+                if let Some(last) = checked.last_mut() {
+                    *last = Some(*is_checked);
+                }
+                continue;
+            }
+            Event::Text(text) => MdNode::Text(text.to_string()),
+            Event::Code(code) => MdNode::Code(code.to_string()),
+            Event::InlineMath(expr) => MdNode::Math {
+                display: false,
+                expr: expr.to_string(),
+            },
+            Event::DisplayMath(expr) => MdNode::Math {
+                display: true,
+                expr: expr.to_string(),
+            },
+            Event::Html(html) | Event::InlineHtml(html) => MdNode::Html(html.to_string()),
+            Event::FootnoteReference(label) => MdNode::FootnoteReference(label.to_string()),
+            Event::SoftBreak => MdNode::SoftBreak,
+            Event::HardBreak => MdNode::HardBreak,
+            Event::Rule => MdNode::Rule,
+        };
+        match open.last_mut() {
+            Some((_, children)) => children.push(node),
+            None => root.push(node),
+        }
+    }
+
+    // Unclosed tags (only from event transforms) still keep their content
+    while let Some((tag, children)) = open.pop() {
+        let node = tag_node(tag, children, None);
+        match open.last_mut() {
+            Some((_, parent)) => parent.push(node),
+            None => root.push(node),
+        }
+    }
+
+    root
+}
+
+fn tag_node(tag: &Tag, children: Vec<MdNode>, checked: Option<bool>) -> MdNode {
+    match tag {
+        Tag::Paragraph => MdNode::Paragraph(children),
+        Tag::Heading { level, id, .. } => MdNode::Heading {
+            level: heading_number(*level),
+            id: id.as_ref().map(|id| id.to_string()),
+            children,
+        },
+        Tag::BlockQuote(kind) => MdNode::BlockQuote {
+            alert: kind.map(alert_kind),
+            children,
+        },
+        Tag::CodeBlock(kind) => {
+            let info = match kind {
+                CodeBlockKind::Fenced(info) => info.to_string(),
+                CodeBlockKind::Indented => String::new(),
+            };
+            let lang = info
+                .split(|c: char| c.is_whitespace() || c == '{')
+                .next()
+                .filter(|lang| !lang.is_empty())
+                .map(str::to_string);
+            MdNode::CodeBlock {
+                lang,
+                info,
+                code: text_of(&children),
+            }
+        }
+        Tag::HtmlBlock => MdNode::Html(
+            children
+                .iter()
+                .map(|node| match node {
+                    MdNode::Html(html) | MdNode::Text(html) => html.as_str(),
+                    _ => "",
+                })
+                .collect(),
+        ),
+        Tag::List(start) => MdNode::List {
+            start: *start,
+            items: children,
+        },
+        Tag::Item => MdNode::ListItem { checked, children },
+        Tag::FootnoteDefinition(label) => MdNode::FootnoteDefinition {
+            label: label.to_string(),
+            children,
+        },
+        Tag::DefinitionList => MdNode::DefinitionList(children),
+        Tag::DefinitionListTitle => MdNode::DefinitionTitle(children),
+        Tag::DefinitionListDefinition => MdNode::DefinitionDetails(children),
+        Tag::Table(_) => MdNode::Table(children),
+        // Header cells sit directly in the table head
+        Tag::TableHead => MdNode::TableRow {
+            header: true,
+            cells: children,
+        },
+        Tag::TableRow => MdNode::TableRow {
+            header: false,
+            cells: children,
+        },
+        Tag::TableCell => MdNode::TableCell(children),
+        Tag::Emphasis => MdNode::Emphasis(children),
+        Tag::Strong => MdNode::Strong(children),
+        Tag::Strikethrough => MdNode::Strikethrough(children),
+        Tag::Superscript => MdNode::Superscript(children),
+        Tag::Subscript => MdNode::Subscript(children),
+        Tag::Link {
+            dest_url, title, ..
+        } => MdNode::Link {
+            url: dest_url.to_string(),
+            title: title.to_string(),
+            children,
+        },
+        Tag::Image {
+            dest_url, title, ..
+        } => MdNode::Image {
+            url: dest_url.to_string(),
+            title: title.to_string(),
+            alt: text_of(&children),
+        },
+        Tag::MetadataBlock(_) => MdNode::Frontmatter(text_of(&children)),
+    }
+}
+
+fn text_of(nodes: &[MdNode]) -> String {
+    nodes.iter().map(MdNode::text).collect()
+}
@@ -0,0 +1,182 @@
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+/// Turn bare `http(s)://` and `www.` URLs and e-mail addresses in text into links, as
+/// GitHub's autolink extension does. Text inside links, images and code is left alone.
+pub(crate) fn autolink_events(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut linked = Vec::with_capacity(events.len());
+    // Open links, images and raw blocks whose text isn't linked
+    let mut protected = 0usize;
+
+    for event in events {
+        match &event {
+            Event::Start(
+                Tag::Link { .. }
+                | Tag::Image { .. }
+                | Tag::CodeBlock(_)
+                | Tag::HtmlBlock
+                | Tag::MetadataBlock(_),
+            ) => protected += 1,
+            Event::End(
+                TagEnd::Link
+                | TagEnd::Image
+                | TagEnd::CodeBlock
+                | TagEnd::HtmlBlock
+                | TagEnd::MetadataBlock(_),
+            ) => protected = protected.saturating_sub(1),
+            Event::Text(text) if protected == 0 => {
+                let links = find_autolinks(text);
+                if !links.is_empty() {
+                    push_linked_text(text, links, &mut linked);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        linked.push(event);
+    }
+
+    linked
+}
+
+/// A bare URL or address at `start..end` of a text, linking to `href`
+struct Autolink {
+    start: usize,
+    end: usize,
+    href: String,
+}
+
+fn push_linked_text(text: &str, links: Vec<Autolink>, events: &mut Vec<Event<'_>>) {
+    let mut text_start = 0;
+    for link in links {
+        if link.start > text_start {
+            events.push(Event::Text(text[text_start..link.start].to_string().into()));
+        }
+        events.push(Event::Start(Tag::Link {
+            link_type: LinkType::Autolink,
+            dest_url: link.href.into(),
+            title: CowStr::Borrowed(""),
+            id: CowStr::Borrowed(""),
+        }));
+        events.push(Event::Text(text[link.start..link.end].to_string().into()));
+        events.push(Event::End(TagEnd::Link));
+        text_start = link.end;
+    }
+    if text_start < text.len() {
+        events.push(Event::Text(text[text_start..].to_string().into()));
+    }
+}
+
+fn find_autolinks(text: &str) -> Vec<Autolink> {
+    let bytes = text.as_bytes();
+    let mut links = Vec::new();
+    let mut last_end = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let found = if bytes[i] == b'@' {
+            email_at(text, i, last_end)
+        } else if i == 0
+            || bytes[i - 1].is_ascii_whitespace()
+            || matches!(bytes[i - 1], b'*' | b'_' | b'~' | b'(')
+        {
+            url_at(text, i)
+        } else {
+            None
+        };
+        match found {
+            Some(link) => {
+                i = link.end;
+                last_end = link.end;
+                links.push(link);
+            }
+            None => i += 1,
+        }
+    }
+
+    links
+}
+
+/// `http://`, `https://` or `www.` URL starting at `start`
+fn url_at(text: &str, start: usize) -> Option<Autolink> {
+    let rest = &text[start..];
+    let prefix = ["https://", "http://", "www."].into_iter().find(|prefix| {
+        rest.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+    })?;
+
+    let len = rest
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .unwrap_or(rest.len());
+    let url = trim_url_end(&rest[..len]);
+    let host_start = if prefix == "www." { 0 } else { prefix.len() };
+    let host = url[host_start..]
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let valid_host = host.contains('.')
+        && !host.starts_with('.')
+        && host
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'));
+    if !valid_host {
+        return None;
+    }
+
+    let href = if prefix == "www." {
+        format!("http://{}", url)
+    } else {
+        url.to_string()
+    };
+    Some(Autolink {
+        start,
+        end: start + url.len(),
+        href,
+    })
+}
+
+/// Drop trailing punctuation and unbalanced closing parentheses, GFM-style
+fn trim_url_end(mut url: &str) -> &str {
+    loop {
+        let trimmed = match url.as_bytes().last() {
+            Some(b'?' | b'!' | b'.' | b',' | b':' | b'*' | b'_' | b'~' | b'\'' | b'"') => {
+                &url[..url.len() - 1]
+            }
+            Some(b')') if url.matches(')').count() > url.matches('(').count() => {
+                &url[..url.len() - 1]
+            }
+            _ => return url,
+        };
+        url = trimmed;
+    }
+}
+
+/// E-mail address around the `@` at `at`, not reaching back before `min_start`
+fn email_at(text: &str, at: usize, min_start: usize) -> Option<Autolink> {
+    let bytes = text.as_bytes();
+    let mut start = at;
+    while start > min_start
+        && (bytes[start - 1].is_ascii_alphanumeric()
+            || matches!(bytes[start - 1], b'.' | b'+' | b'-' | b'_'))
+    {
+        start -= 1;
+    }
+    let domain_len = bytes[at + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_'))
+        .count();
+    let domain = text[at + 1..at + 1 + domain_len].trim_end_matches('.');
+    let valid = start < at
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with(['-', '_']);
+    if !valid {
+        return None;
+    }
+
+    let end = at + 1 + domain.len();
+    Some(Autolink {
+        start,
+        end,
+        href: format!("mailto:{}", &text[start..end]),
+    })
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+/// Storage for rendered HTML fragments, keyed by content and options.
+///
+/// Implement it over Redis, moka or any shared store so SSR servers reuse fragments
+/// across requests and processes; [`MemoryCache`] is the in-process default.
+pub trait MarkdownCacheBackend: Send + Sync {
+    /// Previously stored HTML for `key`, if any
+    fn get(&self, key: &str) -> Option<String>;
+    /// Store the HTML rendered for `key`
+    fn put(&self, key: &str, html: String);
+}
+
+/// Shared cache backend stored on [`MarkdownOptions`](crate::MarkdownOptions)
+#[derive(Clone)]
+pub struct RenderCache(Arc<dyn MarkdownCacheBackend>);
+
+impl RenderCache {
+    pub fn new(backend: impl MarkdownCacheBackend + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+
+    /// Wrap an already shared backend
+    pub fn from_arc(backend: Arc<dyn MarkdownCacheBackend>) -> Self {
+        Self(backend)
+    }
+}
+
+impl Deref for RenderCache {
+    type Target = dyn MarkdownCacheBackend;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for RenderCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RenderCache(..)")
+    }
+}
+
+/// Unbounded in-process cache backend
+#[derive(Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached fragments
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MarkdownCacheBackend for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    fn put(&self, key: &str, html: String) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key.to_string(), html);
+        }
+    }
+}
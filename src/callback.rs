@@ -0,0 +1,86 @@
+use crate::citations::Citation;
+use crate::components::ImageSource;
+use crate::filter::{BlockAction, BlockContext};
+use crate::frontmatter::Frontmatter;
+use crate::mentions::MentionTarget;
+use crate::references::RepoReference;
+use crate::warnings::SanitizeEvent;
+use pulldown_cmark::Event;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Shared, thread-safe callback stored on [`MarkdownOptions`](crate::MarkdownOptions)
+///
+/// Wraps an `Arc<dyn Fn ...>` so options stay `Clone` and `Debug` while holding closures.
+pub struct MarkdownCallback<F: ?Sized>(Arc<F>);
+
+impl<F: ?Sized> MarkdownCallback<F> {
+    /// Wrap an already shared closure
+    pub fn from_arc(f: Arc<F>) -> Self {
+        Self(f)
+    }
+}
+
+impl<F: ?Sized> Clone for MarkdownCallback<F> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> Deref for MarkdownCallback<F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for MarkdownCallback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MarkdownCallback(..)")
+    }
+}
+
+/// Callback receiving the body of each HTML comment dropped from the output
+pub type HtmlCommentHandler = MarkdownCallback<dyn Fn(&str) + Send + Sync>;
+
+/// Callback receiving each piece of content the sanitizer removed or escaped
+pub type SanitizeHandler = MarkdownCallback<dyn Fn(&SanitizeEvent) + Send + Sync>;
+
+/// Callback receiving the frontmatter (metadata block) of each rendered document
+pub type FrontmatterHandler = MarkdownCallback<dyn Fn(&Frontmatter) + Send + Sync>;
+
+/// Callback mapping an in-document anchor id (without `#`) to the href that reaches it
+pub type AnchorHrefResolver = MarkdownCallback<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Callback rewriting each link destination before it is rendered
+pub type LinkRewriter = MarkdownCallback<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Callback mapping each image src to the URL (and optional `srcset`/`sizes`) rendered
+pub type ImageSrcTransform = MarkdownCallback<dyn Fn(&str) -> ImageSource + Send + Sync>;
+
+/// Callback returning the intrinsic `(width, height)` of an image by its src, if known
+pub type ImageDimensions = MarkdownCallback<dyn Fn(&str) -> Option<(u32, u32)> + Send + Sync>;
+
+/// Callback resolving a citation key (without `@`) against the application's bibliography
+pub type CitationResolver = MarkdownCallback<dyn Fn(&str) -> Option<Citation> + Send + Sync>;
+
+/// Callback mapping an emoji shortcode (without colons) to the URL of a custom emoji image
+pub type EmojiResolver = MarkdownCallback<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Callback linking a `@mention` or `#hashtag` by its name (without the sigil)
+pub type MentionResolver = MarkdownCallback<dyn Fn(&str) -> Option<MentionTarget> + Send + Sync>;
+
+/// Callback returning the URL of an issue, pull request or commit reference
+pub type ReferenceLinker = MarkdownCallback<dyn Fn(&RepoReference) -> Option<String> + Send + Sync>;
+
+/// Callback mapping a `[[wikilink]]` target to an href, `None` for pages that don't exist
+pub type WikilinkResolver = MarkdownCallback<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Callback deciding whether a heading section is kept, redacted or dropped
+pub type BlockFilter = MarkdownCallback<dyn Fn(&BlockContext) -> BlockAction + Send + Sync>;
+
+/// Transform run on the parsed events before rendering (emoji, mentions, shortcodes, ...)
+pub type EventTransform =
+    MarkdownCallback<dyn for<'a> Fn(Vec<Event<'a>>) -> Vec<Event<'a>> + Send + Sync>;
@@ -0,0 +1,68 @@
+use crate::components::MarkdownOptions;
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+/// Chat-style syntax resolved against the source while collecting parser events: with
+/// `underline`, `__text__` becomes a pair of `__` delimiters (see
+/// [`crate::spans::SpanKind`]) instead of strong emphasis; without headings, heading
+/// lines stay paragraphs that keep their `#` marker or setext underline as text.
+pub(crate) struct ChatSyntax<'a> {
+    underline: bool,
+    headings: bool,
+    /// Whether each open strong run was written with underscores
+    strong: Vec<bool>,
+    /// Underline line of the open setext heading
+    setext: Option<&'a str>,
+}
+
+impl<'a> ChatSyntax<'a> {
+    /// `None` when the options use no syntax that needs the source
+    pub(crate) fn new(options: &MarkdownOptions) -> Option<Self> {
+        (options.underline || !options.enable_headings).then(|| Self {
+            underline: options.underline,
+            headings: options.enable_headings,
+            strong: Vec::new(),
+            setext: None,
+        })
+    }
+
+    /// Push `event`, parsed from `source`, to `output`
+    pub(crate) fn push(&mut self, event: Event<'a>, source: &'a str, output: &mut Vec<Event<'a>>) {
+        match &event {
+            Event::Start(Tag::Strong) if self.underline => {
+                let underscores = source.starts_with("__");
+                self.strong.push(underscores);
+                if underscores {
+                    output.push(Event::Text(CowStr::Borrowed("__")));
+                    return;
+                }
+            }
+            // Pops the opener of every strong span, `__` or not
+            Event::End(TagEnd::Strong) if self.underline && self.strong.pop().unwrap_or(false) => {
+                output.push(Event::Text(CowStr::Borrowed("__")));
+                return;
+            }
+            Event::Start(Tag::Heading { .. }) if !self.headings => {
+                output.push(Event::Start(Tag::Paragraph));
+                let atx = source.trim_start();
+                if atx.starts_with('#') {
+                    let text = atx.trim_start_matches('#').trim_start_matches([' ', '\t']);
+                    let marker = &atx[..atx.len() - text.len()];
+                    output.push(Event::Text(CowStr::Borrowed(marker)));
+                } else {
+                    self.setext = source.trim_end().lines().last().map(str::trim);
+                }
+                return;
+            }
+            Event::End(TagEnd::Heading(_)) if !self.headings => {
+                if let Some(line) = self.setext.take() {
+                    output.push(Event::SoftBreak);
+                    output.push(Event::Text(CowStr::Borrowed(line)));
+                }
+                output.push(Event::End(TagEnd::Paragraph));
+                return;
+            }
+            _ => {}
+        }
+        output.push(event);
+    }
+}
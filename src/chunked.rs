@@ -0,0 +1,245 @@
+use crate::compat::*;
+use crate::components::MarkdownOptions;
+use crate::renderer::MarkdownRenderer;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+/// Markdown component for huge documents in client-side rendered apps.
+///
+/// The document is cut into sections at blank lines between top-level blocks, and the
+/// sections are parsed and rendered one per animation frame, so opening a massive file
+/// doesn't freeze the main thread. The first section is rendered immediately (and during
+/// SSR); the rest stream in after hydration. `progress` receives the rendered fraction
+/// from `0.0` to `1.0`.
+///
+/// Heading ids, footnote numbers and citations run on across sections, link reference
+/// definitions apply to the whole document, and footnotes are listed after the last
+/// section. `:::` containers and `[[lof]]` lists only see their own section.
+///
+/// A document over `max_input_len`, or a section nesting deeper than
+/// `max_nesting_depth`, renders `<Markdown>`'s error card in its place.
+#[component]
+pub fn MarkdownChunked(
+    /// The markdown content as a string
+    #[prop(into)]
+    content: String,
+    /// Optional CSS class for the wrapper (will be combined with Tailwind prose classes)
+    #[prop(optional)]
+    class: Option<String>,
+    /// Markdown rendering options
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+    /// Number of top-level blocks parsed and rendered per animation frame
+    #[prop(default = 25)]
+    blocks_per_frame: usize,
+    /// Receives the fraction of blocks rendered so far
+    #[prop(optional)]
+    progress: Option<RwSignal<f64>>,
+) -> impl IntoView {
+    let renderer = Arc::new(MarkdownRenderer::new(
+        options
+            .or_else(crate::use_markdown_options)
+            .unwrap_or_default(),
+    ));
+    let wrapper_class = crate::wrapper_class(&renderer, &content, class);
+    if let Err(err) = renderer.check_input_len(&content) {
+        log_error!("Failed to render markdown: {}", err);
+        if let Some(progress) = progress {
+            progress.set(1.0);
+        }
+        return view! { <div class=wrapper_class>{crate::error_card(&err)}</div> }.into_any();
+    }
+    let split = split_sections(&content, blocks_per_frame.max(1));
+    let content: Arc<str> = content.into();
+    let definitions: Arc<str> = split.definitions.into();
+    let section = Arc::new(Mutex::new(renderer.section_state(&content)));
+
+    let total = split.sections.len();
+    let visible = RwSignal::new(1.min(total));
+    if let Some(progress) = progress {
+        progress.set(if total == 0 {
+            1.0
+        } else {
+            visible.get_untracked() as f64 / total as f64
+        });
+    }
+
+    Effect::new(move |_| {
+        schedule_next_section(visible, total, progress);
+    });
+
+    let rendered_sections = split
+        .sections
+        .into_iter()
+        .enumerate()
+        .map(|(index, range)| {
+            let renderer = Arc::clone(&renderer);
+            let content = Arc::clone(&content);
+            let definitions = Arc::clone(&definitions);
+            let section = Arc::clone(&section);
+            let shown = Memo::new(move |_| visible.get() > index);
+            move || {
+                shown.get().then(|| {
+                    // Definitions go first: an unclosed fence at the end would swallow them
+                    let source = format!("{}\n{}", definitions, &content[range.clone()]);
+                    let mut section = section.lock().unwrap_or_else(|e| e.into_inner());
+                    let view = renderer
+                        .render_section(&source, &mut section)
+                        .unwrap_or_else(|err| {
+                            log_error!("Failed to render markdown: {}", err);
+                            crate::error_card(&err)
+                        });
+                    let end = (index + 1 == total).then(|| renderer.finish_sections(&mut section));
+                    view! { {view}{end} }
+                })
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class=wrapper_class>
+            {rendered_sections}
+        </div>
+    }
+    .into_any()
+}
+
+fn schedule_next_section(visible: RwSignal<usize>, total: usize, progress: Option<RwSignal<f64>>) {
+    if visible.get_untracked() >= total {
+        return;
+    }
+
+    request_animation_frame(move || {
+        let next = visible.get_untracked() + 1;
+        visible.set(next);
+        if let Some(progress) = progress {
+            progress.set(next as f64 / total as f64);
+        }
+        schedule_next_section(visible, total, progress);
+    });
+}
+
+/// A document cut into separately parsed sections
+struct Sections {
+    sections: Vec<Range<usize>>,
+    /// The document's link reference definition lines, for every section to resolve
+    /// links against
+    definitions: String,
+}
+
+/// Cut `content` into sections of up to `blocks` top-level blocks, found with a line scan
+/// rather than a parse. Cuts are only made at blank lines no block can continue past:
+/// outside code fences, `$$` math, `:::` containers and HTML comments, and before a line
+/// starting in the first column that doesn't continue a list or quote.
+fn split_sections(content: &str, blocks: usize) -> Sections {
+    let mut sections = Vec::new();
+    let mut definitions = String::new();
+    let mut section_start = 0;
+    let mut section_blocks = 0usize;
+    let mut fence: Option<(char, usize)> = None;
+    let mut in_math = false;
+    let mut in_comment = false;
+    let mut containers = 0usize;
+    let mut after_blank = true;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim();
+
+        if let Some((marker, run)) = fence {
+            if fence_run(text, marker).is_some_and(|closing| closing >= run)
+                && text.trim_start_matches(marker).trim().is_empty()
+            {
+                fence = None;
+            }
+            continue;
+        }
+        if in_comment {
+            in_comment = !text.contains("-->");
+            continue;
+        }
+        if in_math {
+            in_math = text != "$$";
+            continue;
+        }
+        if text.is_empty() {
+            after_blank = true;
+            continue;
+        }
+
+        let top_level = !line.starts_with([' ', '\t']);
+        if after_blank && containers == 0 && top_level && !continues_block(text) {
+            section_blocks += 1;
+            if section_blocks > blocks {
+                sections.push(section_start..start);
+                section_start = start;
+                section_blocks = 1;
+            }
+        }
+        after_blank = false;
+
+        if let Some(marker) = text.chars().next().filter(|c| matches!(c, '`' | '~')) {
+            if let Some(run) = fence_run(text, marker) {
+                fence = Some((marker, run));
+                continue;
+            }
+        }
+        if text == "$$" {
+            in_math = true;
+        } else if text.starts_with("<!--") && !text.contains("-->") {
+            in_comment = true;
+        } else if let Some(rest) = text.strip_prefix(":::") {
+            let rest = rest.trim_start_matches(':').trim();
+            if rest.is_empty() {
+                containers = containers.saturating_sub(1);
+            } else {
+                containers += 1;
+            }
+        } else if top_level && is_reference_definition(text) {
+            definitions.push_str(text);
+            definitions.push('\n');
+        }
+    }
+    if section_start < content.len() {
+        sections.push(section_start..content.len());
+    }
+
+    Sections {
+        sections,
+        definitions,
+    }
+}
+
+/// Length of the run of `marker` opening a code fence line, if it is one
+fn fence_run(text: &str, marker: char) -> Option<usize> {
+    let run = text.chars().take_while(|&c| c == marker).count();
+    (run >= 3).then_some(run)
+}
+
+/// Whether a line after a blank line may still belong to the block before it: list
+/// items continue a loose list, `>` lines a quote
+fn continues_block(text: &str) -> bool {
+    if text.starts_with('>') {
+        return true;
+    }
+    let bullet = text
+        .strip_prefix(['-', '*', '+'])
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '));
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    let ordered = (1..=9).contains(&digits)
+        && text[digits..]
+            .strip_prefix(['.', ')'])
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '));
+    bullet || ordered
+}
+
+/// `[label]: destination`, a link reference definition (footnote definitions excluded)
+fn is_reference_definition(text: &str) -> bool {
+    text.starts_with('[')
+        && !text.starts_with("[^")
+        && text
+            .find("]:")
+            .is_some_and(|close| close > 1 && !text[close + 2..].trim().is_empty())
+}
@@ -0,0 +1,104 @@
+/// A citation key resolved by the application's bibliography
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Citation {
+    /// Inline text replacing the key, e.g. `Knuth 1984`
+    pub text: String,
+    /// Formatted bibliography entry
+    pub reference: String,
+    /// Link for the bibliography entry (DOI, publisher page, ...)
+    pub url: Option<String>,
+}
+
+impl Citation {
+    pub fn new(text: impl Into<String>, reference: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            reference: reference.into(),
+            url: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// One `@key` of a bracketed citation, with its optional locator (`p. 33`)
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CiteItem<'a> {
+    pub(crate) key: &'a str,
+    pub(crate) locator: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum TextSegment<'a> {
+    Text(&'a str),
+    /// `[@a, p. 3; @b]`
+    Citation(Vec<CiteItem<'a>>),
+}
+
+/// Id of a key's bibliography entry
+pub(crate) fn reference_id(key: &str) -> String {
+    format!("ref-{}", key)
+}
+
+/// Split `text` around Pandoc-style bracketed citations (`[@key]`, `[@a; @b, p. 3]`).
+/// Brackets whose content is not a list of `@key`s stay text.
+pub(crate) fn citation_segments(text: &str) -> Vec<TextSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    let mut text_start = 0;
+    let mut offset = 0;
+
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(']') else {
+            break;
+        };
+        let inner = &after[..close];
+        let consumed = open + close + 2;
+        if let Some(items) = parse_cite_items(inner) {
+            let start = offset + open;
+            if start > text_start {
+                segments.push(TextSegment::Text(&text[text_start..start]));
+            }
+            segments.push(TextSegment::Citation(items));
+            text_start = offset + consumed;
+        }
+        rest = &rest[consumed..];
+        offset += consumed;
+    }
+
+    if text_start < text.len() {
+        segments.push(TextSegment::Text(&text[text_start..]));
+    }
+    segments
+}
+
+fn parse_cite_items(inner: &str) -> Option<Vec<CiteItem<'_>>> {
+    if inner.contains('[') {
+        return None;
+    }
+    inner
+        .split(';')
+        .map(|item| {
+            let item = item.trim().strip_prefix('@')?;
+            let key_len = item
+                .find(|c: char| !(c.is_alphanumeric() || "_:.-/".contains(c)))
+                .unwrap_or(item.len());
+            let key = item[..key_len].trim_end_matches(['.', ':']);
+            if key.is_empty() {
+                return None;
+            }
+            let locator = item[key.len()..].trim();
+            let locator = match locator.strip_prefix(',') {
+                Some(locator) => Some(locator.trim()).filter(|l| !l.is_empty()),
+                None if locator.is_empty() => None,
+                None => return None,
+            };
+            Some(CiteItem { key, locator })
+        })
+        .collect()
+}
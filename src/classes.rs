@@ -0,0 +1,736 @@
+use crate::components::{
+    get_alert_classes, get_code_theme_classes, get_code_title_theme_classes,
+    get_dark_code_theme_classes, get_dark_code_title_theme_classes, AlertKind, CodeBlockTheme,
+    MarkdownClassHooks, MarkdownClassMap, MarkdownClasses, MarkdownOptions, MediaLayout,
+    SoftBreakMode,
+};
+use pulldown_cmark::HeadingLevel;
+use std::sync::Arc;
+
+/// Class string shared between the bundle and every element rendered with it
+pub(crate) type Class = Arc<str>;
+
+/// Combined classes with conflicting Tailwind utilities resolved, later ones winning
+#[cfg(feature = "class-merge")]
+pub(crate) fn resolve(classes: String) -> String {
+    crate::merge::merge_classes(&classes)
+}
+
+/// Combined classes, unchanged without the `class-merge` feature
+#[cfg(not(feature = "class-merge"))]
+pub(crate) fn resolve(classes: String) -> String {
+    classes
+}
+
+/// Every element's final class string, combined once per renderer from the options
+/// (explicit classes, hooks, code theme, compact headings), so rendering only clones
+/// reference-counted strings instead of formatting them per element.
+///
+/// Fields only used in explicit-classes mode hold the explicit value; the renderer keeps
+/// emitting the bare element otherwise.
+pub(crate) struct ClassBundle {
+    headings: [Option<Class>; 6],
+    alerts: [Class; 5],
+    pub(crate) alert_title: Class,
+    /// `None` renders a bare `<p>`
+    pub(crate) paragraph: Option<Class>,
+    pub(crate) blockquote: Class,
+    /// `<pre>` class including the code theme, without the language class
+    pub(crate) code_block: Class,
+    pub(crate) code_block_code: Class,
+    /// Title bar above a titled code block, colored like the code theme
+    pub(crate) code_title: Class,
+    /// `<div>` joining the title bar and the code block below it
+    pub(crate) code_titled: Class,
+    /// `<div>` holding a code block and its action buttons
+    pub(crate) code_block_wrapper: Class,
+    /// Copy button, including its position next to the expand button when there is one
+    pub(crate) code_copy_button: Class,
+    /// Expand button and the popover modal it opens: the modal, its close button and the
+    /// classes added to the `<pre>` inside it
+    pub(crate) code_expand_button: Class,
+    pub(crate) code_modal: Class,
+    pub(crate) code_modal_close: Class,
+    pub(crate) code_modal_pre: Class,
+    /// Clamped long code block: wrapper, hidden toggle checkbox, clamped content, fade,
+    /// toggle label and its Expand/Collapse captions
+    pub(crate) code_clamp: Class,
+    pub(crate) code_clamp_toggle: Class,
+    pub(crate) code_clamped: Class,
+    pub(crate) code_clamp_fade: Class,
+    pub(crate) code_clamp_button: Class,
+    pub(crate) code_clamp_expand: Class,
+    pub(crate) code_clamp_collapse: Class,
+    /// Lines picked by a `{1,4-6}` fence range; prose has no style for them
+    pub(crate) code_line_highlight: Class,
+    /// Added, removed and hunk header lines of ```` ```diff ```` blocks
+    pub(crate) code_diff_added: Class,
+    pub(crate) code_diff_removed: Class,
+    pub(crate) code_diff_hunk: Class,
+    /// Always includes `mermaid`, the class Mermaid.js looks for
+    pub(crate) mermaid: Class,
+    pub(crate) inline_code: Class,
+    pub(crate) ol: Class,
+    pub(crate) ul: Class,
+    pub(crate) li: Class,
+    pub(crate) em: Class,
+    pub(crate) strong: Class,
+    pub(crate) del: Class,
+    /// `==highlight==`; prose has no style for `<mark>`, so it keeps its utilities
+    pub(crate) mark: Class,
+    /// `++inserted++`; keeps its utilities under prose like `mark`
+    pub(crate) ins: Class,
+    /// Key caps; keep their utilities under prose like `mark`
+    pub(crate) kbd: Class,
+    /// `||spoiler||` label and the blurred text inside it; keep their utilities like `mark`
+    pub(crate) spoiler: Class,
+    pub(crate) spoiler_text: Class,
+    /// `__underline__` in chat content
+    pub(crate) underline: Class,
+    /// Custom emoji images from the emoji resolver
+    pub(crate) emoji: Class,
+    /// `None` renders links without a class attribute
+    pub(crate) link: Option<Class>,
+    pub(crate) image: Class,
+    /// Wikilinks whose target doesn't resolve, and `![[embed]]` placeholders. Prose has no
+    /// style for them, so they keep their utilities in both modes.
+    pub(crate) wikilink_unresolved: Class,
+    pub(crate) embed: Class,
+    pub(crate) table: Class,
+    pub(crate) thead: Class,
+    pub(crate) tr: Class,
+    pub(crate) th: Class,
+    pub(crate) td: Class,
+    pub(crate) hr: Class,
+    /// `None` renders task list checkboxes without a class attribute
+    pub(crate) checkbox: Option<Class>,
+    pub(crate) math_inline: Class,
+    pub(crate) math_display: Class,
+    pub(crate) footnote_ref: Class,
+    /// Footnote definition class, including the anchor target highlight when enabled
+    pub(crate) footnote_def: Class,
+    /// Footnotes `<section>` ending a rendered document
+    pub(crate) footnotes: Class,
+    pub(crate) footnote_list: Class,
+    pub(crate) footnote_backref: Class,
+    pub(crate) raw_html_block: Class,
+    pub(crate) dl: Class,
+    pub(crate) dt: Class,
+    pub(crate) dd: Class,
+    pub(crate) sup: Class,
+    pub(crate) sub: Class,
+    pub(crate) heading_anchor: Class,
+    /// Table of contents `<nav>`, its entries, their links and nested lists; prose has no
+    /// style for them, so they keep their utilities in both modes
+    pub(crate) toc: Class,
+    pub(crate) toc_item: Class,
+    pub(crate) toc_link: Class,
+    pub(crate) toc_nested: Class,
+    /// Added to headings and footnote definitions with `highlight_anchor_target`; keeps
+    /// its utilities in both modes
+    pub(crate) anchor_target: Class,
+    /// Ellipsis block closing a capped document
+    pub(crate) truncation: Class,
+    pub(crate) figure: Class,
+    pub(crate) figcaption: Class,
+    /// List of figures `<nav>`; its entries are styled like table of contents entries
+    pub(crate) lof: Class,
+    /// Container `<div>`, before the per-name class
+    pub(crate) container: Class,
+    /// `:::details` containers and their summary line
+    pub(crate) details: Class,
+    pub(crate) summary: Class,
+    /// Tab groups: wrapper, tab row, tab buttons and panels. Prose has no style for them,
+    /// so they keep their utilities in both modes.
+    pub(crate) tabs: Class,
+    pub(crate) tab_list: Class,
+    pub(crate) tab: Class,
+    pub(crate) tab_panel: Class,
+    pub(crate) citation: Class,
+    pub(crate) bibliography: Class,
+    /// Placeholder for unsupported content; keeps its utilities under prose, which has no
+    /// style for it
+    pub(crate) unsupported: Class,
+    /// Author mode: wrapper of an annotated block, its line/badge row and each badge.
+    /// Prose has no style for them, so they keep their utilities in both modes.
+    pub(crate) annotated_block: Class,
+    pub(crate) annotation: Class,
+    pub(crate) diagnostic: Class,
+    /// `render_diff` blocks and words removed or added; prose styles `<del>` / `<ins>` as
+    /// text only, so they keep their utilities in both modes
+    pub(crate) diff_added: Class,
+    pub(crate) diff_removed: Class,
+    pub(crate) diff_word_added: Class,
+    pub(crate) diff_word_removed: Class,
+}
+
+impl ClassBundle {
+    pub(crate) fn new(options: &MarkdownOptions) -> Self {
+        let explicit = options.use_explicit_classes;
+        let map = &options.class_map;
+        // Explicit mode: stable `md-*` hook followed by the utilities. Prose mode keeps the
+        // plain class, plus the utilities when the class map changed them.
+        let pick = |hook: &str, utilities: &str, default: &str, plain: &str| -> Class {
+            if explicit {
+                format!("{} {}", hook, utilities).into()
+            } else if utilities != default {
+                join_classes(plain, utilities).into()
+            } else {
+                plain.into()
+            }
+        };
+        // Elements prose doesn't style keep their utilities in both modes
+        let styled = |hook: &str, utilities: &str, plain: &str| -> Class {
+            if explicit {
+                format!("{} {}", hook, utilities).into()
+            } else {
+                join_classes(plain, utilities).into()
+            }
+        };
+
+        let headings = [
+            HeadingLevel::H1,
+            HeadingLevel::H2,
+            HeadingLevel::H3,
+            HeadingLevel::H4,
+            HeadingLevel::H5,
+            HeadingLevel::H6,
+        ]
+        .map(|level| {
+            let utilities = heading_class(map, level, options.compact_headings);
+            let changed = utilities != heading_class(&MarkdownClassMap::default(), level, false);
+            if explicit {
+                Some(format!("{} {}", heading_hook(level), utilities).into())
+            } else if options.compact_headings || changed {
+                Some(utilities.into())
+            } else {
+                None
+            }
+        });
+
+        // Alerts keep their `markdown-alert` classes next to the hook
+        let alert_hook = |hook: &str, class: String| -> Class {
+            if explicit {
+                format!("{} {}", hook, class).into()
+            } else {
+                class.into()
+            }
+        };
+        let alerts = AlertKind::ALL.map(|kind| {
+            alert_hook(
+                MarkdownClassHooks::ALERT,
+                format!(
+                    "markdown-alert markdown-alert-{} {} {}",
+                    kind.as_str(),
+                    map.alert,
+                    get_alert_classes(kind)
+                ),
+            )
+        });
+
+        let base_pre = pick(
+            MarkdownClassHooks::CODE_BLOCK,
+            &map.code_block,
+            MarkdownClasses::CODE_BLOCK,
+            "markdown-code-block",
+        );
+        let themed = |base: Class,
+                      classes: fn(&CodeBlockTheme) -> &'static str,
+                      dark_classes: fn(&CodeBlockTheme) -> &'static str|
+         -> Class {
+            match (&options.code_theme, &options.dark_code_theme) {
+                (Some(light), Some(dark)) => resolve(format!(
+                    "{} {}",
+                    base,
+                    paired_theme_classes(light, dark, classes, dark_classes)
+                ))
+                .into(),
+                (Some(theme), None) => resolve(format!("{} {}", base, classes(theme))).into(),
+                (None, _) => base,
+            }
+        };
+        let code_block = themed(
+            base_pre,
+            get_code_theme_classes,
+            get_dark_code_theme_classes,
+        );
+        let code_overflow = if options.code_wrap {
+            MarkdownClasses::CODE_WRAP
+        } else {
+            MarkdownClasses::CODE_SCROLL
+        };
+        let code_block: Class = format!("{} {}", code_block, code_overflow).into();
+        let code_title = themed(
+            styled(
+                MarkdownClassHooks::CODE_TITLE,
+                &map.code_title,
+                "code-title",
+            ),
+            get_code_title_theme_classes,
+            get_dark_code_title_theme_classes,
+        );
+
+        let code_copy_position = if options.code_expand_action {
+            MarkdownClasses::CODE_COPY_BESIDE_EXPAND
+        } else {
+            MarkdownClasses::CODE_COPY_ALONE
+        };
+        let code_copy_button: Class = format!(
+            "{} {}",
+            styled(
+                MarkdownClassHooks::CODE_COPY_BUTTON,
+                &map.code_copy_button,
+                "markdown-code-copy",
+            ),
+            code_copy_position
+        )
+        .into();
+
+        let preserve_lines = options.soft_break == SoftBreakMode::Newline;
+        let paragraph = pick(
+            MarkdownClassHooks::PARAGRAPH,
+            &map.paragraph,
+            MarkdownClasses::PARAGRAPH,
+            "",
+        );
+        let paragraph = match (paragraph.is_empty(), preserve_lines) {
+            (false, false) => Some(paragraph),
+            (false, true) => {
+                Some(format!("{} {}", paragraph, MarkdownClasses::PRESERVE_LINES).into())
+            }
+            (true, true) => Some(MarkdownClasses::PRESERVE_LINES.into()),
+            (true, false) => None,
+        };
+
+        let mut footnote_def = pick(
+            MarkdownClassHooks::FOOTNOTE_DEF,
+            &map.footnote_def,
+            MarkdownClasses::FOOTNOTE_DEF,
+            "footnote-definition",
+        );
+        let anchor_target = styled(MarkdownClassHooks::ANCHOR_TARGET, &map.anchor_target, "");
+        if options.highlight_anchor_target {
+            footnote_def = join_classes(&footnote_def, &anchor_target).into();
+        }
+
+        Self {
+            headings,
+            alerts,
+            alert_title: alert_hook(
+                MarkdownClassHooks::ALERT_TITLE,
+                format!("markdown-alert-title {}", map.alert_title),
+            ),
+            paragraph,
+            blockquote: pick(
+                MarkdownClassHooks::BLOCKQUOTE,
+                &map.blockquote,
+                MarkdownClasses::BLOCKQUOTE,
+                "markdown-blockquote",
+            ),
+            code_block,
+            code_title,
+            code_titled: styled(MarkdownClassHooks::CODE_TITLED, &map.code_titled, ""),
+            code_block_wrapper: styled(
+                MarkdownClassHooks::CODE_BLOCK_WRAPPER,
+                &map.code_block_wrapper,
+                "",
+            ),
+            code_copy_button,
+            code_expand_button: styled(
+                MarkdownClassHooks::CODE_EXPAND_BUTTON,
+                &map.code_expand_button,
+                "markdown-code-expand",
+            ),
+            code_modal: styled(
+                MarkdownClassHooks::CODE_MODAL,
+                &map.code_modal,
+                "markdown-code-modal",
+            ),
+            code_modal_close: styled(
+                MarkdownClassHooks::CODE_MODAL_CLOSE,
+                &map.code_modal_close,
+                "",
+            ),
+            code_modal_pre: styled(MarkdownClassHooks::CODE_MODAL_PRE, &map.code_modal_pre, ""),
+            code_block_code: pick(
+                MarkdownClassHooks::CODE_BLOCK_CODE,
+                &map.code_block_code,
+                MarkdownClasses::CODE_BLOCK_CODE,
+                "",
+            ),
+            code_clamp: styled(
+                MarkdownClassHooks::CODE_CLAMP,
+                &map.code_clamp,
+                "markdown-code-clamp",
+            ),
+            code_clamp_toggle: styled(
+                MarkdownClassHooks::CODE_CLAMP_TOGGLE,
+                &map.code_clamp_toggle,
+                "",
+            ),
+            code_clamped: styled(MarkdownClassHooks::CODE_CLAMPED, &map.code_clamped, ""),
+            code_clamp_fade: styled(
+                MarkdownClassHooks::CODE_CLAMP_FADE,
+                &map.code_clamp_fade,
+                "",
+            ),
+            code_clamp_button: styled(
+                MarkdownClassHooks::CODE_CLAMP_BUTTON,
+                &map.code_clamp_button,
+                "",
+            ),
+            code_clamp_expand: styled(
+                MarkdownClassHooks::CODE_CLAMP_EXPAND,
+                &map.code_clamp_expand,
+                "",
+            ),
+            code_clamp_collapse: styled(
+                MarkdownClassHooks::CODE_CLAMP_COLLAPSE,
+                &map.code_clamp_collapse,
+                "",
+            ),
+            code_line_highlight: styled(
+                MarkdownClassHooks::CODE_LINE_HIGHLIGHT,
+                &map.code_line_highlight,
+                "",
+            ),
+            code_diff_added: styled(
+                MarkdownClassHooks::CODE_DIFF_ADDED,
+                &map.code_diff_added,
+                "",
+            ),
+            code_diff_removed: styled(
+                MarkdownClassHooks::CODE_DIFF_REMOVED,
+                &map.code_diff_removed,
+                "",
+            ),
+            code_diff_hunk: styled(MarkdownClassHooks::CODE_DIFF_HUNK, &map.code_diff_hunk, ""),
+            mermaid: join_classes(
+                "mermaid",
+                &pick(
+                    MarkdownClassHooks::MERMAID,
+                    &map.mermaid,
+                    MarkdownClasses::MERMAID,
+                    "",
+                ),
+            )
+            .into(),
+            inline_code: pick(
+                MarkdownClassHooks::INLINE_CODE,
+                &map.inline_code,
+                MarkdownClasses::INLINE_CODE,
+                "inline-code",
+            ),
+            ol: pick(MarkdownClassHooks::OL, &map.ol, MarkdownClasses::OL, ""),
+            ul: pick(MarkdownClassHooks::UL, &map.ul, MarkdownClasses::UL, ""),
+            li: pick(MarkdownClassHooks::LI, &map.li, MarkdownClasses::LI, ""),
+            em: pick(MarkdownClassHooks::EM, &map.em, MarkdownClasses::EM, ""),
+            strong: pick(
+                MarkdownClassHooks::STRONG,
+                &map.strong,
+                MarkdownClasses::STRONG,
+                "",
+            ),
+            del: pick(MarkdownClassHooks::DEL, &map.del, MarkdownClasses::DEL, ""),
+            mark: styled(MarkdownClassHooks::MARK, &map.mark, "markdown-mark"),
+            ins: styled(MarkdownClassHooks::INS, &map.ins, "markdown-ins"),
+            kbd: styled(MarkdownClassHooks::KBD, &map.kbd, "markdown-kbd"),
+            spoiler: styled(
+                MarkdownClassHooks::SPOILER,
+                &map.spoiler,
+                "leptos-md-spoiler",
+            ),
+            spoiler_text: styled(MarkdownClassHooks::SPOILER_TEXT, &map.spoiler_text, ""),
+            underline: styled(MarkdownClassHooks::UNDERLINE, &map.underline, ""),
+            emoji: styled(MarkdownClassHooks::EMOJI, &map.emoji, ""),
+            link: non_empty(pick(
+                MarkdownClassHooks::LINK,
+                &map.link,
+                MarkdownClasses::LINK,
+                "",
+            )),
+            image: with_media_layout(
+                pick(
+                    MarkdownClassHooks::IMAGE,
+                    &map.image,
+                    MarkdownClasses::IMAGE,
+                    "markdown-image",
+                ),
+                options.image_layout.as_ref(),
+                // Images are inline; `mx-auto` only centers a block
+                "block mx-auto",
+            ),
+            wikilink_unresolved: styled(
+                MarkdownClassHooks::WIKILINK_UNRESOLVED,
+                &map.wikilink_unresolved,
+                "leptos-md-wikilink-unresolved",
+            ),
+            embed: styled(MarkdownClassHooks::EMBED, &map.embed, "leptos-md-embed"),
+            table: with_media_layout(
+                pick(
+                    MarkdownClassHooks::TABLE,
+                    &map.table,
+                    MarkdownClasses::TABLE,
+                    "markdown-table",
+                ),
+                options.table_layout.as_ref(),
+                "mx-auto",
+            ),
+            thead: pick(
+                MarkdownClassHooks::THEAD,
+                &map.thead,
+                MarkdownClasses::THEAD,
+                "",
+            ),
+            tr: pick(MarkdownClassHooks::TR, &map.tr, MarkdownClasses::TR, ""),
+            th: pick(MarkdownClassHooks::TH, &map.th, MarkdownClasses::TH, ""),
+            td: pick(MarkdownClassHooks::TD, &map.td, MarkdownClasses::TD, ""),
+            hr: pick(
+                MarkdownClassHooks::HR,
+                &map.hr,
+                MarkdownClasses::HR,
+                "markdown-hr",
+            ),
+            checkbox: non_empty(pick(
+                MarkdownClassHooks::CHECKBOX,
+                &map.checkbox,
+                MarkdownClasses::CHECKBOX,
+                "",
+            )),
+            math_inline: pick(
+                MarkdownClassHooks::MATH_INLINE,
+                &map.math_inline,
+                MarkdownClasses::MATH_INLINE,
+                "math math-inline",
+            ),
+            math_display: pick(
+                MarkdownClassHooks::MATH_DISPLAY,
+                &map.math_display,
+                MarkdownClasses::MATH_DISPLAY,
+                "math math-display",
+            ),
+            footnote_ref: pick(
+                MarkdownClassHooks::FOOTNOTE_REF,
+                &map.footnote_ref,
+                MarkdownClasses::FOOTNOTE_REF,
+                "footnote-ref",
+            ),
+            footnote_def,
+            footnotes: pick(
+                MarkdownClassHooks::FOOTNOTES,
+                &map.footnotes,
+                MarkdownClasses::FOOTNOTES,
+                "footnotes",
+            ),
+            footnote_list: pick(
+                MarkdownClassHooks::FOOTNOTE_LIST,
+                &map.footnote_list,
+                MarkdownClasses::FOOTNOTE_LIST,
+                "",
+            ),
+            footnote_backref: pick(
+                MarkdownClassHooks::FOOTNOTE_BACKREF,
+                &map.footnote_backref,
+                MarkdownClasses::FOOTNOTE_BACKREF,
+                "footnote-backref",
+            ),
+            raw_html_block: pick(
+                MarkdownClassHooks::RAW_HTML_BLOCK,
+                &map.raw_html_block,
+                MarkdownClasses::RAW_HTML_BLOCK,
+                "raw-html-block",
+            ),
+            dl: pick(MarkdownClassHooks::DL, &map.dl, MarkdownClasses::DL, ""),
+            dt: pick(MarkdownClassHooks::DT, &map.dt, MarkdownClasses::DT, ""),
+            dd: pick(MarkdownClassHooks::DD, &map.dd, MarkdownClasses::DD, ""),
+            sup: pick(MarkdownClassHooks::SUP, &map.sup, MarkdownClasses::SUP, ""),
+            sub: pick(MarkdownClassHooks::SUB, &map.sub, MarkdownClasses::SUB, ""),
+            heading_anchor: styled(MarkdownClassHooks::HEADING_ANCHOR, &map.heading_anchor, ""),
+            toc: styled(MarkdownClassHooks::TOC, &map.toc, ""),
+            toc_item: styled(MarkdownClassHooks::TOC_ITEM, &map.toc_item, ""),
+            toc_link: styled(MarkdownClassHooks::TOC_LINK, &map.toc_link, ""),
+            toc_nested: styled(MarkdownClassHooks::TOC_NESTED, &map.toc_nested, ""),
+            anchor_target,
+            truncation: pick(
+                MarkdownClassHooks::TRUNCATION,
+                &map.truncation,
+                MarkdownClasses::TRUNCATION,
+                "markdown-truncated",
+            ),
+            figure: pick(
+                MarkdownClassHooks::FIGURE,
+                &map.figure,
+                MarkdownClasses::FIGURE,
+                "markdown-figure",
+            ),
+            figcaption: pick(
+                MarkdownClassHooks::FIGCAPTION,
+                &map.figcaption,
+                MarkdownClasses::FIGCAPTION,
+                "",
+            ),
+            lof: styled(MarkdownClassHooks::LOF, &map.lof, ""),
+            container: pick(
+                MarkdownClassHooks::CONTAINER,
+                &map.container,
+                MarkdownClasses::CONTAINER,
+                "markdown-container",
+            ),
+            details: pick(
+                MarkdownClassHooks::DETAILS,
+                &map.details,
+                MarkdownClasses::DETAILS,
+                "markdown-details",
+            ),
+            summary: pick(
+                MarkdownClassHooks::SUMMARY,
+                &map.summary,
+                MarkdownClasses::SUMMARY,
+                "markdown-summary",
+            ),
+            tabs: styled(MarkdownClassHooks::TABS, &map.tabs, "markdown-tabs"),
+            tab_list: styled(MarkdownClassHooks::TAB_LIST, &map.tab_list, ""),
+            tab: styled(MarkdownClassHooks::TAB, &map.tab, ""),
+            tab_panel: styled(MarkdownClassHooks::TAB_PANEL, &map.tab_panel, ""),
+            citation: pick(
+                MarkdownClassHooks::CITATION,
+                &map.citation,
+                MarkdownClasses::CITATION,
+                "citation",
+            ),
+            bibliography: pick(
+                MarkdownClassHooks::BIBLIOGRAPHY,
+                &map.bibliography,
+                MarkdownClasses::BIBLIOGRAPHY,
+                "references",
+            ),
+            unsupported: styled(
+                MarkdownClassHooks::UNSUPPORTED,
+                &map.unsupported,
+                "markdown-unsupported",
+            ),
+            annotated_block: styled(
+                MarkdownClassHooks::ANNOTATED_BLOCK,
+                &map.annotated_block,
+                "markdown-annotated",
+            ),
+            annotation: styled(
+                MarkdownClassHooks::ANNOTATION,
+                &map.annotation,
+                "markdown-annotation",
+            ),
+            diagnostic: styled(
+                MarkdownClassHooks::DIAGNOSTIC,
+                &map.diagnostic,
+                "markdown-diagnostic",
+            ),
+            diff_added: styled(MarkdownClassHooks::DIFF_ADDED, &map.diff_added, ""),
+            diff_removed: styled(MarkdownClassHooks::DIFF_REMOVED, &map.diff_removed, ""),
+            diff_word_added: styled(
+                MarkdownClassHooks::DIFF_WORD_ADDED,
+                &map.diff_word_added,
+                "",
+            ),
+            diff_word_removed: styled(
+                MarkdownClassHooks::DIFF_WORD_REMOVED,
+                &map.diff_word_removed,
+                "",
+            ),
+        }
+    }
+
+    /// Built-in heading class, or `None` when the heading renders without one
+    pub(crate) fn heading(&self, level: HeadingLevel) -> Option<&Class> {
+        self.headings[level as usize - 1].as_ref()
+    }
+
+    pub(crate) fn alert(&self, kind: AlertKind) -> &Class {
+        &self.alerts[kind as usize]
+    }
+}
+
+/// Stable hook for a heading, always reflecting the semantic level
+fn heading_hook(level: HeadingLevel) -> &'static str {
+    match level {
+        HeadingLevel::H1 => MarkdownClassHooks::H1,
+        HeadingLevel::H2 => MarkdownClassHooks::H2,
+        HeadingLevel::H3 => MarkdownClassHooks::H3,
+        HeadingLevel::H4 => MarkdownClassHooks::H4,
+        HeadingLevel::H5 => MarkdownClassHooks::H5,
+        HeadingLevel::H6 => MarkdownClassHooks::H6,
+    }
+}
+
+/// Heading classes from `map`, shifted one size step down in compact mode
+fn heading_class(map: &MarkdownClassMap, level: HeadingLevel, compact: bool) -> &str {
+    let level = if compact {
+        match level {
+            HeadingLevel::H1 => HeadingLevel::H2,
+            HeadingLevel::H2 => HeadingLevel::H3,
+            HeadingLevel::H3 => HeadingLevel::H4,
+            HeadingLevel::H4 => HeadingLevel::H5,
+            HeadingLevel::H5 | HeadingLevel::H6 => HeadingLevel::H6,
+        }
+    } else {
+        level
+    };
+
+    match level {
+        HeadingLevel::H1 => &map.h1,
+        HeadingLevel::H2 => &map.h2,
+        HeadingLevel::H3 => &map.h3,
+        HeadingLevel::H4 => &map.h4,
+        HeadingLevel::H5 => &map.h5,
+        HeadingLevel::H6 => &map.h6,
+    }
+}
+
+/// `class`, or `None` so the element renders without an empty class attribute
+fn non_empty(class: Class) -> Option<Class> {
+    (!class.is_empty()).then_some(class)
+}
+
+/// `base` and `extra` separated by a space, either may be empty
+fn join_classes(base: &str, extra: &str) -> String {
+    match (base.is_empty(), extra.is_empty()) {
+        (true, _) => extra.to_string(),
+        (false, true) => base.to_string(),
+        (false, false) => resolve(format!("{} {}", base, extra)),
+    }
+}
+
+/// `light`'s plain utilities for light mode followed by `dark`'s literal `dark:` classes
+fn paired_theme_classes(
+    light: &CodeBlockTheme,
+    dark: &CodeBlockTheme,
+    classes: fn(&CodeBlockTheme) -> &'static str,
+    dark_classes: fn(&CodeBlockTheme) -> &'static str,
+) -> String {
+    let mut paired: Vec<&str> = classes(light)
+        .split_whitespace()
+        .filter(|class| !class.starts_with("dark:"))
+        .collect();
+    paired.push(dark_classes(dark));
+    paired.join(" ")
+}
+
+/// `class` followed by the layout's max-width utility and `centering` classes
+fn with_media_layout(class: Class, layout: Option<&MediaLayout>, centering: &str) -> Class {
+    let Some(layout) = layout else {
+        return class;
+    };
+    let mut combined = class.to_string();
+    for extra in [
+        layout.max_width.as_deref(),
+        layout.centered.then_some(centering),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if !combined.is_empty() {
+            combined.push(' ');
+        }
+        combined.push_str(extra);
+    }
+    combined.into()
+}
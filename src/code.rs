@@ -0,0 +1,30 @@
+use crate::compat::*;
+use crate::components::{CodeBlockTheme, MarkdownOptions};
+use crate::renderer::MarkdownRenderer;
+
+/// A single highlighted snippet outside a markdown document, themed like the code blocks
+/// of `<Markdown>`.
+///
+/// Without `options` the block carries its own Tailwind utilities, since there is no
+/// prose wrapper to style it; pass the document's options to match its code blocks.
+#[component]
+pub fn CodeBlock(
+    /// The code to show, verbatim
+    #[prop(into)]
+    code: String,
+    /// Language used for highlighting and the `language-xxx` class
+    #[prop(optional, into)]
+    lang: Option<String>,
+    /// Code block theme; takes precedence over `options.code_theme`
+    #[prop(optional)]
+    theme: Option<CodeBlockTheme>,
+    /// Markdown rendering options
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+) -> impl IntoView {
+    let mut options = options.unwrap_or_else(|| MarkdownOptions::new().with_explicit_classes(true));
+    if let Some(theme) = theme {
+        options.code_theme = Some(theme);
+    }
+    MarkdownRenderer::new(options).render_code(&code, lang.as_deref().unwrap_or(""))
+}
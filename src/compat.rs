@@ -0,0 +1,22 @@
+//! Leptos version compatibility layer.
+//!
+//! Every module renders and logs through these re-exports instead of importing `leptos`
+//! directly, so moving to another Leptos release only means changing this file. The
+//! crate currently targets Leptos 0.8.
+
+pub(crate) use leptos::prelude::*;
+
+/// Console error logging on the client, stderr on the server
+pub(crate) use leptos::logging::error as log_error;
+
+/// Server-side HTML rendering of views
+#[cfg(feature = "ssr")]
+pub(crate) use leptos::tachys::view::RenderHtml;
+
+/// Client-side navigation links for route directives
+#[cfg(feature = "router")]
+pub(crate) use leptos_router::components::A;
+
+/// Typed element handles for node refs
+#[cfg(feature = "editor")]
+pub(crate) use leptos::html;
@@ -1,6 +1,24 @@
-use leptos::prelude::*;
+use crate::cache::RenderCache;
+use crate::callback::{
+    AnchorHrefResolver, BlockFilter, CitationResolver, EmojiResolver, EventTransform,
+    FrontmatterHandler, HtmlCommentHandler, ImageDimensions, ImageSrcTransform, LinkRewriter,
+    MentionResolver, ReferenceLinker, SanitizeHandler, WikilinkResolver,
+};
+use crate::citations::Citation;
+use crate::compat::*;
+use crate::filter::{BlockAction, BlockContext};
+use crate::frontmatter::Frontmatter;
+use crate::mentions::MentionTarget;
+use crate::overrides::MarkdownComponents;
+use crate::references::RepoReference;
+use crate::routes::RouteMap;
+use crate::warnings::SanitizeEvent;
+use pulldown_cmark::Event;
+use std::borrow::Cow;
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CodeBlockTheme {
     #[default]
     Default,
@@ -10,94 +28,1626 @@ pub enum CodeBlockTheme {
     Monokai,
 }
 
+/// Preset bundles of options that mirror how a known Markdown host renders content
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkdownFlavor {
+    /// Approximates GitHub's rendering of a repository README
+    GitHub,
+    /// Obsidian vaults: wikilinks, `![[embed]]` placeholders, callouts and highlights
+    Obsidian,
+    /// Chat messages as Discord and Slack show them: newlines kept, `||spoilers||` and
+    /// `__underline__`
+    Chat,
+}
+
+/// Kind of a styled callout ("alert"), matching GitHub's alert types
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AlertKind {
+    /// Every kind, in declaration order
+    pub const ALL: [AlertKind; 5] = [
+        AlertKind::Note,
+        AlertKind::Tip,
+        AlertKind::Important,
+        AlertKind::Warning,
+        AlertKind::Caution,
+    ];
+
+    /// Default title shown in the alert header
+    pub fn title(&self) -> &'static str {
+        match self {
+            AlertKind::Note => "Note",
+            AlertKind::Tip => "Tip",
+            AlertKind::Important => "Important",
+            AlertKind::Warning => "Warning",
+            AlertKind::Caution => "Caution",
+        }
+    }
+
+    /// Decorative icon shown before the title
+    pub fn icon(&self) -> &'static str {
+        match self {
+            AlertKind::Note => "ℹ️",
+            AlertKind::Tip => "💡",
+            AlertKind::Important => "❗",
+            AlertKind::Warning => "⚠️",
+            AlertKind::Caution => "⛔",
+        }
+    }
+
+    /// Lowercase identifier used in class names (`markdown-alert-note`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertKind::Note => "note",
+            AlertKind::Tip => "tip",
+            AlertKind::Important => "important",
+            AlertKind::Warning => "warning",
+            AlertKind::Caution => "caution",
+        }
+    }
+
+    /// Match a callout label such as `Note`, `warning` or `Danger` (case-insensitive)
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "note" | "info" => Some(AlertKind::Note),
+            "tip" | "hint" => Some(AlertKind::Tip),
+            "important" => Some(AlertKind::Important),
+            "warning" | "warn" => Some(AlertKind::Warning),
+            "caution" | "danger" => Some(AlertKind::Caution),
+            _ => None,
+        }
+    }
+}
+
+/// Unique class added to the wrapper so CSS can target a single markdown instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScopeClass {
+    /// `md-scope-<hash>` derived from the content, stable between SSR and hydration.
+    /// Instances showing the same content share the class; use `Named` to tell them apart.
+    ContentHash,
+    /// `md-scope-<name>` with a caller-chosen name
+    Named(String),
+}
+
+/// Element wrapping the output of `<Markdown>`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarkdownWrapper {
+    #[default]
+    Div,
+    Article,
+    Section,
+    /// No wrapper: the rendered blocks go straight into the parent, without the wrapper
+    /// classes or attributes
+    Fragment,
+}
+
+/// Arrangement of `<MarkdownEditor>`'s textarea and preview
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditorLayout {
+    /// Textarea and preview side by side, scrolling together
+    #[default]
+    Split,
+    /// One at a time, switched with Write / Preview tabs
+    Tabs,
+}
+
+/// Element used for `~~strikethrough~~`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrikethroughElement {
+    /// `<del>`: content removed by an edit
+    #[default]
+    Del,
+    /// `<s>`: content no longer accurate or relevant (e.g. a crossed-out price)
+    S,
+}
+
+/// How soft line breaks (a single newline inside a paragraph) are rendered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SoftBreakMode {
+    /// A space, joining the lines as CommonMark specifies
+    #[default]
+    Space,
+    /// A newline kept visible by `whitespace-pre-line` on paragraphs, for poetry,
+    /// lyrics and addresses
+    Newline,
+    /// A `<br>`, as chat apps render a single newline
+    LineBreak,
+}
+
+/// How top-level `# H1` headings are treated, for documents embedded in a page that
+/// already has its own `<h1>`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum H1Policy {
+    /// Render H1 headings as written
+    #[default]
+    Keep,
+    /// Render every H1 as an H2
+    Demote,
+    /// Drop H1 headings whose text matches this page title; demote any other H1
+    StripTitle(String),
+}
+
+/// Width cap and alignment of images or tables within the prose column, so media
+/// doesn't run full-bleed in wide layouts
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediaLayout {
+    /// Tailwind max-width utility, e.g. `max-w-2xl`
+    pub max_width: Option<String>,
+    /// Center the element horizontally
+    pub centered: bool,
+}
+
+impl MediaLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap the width with a Tailwind utility such as `max-w-2xl`
+    #[must_use]
+    pub fn with_max_width(mut self, class: impl Into<String>) -> Self {
+        self.max_width = Some(class.into());
+        self
+    }
+
+    /// Configure horizontal centering
+    #[must_use]
+    pub fn with_centered(mut self, centered: bool) -> Self {
+        self.centered = centered;
+        self
+    }
+}
+
+/// Where an image is loaded from, as returned by an image src transform
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageSource {
+    pub src: String,
+    /// Responsive candidates, e.g. `"/cdn/w_480/a.png 480w, /cdn/w_960/a.png 960w"`
+    pub srcset: Option<String>,
+    /// Slot widths for `srcset`, e.g. `"(min-width: 768px) 50vw, 100vw"`
+    pub sizes: Option<String>,
+}
+
+impl ImageSource {
+    pub fn new(src: impl Into<String>) -> Self {
+        Self {
+            src: src.into(),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_srcset(mut self, srcset: impl Into<String>) -> Self {
+        self.srcset = Some(srcset.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_sizes(mut self, sizes: impl Into<String>) -> Self {
+        self.sizes = Some(sizes.into());
+        self
+    }
+}
+
+impl From<String> for ImageSource {
+    fn from(src: String) -> Self {
+        Self::new(src)
+    }
+}
+
+/// Which links open in a new tab (`target="_blank" rel="noopener noreferrer"`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LinkTargetPolicy {
+    /// Every link
+    Always,
+    /// Only absolute `http(s)` links to another host than `site_origin`
+    /// (e.g. `https://example.com`); relative `/docs/...` links stay in the tab
+    ExternalOnly { site_origin: String },
+    /// No link
+    Never,
+}
+
+impl LinkTargetPolicy {
+    /// Whether a link to `href` opens in a new tab under this policy
+    pub fn opens_in_new_tab(&self, href: &str) -> bool {
+        match self {
+            LinkTargetPolicy::Always => true,
+            LinkTargetPolicy::Never => false,
+            LinkTargetPolicy::ExternalOnly { site_origin } => link_host(href).is_some_and(|host| {
+                let site = link_host(site_origin).unwrap_or(site_origin);
+                !host.eq_ignore_ascii_case(site.trim_end_matches('/'))
+            }),
+        }
+    }
+}
+
+/// Host (with port) of an absolute `http(s)://` or protocol-relative `//` URL
+fn link_host(url: &str) -> Option<&str> {
+    let lower = url.get(..8).unwrap_or(url).to_ascii_lowercase();
+    let rest = if lower.starts_with("https://") {
+        &url[8..]
+    } else if lower.starts_with("http://") {
+        &url[7..]
+    } else {
+        url.strip_prefix("//")?
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let host = &rest[..end];
+    // Drop any `user:password@`
+    Some(host.rsplit('@').next().unwrap_or(host))
+}
+
+/// Which link and image URLs written in the markdown are rendered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UrlPolicy {
+    /// Only `http(s):`, `mailto:` and relative URLs; links to any other scheme
+    /// (`javascript:`, `data:`, ...) render as their text and images as their alt text
+    SafeSchemes,
+    /// Every URL as written. Only for trusted content.
+    #[default]
+    Allow,
+}
+
+impl UrlPolicy {
+    /// Whether `url` may be rendered under this policy
+    pub fn allows(&self, url: &str) -> bool {
+        match self {
+            UrlPolicy::Allow => true,
+            UrlPolicy::SafeSchemes => match url_scheme(url) {
+                Some(scheme) => ["http", "https", "mailto"]
+                    .iter()
+                    .any(|safe| scheme.eq_ignore_ascii_case(safe)),
+                None => true,
+            },
+        }
+    }
+}
+
+/// Scheme of `url`, read the way browsers do: ignoring surrounding whitespace and any
+/// tabs, newlines or control characters inside it. `None` for relative URLs.
+fn url_scheme(url: &str) -> Option<String> {
+    let cleaned: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_control() && *c != ' ')
+        .collect();
+    let end = cleaned.find(':')?;
+    let scheme = &cleaned[..end];
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| scheme.to_string())
+}
+
+/// What happens to raw HTML written in the markdown
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RawHtmlPolicy {
+    /// Leave it out of the output
+    Deny,
+    /// Show it as text
+    Escape,
+    /// Render it after stripping scripts, event handlers and other unsafe markup, keeping
+    /// safe tags like `<sup>` and `<kbd>`. Needs the `ammonia` feature; escapes without it.
+    Sanitize,
+    /// Render it as written. Only for trusted content.
+    #[default]
+    Allow,
+}
+
+/// Content the renderer deliberately doesn't render: math with `enable_math` off, raw
+/// HTML under [`RawHtmlPolicy::Deny`] and `[:route:]` directives naming unknown routes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UnsupportedKind {
+    Math,
+    Embed,
+    Directive,
+}
+
+impl UnsupportedKind {
+    /// Short label shown by the default placeholder
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnsupportedKind::Math => "Math not rendered",
+            UnsupportedKind::Embed => "Embedded HTML blocked",
+            UnsupportedKind::Directive => "Unknown directive",
+        }
+    }
+
+    /// Lowercase identifier used in `data-unsupported` attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnsupportedKind::Math => "math",
+            UnsupportedKind::Embed => "embed",
+            UnsupportedKind::Directive => "directive",
+        }
+    }
+}
+
+/// What is shown where unsupported content would have rendered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnsupportedContent {
+    /// Math and unknown directives stay as their source text; blocked HTML is left out
+    #[default]
+    Fallback,
+    /// A small labelled placeholder (customizable with
+    /// [`MarkdownComponents::with_placeholder`]), so authors notice the missing output
+    /// while previewing
+    Placeholder,
+    /// Leave it out of the output
+    Hide,
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct MarkdownOptions {
+    /// GitHub Flavored Markdown. When `false`, the tables, footnotes, strikethrough and
+    /// task list flags below are ignored; `with_gfm` toggles them as a group.
     pub enable_gfm: bool,
+    /// GFM tables (`| a | b |`).
+    pub enable_tables: bool,
+    /// Footnotes (`[^1]`).
+    pub enable_footnotes: bool,
+    /// Strikethrough (`~~text~~`).
+    pub enable_strikethrough: bool,
+    /// Task list markers (`- [x]`).
+    pub enable_tasklists: bool,
+    /// Math (`$inline$`, `$$display$$`).
+    pub enable_math: bool,
+    /// Definition lists (`Term` followed by `: definition`).
+    pub enable_definition_lists: bool,
+    /// Superscript (`^text^`).
+    pub enable_superscript: bool,
+    /// Subscript (`~text~`). Takes precedence over single-tilde strikethrough.
+    pub enable_subscript: bool,
+    /// Heading attributes (`# Title {#id .class}`), applied to the heading element.
+    pub enable_heading_attributes: bool,
+    /// Leading YAML (`---`) or TOML (`+++`) frontmatter is parsed as metadata instead of
+    /// rendered; it reaches `on_frontmatter` rather than the output. Off by default, so a
+    /// leading `---` stays a thematic break.
+    pub enable_metadata_blocks: bool,
     /// Code block theme. `Some(theme)` applies Tailwind styling, `None` outputs no theme classes.
     pub code_theme: Option<CodeBlockTheme>,
+    /// Code block theme under Tailwind's `dark` class; `code_theme` then only styles
+    /// light mode. Both are emitted and toggled with `dark:` utilities, no re-render.
+    pub dark_code_theme: Option<CodeBlockTheme>,
     /// Whether to emit `language-xxx` classes on code blocks (for external syntax highlighters).
     pub syntax_highlighting_language_classes: bool,
+    /// Tokenize fenced code blocks with syntect into classed `<span>`s.
+    /// Only takes effect with the `syntect` feature.
+    pub syntect_highlighting: bool,
     pub open_links_in_new_tab: bool,
+    /// Which links open in a new tab; overrides `open_links_in_new_tab` when set.
+    pub link_target: Option<LinkTargetPolicy>,
+    /// `rel` emitted on every link (e.g. `nofollow ugc`). `None` adds
+    /// `noopener noreferrer` to new-tab links only; an empty string emits none.
+    pub link_rel: Option<String>,
+    /// Rewrites each link destination (relative paths, UTM parameters, wiki slugs)
+    /// before anchors are resolved and the link is rendered.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub link_rewriter: Option<LinkRewriter>,
+    /// Rewrites each image src (e.g. to a resizing CDN), optionally adding `srcset`
+    /// and `sizes`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub image_src_transform: Option<ImageSrcTransform>,
     pub allow_raw_html: bool,
+    /// How raw HTML is handled; overrides `allow_raw_html` when set.
+    pub raw_html: Option<RawHtmlPolicy>,
+    /// Which link and image URLs are rendered; when unset, `SafeSchemes` under the
+    /// `Sanitize` and `Deny` raw HTML policies and `Allow` otherwise.
+    pub url_policy: Option<UrlPolicy>,
     /// Use explicit Tailwind utility classes on each element instead of relying on prose.
     /// When `false` (default), relies on Tailwind's `prose` classes for styling.
     /// When `true`, applies `MarkdownClasses::*` constants directly to elements.
     pub use_explicit_classes: bool,
+    /// Utility classes per element, used in place of the `MarkdownClasses` constants.
+    pub class_map: MarkdownClassMap,
+    /// Preset supplying the wrapper classes.
+    pub theme: MarkdownTheme,
+    /// Wrapper classes used instead of the theme's, e.g. to leave out `prose`.
+    pub base_class: Option<String>,
+    /// Drop `<!-- ... -->` comments from the output regardless of `allow_raw_html`.
+    pub strip_html_comments: bool,
+    /// Optional handler receiving each dropped HTML comment (e.g. for editor diagnostics).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_html_comment: Option<HtmlCommentHandler>,
+    /// Audit handler receiving each removed comment and each escaped, removed or sanitized
+    /// raw HTML fragment with its source offset (e.g. for trust-and-safety logs).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_sanitize: Option<SanitizeHandler>,
+    /// Called with the frontmatter of each rendered document.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub on_frontmatter: Option<FrontmatterHandler>,
+    /// Minimum length of an unbroken token (URL, hash, ...) that gets `<wbr>` break
+    /// opportunities and `overflow-wrap: anywhere` on the wrapper. `None` disables it.
+    pub long_token_breaks: Option<usize>,
+    /// Render each heading one size step smaller (H1 at H2 size, ...) while keeping the
+    /// semantic tag, for cards and sidebars. Applies the shifted `MarkdownClasses::H*`.
+    pub compact_headings: bool,
+    /// Max width and centering of images.
+    pub image_layout: Option<MediaLayout>,
+    /// `loading="lazy"` and `decoding="async"` on every image, so long documents don't
+    /// fetch images far below the fold up front.
+    pub lazy_images: bool,
+    /// Intrinsic image sizes emitted as `width`/`height`, reserving space before the
+    /// image loads.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub image_dimensions: Option<ImageDimensions>,
+    /// Max width and centering of tables.
+    pub table_layout: Option<MediaLayout>,
+    /// Route prepended to in-document anchor hrefs (`/docs/page` yields `/docs/page#id`),
+    /// for apps using `<base href>` or client-side routers.
+    pub anchor_base: Option<String>,
+    /// Optional resolver building anchor hrefs; takes precedence over `anchor_base`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub anchor_href_resolver: Option<AnchorHrefResolver>,
+    /// Highlight the element targeted by the URL hash and scroll it into view after mount
+    /// and after each content change.
+    pub highlight_anchor_target: bool,
+    /// Treatment of `# H1` headings in embedded contexts.
+    pub h1_policy: H1Policy,
+    /// Render legacy callouts (blockquotes starting with `**Note:**`, `**Warning:**`, ...)
+    /// as styled alerts.
+    pub legacy_alerts: bool,
+    /// Render GFM alerts (`> [!NOTE]`, `> [!WARNING]`, ...) as styled callouts.
+    pub gfm_alerts: bool,
+    /// Render Obsidian callouts (`> [!tip] Custom title`, any callout type) as alerts.
+    pub callouts: bool,
+    /// Unique `md-scope-*` class appended to the wrapper for per-instance CSS overrides.
+    pub scope_class: Option<ScopeClass>,
+    /// Element used for `~~strikethrough~~` (`<del>` or `<s>`).
+    pub strikethrough_element: StrikethroughElement,
+    /// Visually hidden text announced by screen readers before struck text (e.g. `"deleted:"`).
+    pub strikethrough_announcement: Option<String>,
+    /// Keep text rewrite passes (long-token breaks, autolinking, mentions, shortcodes) out
+    /// of existing links. Code spans are never rewritten.
+    pub protect_links_from_rewrites: bool,
+    /// Give headings GitHub-style slug ids (`intro`, `intro-1`, ...) for deep links.
+    pub heading_ids: bool,
+    /// Render a hover-visible `#` permalink inside headings that have an id.
+    pub heading_anchors: bool,
+    /// Backend storing HTML produced by `MarkdownRenderer::render_to_html`, keyed by
+    /// content and options.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub render_cache: Option<RenderCache>,
+    /// Prefix for cache keys. Keys cover the content, the crate version and the
+    /// options' `Debug` output, which doesn't capture what callbacks (link rewriters,
+    /// resolvers, components) do; give renderers differing only in callbacks their own
+    /// namespace, and change it to invalidate entries after changing a callback.
+    pub cache_namespace: Option<String>,
+    /// Render at most this many top-level blocks, ending with an ellipsis (chat bubbles).
+    pub max_blocks: Option<usize>,
+    /// Stop adding top-level blocks once their text exceeds this many characters.
+    pub max_chars: Option<usize>,
+    /// Refuse to render input longer than this many bytes.
+    pub max_input_len: Option<usize>,
+    /// Refuse to render documents whose elements nest deeper than this.
+    pub max_nesting_depth: Option<usize>,
+    /// Custom views replacing the built-in rendering of individual elements.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub components: MarkdownComponents,
+    /// Rendering of soft line breaks inside paragraphs.
+    pub soft_break: SoftBreakMode,
+    /// Add a button to code blocks opening the code in a full-screen modal.
+    pub code_expand_action: bool,
+    /// Add a copy-to-clipboard button to code blocks.
+    pub code_copy_button: bool,
+    /// Color added and removed lines of ```` ```diff ```` blocks green and red.
+    pub diff_highlighting: bool,
+    /// Clamp code blocks longer than this many lines behind an "Expand" toggle.
+    pub code_max_lines: Option<usize>,
+    /// Wrap long code lines instead of scrolling horizontally.
+    pub code_wrap: bool,
+    /// Width of a tab character in code blocks, in spaces.
+    pub code_tab_size: u8,
+    /// Transforms run in order on the parsed events before rendering.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub event_transforms: Vec<EventTransform>,
+    /// Render titled images standing alone in a paragraph as numbered `<figure>`s.
+    pub figures: bool,
+    /// Render fenced code with a `caption="..."` attribute as numbered listings.
+    pub captioned_listings: bool,
+    /// Resolves Pandoc-style `[@key]` citations; `None` leaves them as text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub citation_resolver: Option<CitationResolver>,
+    /// Named routes that `[:route:name param=value]` directives link to. Empty disables
+    /// the directive.
+    pub routes: RouteMap,
+    /// Inline footnotes (`^[note text]`), numbered with the regular footnotes.
+    pub inline_footnotes: bool,
+    /// Emit ```` ```mermaid ```` blocks as `<pre class="mermaid">` for Mermaid.js to draw.
+    pub mermaid_diagrams: bool,
+    /// Decides per heading section whether it is kept, redacted or dropped.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub block_filter: Option<BlockFilter>,
+    /// Wrap `:::name` ... `:::` fenced containers in a `<div>` named after them.
+    pub containers: bool,
+    /// Render `:::note`, `:::tip`, `:::warning`, `:::danger` (and the other alert names)
+    /// containers as alerts, titled by the rest of the marker line.
+    pub admonitions: bool,
+    /// Render `:::tabs` containers split at `== Title` lines, and consecutive code fences
+    /// with a `tab=` attribute, as tab groups.
+    pub tabs: bool,
+    /// Render `:::details Summary` containers as collapsible `<details>` blocks.
+    pub details: bool,
+    /// Containers (by name) whose headings are left out of the table of contents.
+    pub toc_excluded_containers: Vec<String>,
+    /// Rendering of math with math disabled, blocked HTML and unknown directives.
+    pub unsupported: UnsupportedContent,
+    /// Replace `:shortcode:`s with emoji. The gemoji table needs the `emoji` feature;
+    /// without it only `emoji_resolver` shortcodes are replaced.
+    pub emoji_shortcodes: bool,
+    /// Custom emoji images by shortcode, checked before the gemoji table.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub emoji_resolver: Option<EmojiResolver>,
+    /// Links `@name` mentions in text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub mention_resolver: Option<MentionResolver>,
+    /// Links `#topic` hashtags in text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hashtag_resolver: Option<MentionResolver>,
+    /// Links `#123`, `owner/repo#123` and commit SHA references in text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub reference_linker: Option<ReferenceLinker>,
+    /// Parse only inline constructs, without block wrappers such as `<p>`.
+    pub inline_only: bool,
+    /// Link bare `https://`/`www.` URLs and e-mail addresses in text (GFM autolinks).
+    pub autolinks: bool,
+    /// Parse `[[Page]]` and `[[page|label]]` wikilinks.
+    pub wikilinks: bool,
+    /// Href of a wikilink target; `None` renders the link as unresolved.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub wikilink_resolver: Option<WikilinkResolver>,
+    /// Render `==highlighted==` text as `<mark>`.
+    pub highlight: bool,
+    /// Render `||spoiler||` text hidden until clicked.
+    pub spoilers: bool,
+    /// Render `__text__` as `<u>` rather than strong emphasis.
+    pub underline: bool,
+    /// Parse `#` and setext headings; disabled, heading lines stay paragraphs.
+    pub enable_headings: bool,
+    /// Render `++inserted++` text as `<ins>`.
+    pub insertions: bool,
+    /// Render `` `kbd:Ctrl+C` `` code spans and `[[Key]]`s as `<kbd>` key caps; with
+    /// `wikilinks` on, `[[Key]]` is a wikilink instead.
+    pub kbd: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            enable_gfm: true,
+            enable_tables: true,
+            enable_footnotes: true,
+            enable_strikethrough: true,
+            enable_tasklists: true,
+            enable_math: false,
+            enable_definition_lists: false,
+            enable_superscript: false,
+            enable_subscript: false,
+            enable_heading_attributes: false,
+            enable_metadata_blocks: false,
+            code_theme: Some(CodeBlockTheme::default()),
+            dark_code_theme: None,
+            syntax_highlighting_language_classes: true,
+            syntect_highlighting: true,
+            open_links_in_new_tab: true,
+            link_target: None,
+            link_rel: None,
+            link_rewriter: None,
+            image_src_transform: None,
+            allow_raw_html: true,
+            raw_html: None,
+            url_policy: None,
+            use_explicit_classes: false,
+            class_map: MarkdownClassMap::default(),
+            theme: MarkdownTheme::default(),
+            base_class: None,
+            strip_html_comments: true,
+            on_html_comment: None,
+            on_sanitize: None,
+            on_frontmatter: None,
+            long_token_breaks: None,
+            compact_headings: false,
+            image_layout: None,
+            lazy_images: false,
+            image_dimensions: None,
+            table_layout: None,
+            anchor_base: None,
+            anchor_href_resolver: None,
+            highlight_anchor_target: false,
+            h1_policy: H1Policy::Keep,
+            legacy_alerts: false,
+            gfm_alerts: true,
+            callouts: false,
+            scope_class: None,
+            strikethrough_element: StrikethroughElement::Del,
+            strikethrough_announcement: None,
+            protect_links_from_rewrites: true,
+            heading_ids: false,
+            heading_anchors: false,
+            render_cache: None,
+            cache_namespace: None,
+            max_blocks: None,
+            max_chars: None,
+            max_input_len: None,
+            max_nesting_depth: None,
+            components: MarkdownComponents::default(),
+            soft_break: SoftBreakMode::Space,
+            code_expand_action: false,
+            code_copy_button: false,
+            diff_highlighting: false,
+            code_max_lines: None,
+            code_wrap: false,
+            code_tab_size: 4,
+            event_transforms: Vec::new(),
+            figures: false,
+            captioned_listings: false,
+            citation_resolver: None,
+            routes: RouteMap::new(),
+            inline_footnotes: false,
+            mermaid_diagrams: false,
+            block_filter: None,
+            containers: false,
+            admonitions: false,
+            tabs: false,
+            details: false,
+            toc_excluded_containers: Vec::new(),
+            unsupported: UnsupportedContent::Fallback,
+            inline_only: false,
+            autolinks: false,
+            wikilinks: false,
+            wikilink_resolver: None,
+            highlight: false,
+            spoilers: false,
+            underline: false,
+            enable_headings: true,
+            insertions: false,
+            kbd: false,
+            emoji_shortcodes: false,
+            emoji_resolver: None,
+            mention_resolver: None,
+            hashtag_resolver: None,
+            reference_linker: None,
+        }
+    }
 }
 
-impl Default for MarkdownOptions {
-    fn default() -> Self {
-        Self {
-            enable_gfm: true,
-            code_theme: Some(CodeBlockTheme::default()),
-            syntax_highlighting_language_classes: true,
-            open_links_in_new_tab: true,
-            allow_raw_html: true,
-            use_explicit_classes: false,
-        }
+impl MarkdownOptions {
+    /// Create a new MarkdownOptions with default values
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create options preset for the given flavor
+    #[must_use]
+    pub fn for_flavor(flavor: MarkdownFlavor) -> Self {
+        match flavor {
+            MarkdownFlavor::GitHub => Self::new()
+                .with_gfm(true)
+                .with_code_theme(CodeBlockTheme::GitHub)
+                .with_language_classes(true)
+                .with_new_tab_links(false)
+                // GitHub sanitizes README HTML rather than rendering it as written
+                .with_raw_html_policy(RawHtmlPolicy::Sanitize)
+                // and drops `javascript:` and other unsafe link schemes
+                .with_url_policy(UrlPolicy::SafeSchemes)
+                .with_gfm_alerts(true)
+                .with_heading_ids(true)
+                .with_autolinks(true)
+                // GitHub renders `:shortcode:` emoji; the table needs the `emoji` feature
+                .with_emoji_shortcodes(cfg!(feature = "emoji")),
+            MarkdownFlavor::Obsidian => Self::new()
+                .with_gfm(true)
+                // Vault notes carry YAML properties
+                .with_metadata_blocks(true)
+                .with_math(true)
+                .with_wikilinks(true)
+                .with_callouts(true)
+                .with_highlight(true),
+            MarkdownFlavor::Chat => Self::new()
+                .with_gfm(true)
+                .with_tables(false)
+                .with_footnotes(false)
+                .with_soft_break(SoftBreakMode::LineBreak)
+                .with_autolinks(true)
+                .with_spoilers(true)
+                .with_underline(true),
+        }
+    }
+
+    /// Enable or disable GitHub Flavored Markdown features
+    /// (tables, footnotes, strikethrough and task lists together)
+    #[must_use]
+    pub fn with_gfm(mut self, enable: bool) -> Self {
+        self.enable_gfm = enable;
+        self.enable_tables = enable;
+        self.enable_footnotes = enable;
+        self.enable_strikethrough = enable;
+        self.enable_tasklists = enable;
+        self
+    }
+
+    /// Enable or disable GFM tables
+    #[must_use]
+    pub fn with_tables(mut self, enable: bool) -> Self {
+        self.enable_tables = enable;
+        self.enable_gfm |= enable;
+        self
+    }
+
+    /// Enable or disable footnotes
+    #[must_use]
+    pub fn with_footnotes(mut self, enable: bool) -> Self {
+        self.enable_footnotes = enable;
+        self.enable_gfm |= enable;
+        self
+    }
+
+    /// Enable or disable strikethrough
+    #[must_use]
+    pub fn with_strikethrough(mut self, enable: bool) -> Self {
+        self.enable_strikethrough = enable;
+        self.enable_gfm |= enable;
+        self
+    }
+
+    /// Enable or disable task list markers
+    #[must_use]
+    pub fn with_tasklists(mut self, enable: bool) -> Self {
+        self.enable_tasklists = enable;
+        self.enable_gfm |= enable;
+        self
+    }
+
+    /// Enable or disable `$...$` / `$$...$$` math
+    #[must_use]
+    pub fn with_math(mut self, enable: bool) -> Self {
+        self.enable_math = enable;
+        self
+    }
+
+    /// Enable or disable definition lists
+    #[must_use]
+    pub fn with_definition_lists(mut self, enable: bool) -> Self {
+        self.enable_definition_lists = enable;
+        self
+    }
+
+    /// Enable or disable `^superscript^`
+    #[must_use]
+    pub fn with_superscript(mut self, enable: bool) -> Self {
+        self.enable_superscript = enable;
+        self
+    }
+
+    /// Enable or disable `~subscript~`
+    #[must_use]
+    pub fn with_subscript(mut self, enable: bool) -> Self {
+        self.enable_subscript = enable;
+        self
+    }
+
+    /// Enable or disable `{#id .class}` heading attributes
+    #[must_use]
+    pub fn with_heading_attributes(mut self, enable: bool) -> Self {
+        self.enable_heading_attributes = enable;
+        self
+    }
+
+    /// Enable or disable YAML/TOML frontmatter parsing
+    #[must_use]
+    pub fn with_metadata_blocks(mut self, enable: bool) -> Self {
+        self.enable_metadata_blocks = enable;
+        self
+    }
+
+    /// Set the code block theme (applies Tailwind styling)
+    #[must_use]
+    pub fn with_code_theme(mut self, theme: CodeBlockTheme) -> Self {
+        self.code_theme = Some(theme);
+        self
+    }
+
+    /// Pair a light and a dark code block theme, switched by Tailwind's `dark` class
+    #[must_use]
+    pub fn with_code_theme_pair(mut self, light: CodeBlockTheme, dark: CodeBlockTheme) -> Self {
+        self.code_theme = Some(light);
+        self.dark_code_theme = Some(dark);
+        self
+    }
+
+    /// Disable code block theme styling (useful when using external syntax highlighters)
+    #[must_use]
+    pub fn without_code_theme(mut self) -> Self {
+        self.code_theme = None;
+        self.dark_code_theme = None;
+        self
+    }
+
+    /// Enable or disable `language-xxx` classes on code blocks
+    #[must_use]
+    pub fn with_language_classes(mut self, enable: bool) -> Self {
+        self.syntax_highlighting_language_classes = enable;
+        self
+    }
+
+    /// Enable or disable server-side syntect highlighting (needs the `syntect` feature)
+    #[must_use]
+    pub fn with_syntect_highlighting(mut self, enable: bool) -> Self {
+        self.syntect_highlighting = enable;
+        self
+    }
+
+    /// Configure whether links open in new tabs
+    #[must_use]
+    pub fn with_new_tab_links(mut self, enable: bool) -> Self {
+        self.open_links_in_new_tab = enable;
+        self.link_target = None;
+        self
+    }
+
+    /// Choose which links open in new tabs, e.g. only external ones
+    #[must_use]
+    pub fn with_link_target_policy(mut self, policy: LinkTargetPolicy) -> Self {
+        self.link_target = Some(policy);
+        self
+    }
+
+    /// Emit `rel` on every link instead of the default `noopener noreferrer` on new-tab
+    /// links, e.g. `"nofollow ugc noopener"` for user comments
+    #[must_use]
+    pub fn with_link_rel(mut self, rel: impl Into<String>) -> Self {
+        self.link_rel = Some(rel.into());
+        self
+    }
+
+    /// Rewrite every link destination with `rewriter`, e.g. to resolve relative paths,
+    /// add UTM parameters or map wiki slugs to routes
+    #[must_use]
+    pub fn with_link_rewriter(
+        mut self,
+        rewriter: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let rewriter: Arc<dyn Fn(&str) -> String + Send + Sync> = Arc::new(rewriter);
+        self.link_rewriter = Some(LinkRewriter::from_arc(rewriter));
+        self
+    }
+
+    /// Rewrite every image src with `transform`, e.g. to a resizing CDN URL. Return an
+    /// [`ImageSource`] with `srcset`/`sizes` to serve responsive candidates:
+    ///
+    /// ```rust,ignore
+    /// let options = MarkdownOptions::new().with_image_src_transform(|src| {
+    ///     ImageSource::new(format!("https://cdn.example.com/w_960/{src}"))
+    ///         .with_srcset(format!("https://cdn.example.com/w_480/{src} 480w, https://cdn.example.com/w_960/{src} 960w"))
+    ///         .with_sizes("(min-width: 768px) 768px, 100vw")
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_image_src_transform(
+        mut self,
+        transform: impl Fn(&str) -> ImageSource + Send + Sync + 'static,
+    ) -> Self {
+        let transform: Arc<dyn Fn(&str) -> ImageSource + Send + Sync> = Arc::new(transform);
+        self.image_src_transform = Some(ImageSrcTransform::from_arc(transform));
+        self
+    }
+
+    /// Whether a link to `href` opens in a new tab: per `link_target` if set, otherwise
+    /// per `open_links_in_new_tab`
+    pub fn link_opens_in_new_tab(&self, href: &str) -> bool {
+        match &self.link_target {
+            Some(policy) => policy.opens_in_new_tab(href),
+            None => self.open_links_in_new_tab,
+        }
+    }
+
+    /// Configure whether raw HTML in markdown is rendered
+    #[must_use]
+    pub fn with_allow_raw_html(mut self, enable: bool) -> Self {
+        self.allow_raw_html = enable;
+        self.raw_html = None;
+        self
+    }
+
+    /// Handle raw HTML with `policy`, e.g. `RawHtmlPolicy::Sanitize` for user content
+    #[must_use]
+    pub fn with_raw_html_policy(mut self, policy: RawHtmlPolicy) -> Self {
+        self.raw_html = Some(policy);
+        self
+    }
+
+    /// Filter link and image URLs with `policy`, e.g. `UrlPolicy::SafeSchemes` for user
+    /// content rendered with raw HTML escaped
+    #[must_use]
+    pub fn with_url_policy(mut self, policy: UrlPolicy) -> Self {
+        self.url_policy = Some(policy);
+        self
+    }
+
+    /// The URL policy in effect: `url_policy` if set, otherwise `SafeSchemes` when raw
+    /// HTML is sanitized or denied
+    pub fn url_policy(&self) -> UrlPolicy {
+        self.url_policy.unwrap_or(match self.raw_html_policy() {
+            RawHtmlPolicy::Sanitize | RawHtmlPolicy::Deny => UrlPolicy::SafeSchemes,
+            RawHtmlPolicy::Escape | RawHtmlPolicy::Allow => UrlPolicy::Allow,
+        })
+    }
+
+    /// The raw HTML policy in effect: `raw_html` if set, otherwise `Allow` or `Escape`
+    /// following `allow_raw_html`
+    pub fn raw_html_policy(&self) -> RawHtmlPolicy {
+        self.raw_html.unwrap_or(if self.allow_raw_html {
+            RawHtmlPolicy::Allow
+        } else {
+            RawHtmlPolicy::Escape
+        })
+    }
+
+    /// Use explicit Tailwind utility classes on each element instead of relying on prose.
+    /// When `false` (default), relies on Tailwind's `prose` classes for styling.
+    /// When `true`, applies `MarkdownClasses::*` constants directly to elements.
+    #[must_use]
+    pub fn with_explicit_classes(mut self, enable: bool) -> Self {
+        self.use_explicit_classes = enable;
+        self
+    }
+
+    /// Restyle individual elements; see [`MarkdownClassMap`] for how entries apply in
+    /// prose and explicit-classes mode
+    #[must_use]
+    pub fn with_class_map(mut self, classes: MarkdownClassMap) -> Self {
+        self.class_map = classes;
+        self
+    }
+
+    /// Switch to a bundled look: sets the wrapper classes, the class map, the code theme
+    /// the preset is designed around, and explicit classes for presets that don't use
+    /// `prose`. Call it before fine-tuning those options.
+    #[must_use]
+    pub fn with_theme(mut self, theme: MarkdownTheme) -> Self {
+        self.class_map = theme.class_map();
+        self.use_explicit_classes = theme != MarkdownTheme::Prose;
+        if let Some(code_theme) = theme.code_theme() {
+            self.code_theme = Some(code_theme);
+        }
+        self.theme = theme;
+        self
+    }
+
+    /// Put `class` on the wrapper instead of the theme's wrapper classes. The `class`
+    /// prop of `<Markdown>` and option-driven extras are still added.
+    #[must_use]
+    pub fn with_base_class(mut self, class: impl Into<String>) -> Self {
+        self.base_class = Some(class.into());
+        self
+    }
+
+    /// Keep the Tailwind `prose` classes off the wrapper, leaving only the
+    /// `leptos-mdx-content` hook, for non-Tailwind and custom design system setups
+    #[must_use]
+    pub fn without_prose(self) -> Self {
+        self.with_base_class("leptos-mdx-content")
+    }
+
+    /// Configure whether HTML comments are dropped from the output
+    #[must_use]
+    pub fn with_strip_html_comments(mut self, enable: bool) -> Self {
+        self.strip_html_comments = enable;
+        self
+    }
+
+    /// Report each dropped HTML comment to `handler`
+    #[must_use]
+    pub fn with_html_comment_handler(
+        mut self,
+        handler: impl Fn(&str) + Send + Sync + 'static,
+    ) -> Self {
+        let handler: Arc<dyn Fn(&str) + Send + Sync> = Arc::new(handler);
+        self.on_html_comment = Some(HtmlCommentHandler::from_arc(handler));
+        self
+    }
+
+    /// Report everything the sanitizer removes or escapes to `handler`, with the byte
+    /// offset of the altered content in the source
+    #[must_use]
+    pub fn with_sanitize_handler(
+        mut self,
+        handler: impl Fn(&SanitizeEvent) + Send + Sync + 'static,
+    ) -> Self {
+        let handler: Arc<dyn Fn(&SanitizeEvent) + Send + Sync> = Arc::new(handler);
+        self.on_sanitize = Some(SanitizeHandler::from_arc(handler));
+        self
+    }
+
+    /// Pass each rendered document's frontmatter to `handler` (title, date, tags, ...).
+    /// Enables metadata blocks.
+    #[must_use]
+    pub fn with_frontmatter_handler(
+        mut self,
+        handler: impl Fn(&Frontmatter) + Send + Sync + 'static,
+    ) -> Self {
+        let handler: Arc<dyn Fn(&Frontmatter) + Send + Sync> = Arc::new(handler);
+        self.on_frontmatter = Some(FrontmatterHandler::from_arc(handler));
+        self.enable_metadata_blocks = true;
+        self
+    }
+
+    /// Insert `<wbr>` break opportunities into tokens of at least `min_len` characters,
+    /// preventing long URLs and hashes from overflowing narrow layouts
+    #[must_use]
+    pub fn with_long_token_breaks(mut self, min_len: usize) -> Self {
+        self.long_token_breaks = Some(min_len.max(1));
+        self
+    }
+
+    /// Leave long tokens unbroken (default)
+    #[must_use]
+    pub fn without_long_token_breaks(mut self) -> Self {
+        self.long_token_breaks = None;
+        self
+    }
+
+    /// Render headings one size step smaller without changing their semantic level
+    #[must_use]
+    pub fn with_compact_headings(mut self, enable: bool) -> Self {
+        self.compact_headings = enable;
+        self
+    }
+
+    /// Cap and/or center images, e.g. `MediaLayout::new().with_max_width("max-w-2xl")
+    /// .with_centered(true)`
+    #[must_use]
+    pub fn with_image_layout(mut self, layout: MediaLayout) -> Self {
+        self.image_layout = Some(layout);
+        self
+    }
+
+    /// Load images lazily and decode them off the main thread
+    /// (`loading="lazy" decoding="async"`)
+    #[must_use]
+    pub fn with_lazy_images(mut self, enable: bool) -> Self {
+        self.lazy_images = enable;
+        self
+    }
+
+    /// Look up each image's intrinsic `(width, height)` by its src as written, e.g. from
+    /// an asset manifest, and emit them so the layout doesn't shift as images load
+    #[must_use]
+    pub fn with_image_dimensions(
+        mut self,
+        dimensions: impl Fn(&str) -> Option<(u32, u32)> + Send + Sync + 'static,
+    ) -> Self {
+        self.image_dimensions = Some(ImageDimensions::from_arc(Arc::new(dimensions)));
+        self
+    }
+
+    /// Cap and/or center tables
+    #[must_use]
+    pub fn with_table_layout(mut self, layout: MediaLayout) -> Self {
+        self.table_layout = Some(layout);
+        self
+    }
+
+    /// Prefix in-document anchor hrefs (footnotes, `[x](#id)` links) with the given route
+    #[must_use]
+    pub fn with_anchor_base(mut self, route: impl Into<String>) -> Self {
+        self.anchor_base = Some(route.into());
+        self
+    }
+
+    /// Build in-document anchor hrefs with `resolver`, which receives the id without `#`
+    #[must_use]
+    pub fn with_anchor_href_resolver(
+        mut self,
+        resolver: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let resolver: Arc<dyn Fn(&str) -> String + Send + Sync> = Arc::new(resolver);
+        self.anchor_href_resolver = Some(AnchorHrefResolver::from_arc(resolver));
+        self
+    }
+
+    /// Highlight deep-link targets (`:target`) and scroll them into view after hydration
+    #[must_use]
+    pub fn with_anchor_target_highlight(mut self, enable: bool) -> Self {
+        self.highlight_anchor_target = enable;
+        self
+    }
+
+    /// Set how `# H1` headings are treated (keep, demote to H2, or strip a duplicate title)
+    #[must_use]
+    pub fn with_h1_policy(mut self, policy: H1Policy) -> Self {
+        self.h1_policy = policy;
+        self
+    }
+
+    /// Render blockquotes starting with a bold label (`> **Note:** ...`) as styled alerts
+    #[must_use]
+    pub fn with_legacy_alerts(mut self, enable: bool) -> Self {
+        self.legacy_alerts = enable;
+        self
+    }
+
+    /// Enable or disable GFM alert callouts (`> [!NOTE]`); disabled, they render as
+    /// plain blockquotes
+    #[must_use]
+    pub fn with_gfm_alerts(mut self, enable: bool) -> Self {
+        self.gfm_alerts = enable;
+        self
+    }
+
+    /// Render Obsidian callouts as alerts: `> [!kind] Optional title`, with the title
+    /// replacing the default one and Obsidian's types (`info`, `success`, `question`,
+    /// `bug`, ...) mapped to the closest alert kind
+    #[must_use]
+    pub fn with_callouts(mut self, enable: bool) -> Self {
+        self.callouts = enable;
+        self
+    }
+
+    /// Append an `md-scope-<hash>` class derived from the content to the wrapper.
+    /// Identical content gets the same class; see `with_named_scope` for distinct ones.
+    #[must_use]
+    pub fn with_scope_class(mut self) -> Self {
+        self.scope_class = Some(ScopeClass::ContentHash);
+        self
+    }
+
+    /// Append an `md-scope-<name>` class to the wrapper
+    #[must_use]
+    pub fn with_named_scope(mut self, name: impl Into<String>) -> Self {
+        self.scope_class = Some(ScopeClass::Named(name.into()));
+        self
+    }
+
+    /// Render `~~strikethrough~~` as `<del>` (edits, default) or `<s>` (outdated content)
+    #[must_use]
+    pub fn with_strikethrough_element(mut self, element: StrikethroughElement) -> Self {
+        self.strikethrough_element = element;
+        self
+    }
+
+    /// Prefix struck text with visually hidden text for screen readers (e.g. `"deleted:"`)
+    #[must_use]
+    pub fn with_strikethrough_announcement(mut self, text: impl Into<String>) -> Self {
+        self.strikethrough_announcement = Some(text.into());
+        self
+    }
+
+    /// Configure whether text rewrite passes skip the text of existing links
+    #[must_use]
+    pub fn with_protected_links(mut self, enable: bool) -> Self {
+        self.protect_links_from_rewrites = enable;
+        self
+    }
+
+    /// Enable or disable automatic heading ids
+    #[must_use]
+    pub fn with_heading_ids(mut self, enable: bool) -> Self {
+        self.heading_ids = enable;
+        self
+    }
+
+    /// Enable or disable `#` permalink anchors on headings (needs heading ids)
+    #[must_use]
+    pub fn with_heading_anchors(mut self, enable: bool) -> Self {
+        self.heading_anchors = enable;
+        self
+    }
+
+    /// Cache rendered HTML in `cache` (e.g. a [`MemoryCache`](crate::MemoryCache) or a
+    /// shared Redis-backed [`MarkdownCacheBackend`](crate::MarkdownCacheBackend))
+    #[must_use]
+    pub fn with_render_cache(mut self, cache: RenderCache) -> Self {
+        self.render_cache = Some(cache);
+        self
+    }
+
+    /// Prefix cache keys with `namespace`, separating renderers whose callbacks differ
+    #[must_use]
+    pub fn with_cache_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.cache_namespace = Some(namespace.into());
+        self
+    }
+
+    /// Cap the output at `max_blocks` top-level blocks, followed by an ellipsis
+    #[must_use]
+    pub fn with_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = Some(max_blocks);
+        self
     }
-}
 
-impl MarkdownOptions {
-    /// Create a new MarkdownOptions with default values
+    /// Cap the output at about `max_chars` characters of text, cut between blocks (inside
+    /// the first one when it alone is longer) and followed by an ellipsis
     #[must_use]
-    pub fn new() -> Self {
-        Self::default()
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
     }
 
-    /// Enable or disable GitHub Flavored Markdown features
+    /// Fail with [`MarkdownError::InputTooLong`](crate::MarkdownError::InputTooLong) for
+    /// input over `max_len` bytes, before it is parsed
     #[must_use]
-    pub fn with_gfm(mut self, enable: bool) -> Self {
-        self.enable_gfm = enable;
+    pub fn with_max_input_len(mut self, max_len: usize) -> Self {
+        self.max_input_len = Some(max_len);
         self
     }
 
-    /// Set the code block theme (applies Tailwind styling)
+    /// Fail with [`MarkdownError::NestingTooDeep`](crate::MarkdownError::NestingTooDeep)
+    /// when blocks or inline elements nest more than `max_depth` levels deep, e.g.
+    /// hundreds of `>` quote markers in untrusted input
     #[must_use]
-    pub fn with_code_theme(mut self, theme: CodeBlockTheme) -> Self {
-        self.code_theme = Some(theme);
+    pub fn with_max_nesting_depth(mut self, max_depth: usize) -> Self {
+        self.max_nesting_depth = Some(max_depth);
         self
     }
 
-    /// Disable code block theme styling (useful when using external syntax highlighters)
+    /// Replace the rendering of individual elements with custom views
     #[must_use]
-    pub fn without_code_theme(mut self) -> Self {
-        self.code_theme = None;
+    pub fn with_components(mut self, components: MarkdownComponents) -> Self {
+        self.components = components;
         self
     }
 
-    /// Enable or disable `language-xxx` classes on code blocks
+    /// Choose how soft line breaks render (`SoftBreakMode::Newline` keeps line structure)
     #[must_use]
-    pub fn with_language_classes(mut self, enable: bool) -> Self {
-        self.syntax_highlighting_language_classes = enable;
+    pub fn with_soft_break(mut self, mode: SoftBreakMode) -> Self {
+        self.soft_break = mode;
         self
     }
 
-    /// Configure whether links open in new tabs
+    /// Add an "expand" button opening each code block in a full-screen modal (no wrapping,
+    /// larger font) for reviewing long snippets on small screens
     #[must_use]
-    pub fn with_new_tab_links(mut self, enable: bool) -> Self {
-        self.open_links_in_new_tab = enable;
+    pub fn with_code_expand_action(mut self, enable: bool) -> Self {
+        self.code_expand_action = enable;
         self
     }
 
-    /// Configure whether raw HTML in markdown is rendered
+    /// Add a button copying each code block's text to the clipboard, showing "Copied!"
+    /// briefly afterwards. Needs hydration: the click handler runs client-side.
     #[must_use]
-    pub fn with_allow_raw_html(mut self, enable: bool) -> Self {
-        self.allow_raw_html = enable;
+    pub fn with_code_copy_button(mut self, enable: bool) -> Self {
+        self.code_copy_button = enable;
         self
     }
 
-    /// Use explicit Tailwind utility classes on each element instead of relying on prose.
-    /// When `false` (default), relies on Tailwind's `prose` classes for styling.
-    /// When `true`, applies `MarkdownClasses::*` constants directly to elements.
+    /// Color `+` lines green and `-` lines red in ```` ```diff ```` blocks (hunk headers
+    /// blue), with dark-mode variants
     #[must_use]
-    pub fn with_explicit_classes(mut self, enable: bool) -> Self {
-        self.use_explicit_classes = enable;
+    pub fn with_diff_highlighting(mut self, enable: bool) -> Self {
+        self.diff_highlighting = enable;
+        self
+    }
+
+    /// Show at most `lines` lines of each code block, fading out above an "Expand"
+    /// toggle, so huge pastes don't dominate chat and docs layouts
+    #[must_use]
+    pub fn with_code_max_lines(mut self, lines: usize) -> Self {
+        self.code_max_lines = Some(lines);
+        self
+    }
+
+    /// Wrap long code lines instead of scrolling the block horizontally
+    #[must_use]
+    pub fn with_code_wrap(mut self, enable: bool) -> Self {
+        self.code_wrap = enable;
+        self
+    }
+
+    /// Set how many spaces wide a tab renders in code blocks (defaults to 4)
+    #[must_use]
+    pub fn with_code_tab_size(mut self, size: u8) -> Self {
+        self.code_tab_size = size;
+        self
+    }
+
+    /// Append a transform rewriting the parsed events before rendering, e.g. to expand
+    /// emoji shortcodes or link mentions. Transforms run in registration order.
+    #[must_use]
+    pub fn with_event_transform(
+        mut self,
+        transform: impl for<'a> Fn(Vec<Event<'a>>) -> Vec<Event<'a>> + Send + Sync + 'static,
+    ) -> Self {
+        self.event_transforms
+            .push(EventTransform::from_arc(Arc::new(transform)));
+        self
+    }
+
+    /// Render a titled image alone in its paragraph (`![alt](src "Title")`) as a numbered
+    /// figure with the title as caption
+    #[must_use]
+    pub fn with_figures(mut self, enable: bool) -> Self {
+        self.figures = enable;
+        self
+    }
+
+    /// Render fenced code with a `caption="..."` attribute (```` ```rust caption="Setup" ````)
+    /// as a numbered listing
+    #[must_use]
+    pub fn with_captioned_listings(mut self, enable: bool) -> Self {
+        self.captioned_listings = enable;
+        self
+    }
+
+    /// Render Pandoc-style citations (`[@key]`, `[@a, p. 3; @b]`) with `resolver`, linking
+    /// each to a bibliography section of the cited entries after the document
+    #[must_use]
+    pub fn with_citations(
+        mut self,
+        resolver: impl Fn(&str) -> Option<Citation> + Send + Sync + 'static,
+    ) -> Self {
+        self.citation_resolver = Some(CitationResolver::from_arc(Arc::new(resolver)));
+        self
+    }
+
+    /// Register an in-app route (`/users/:id`) that `[:route:name id=42 label="Profile"]`
+    /// directives link to. With the `router` feature links render as `leptos_router`'s
+    /// `<A>`.
+    #[must_use]
+    pub fn with_route(mut self, name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        self.routes = self.routes.with_route(name, pattern);
+        self
+    }
+
+    /// Use `routes` for `[:route:...]` directives, replacing any registered so far
+    #[must_use]
+    pub fn with_routes(mut self, routes: RouteMap) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Accept inline footnotes (`^[note text]`), moved to the footnotes section and
+    /// numbered in order with the regular ones. Requires `enable_footnotes`.
+    #[must_use]
+    pub fn with_inline_footnotes(mut self, enable: bool) -> Self {
+        self.inline_footnotes = enable;
+        self
+    }
+
+    /// Render ```` ```mermaid ```` fences as `<pre class="mermaid">` holding the diagram
+    /// source, which Mermaid.js turns into charts, instead of as code
+    #[must_use]
+    pub fn with_mermaid_diagrams(mut self, enable: bool) -> Self {
+        self.mermaid_diagrams = enable;
+        self
+    }
+
+    /// Filter heading sections before rendering, e.g. hiding sections marked `{.internal}`
+    /// from unauthorized viewers. Runs after the event transforms, and also applies to
+    /// extracted headings, so removed sections never reach the table of contents.
+    #[must_use]
+    pub fn with_block_filter(
+        mut self,
+        filter: impl Fn(&BlockContext) -> BlockAction + Send + Sync + 'static,
+    ) -> Self {
+        let filter: Arc<dyn Fn(&BlockContext) -> BlockAction + Send + Sync> = Arc::new(filter);
+        self.block_filter = Some(BlockFilter::from_arc(filter));
+        self
+    }
+
+    /// Render `:::name` ... `:::` fenced containers as
+    /// `<div class="markdown-container markdown-container-name">`
+    #[must_use]
+    pub fn with_containers(mut self, enable: bool) -> Self {
+        self.containers = enable;
+        self
+    }
+
+    /// Render `::: note|tip|warning|danger [Custom title]` containers as callout boxes with
+    /// the alert icon and title, holding any nested content. `info`, `hint`, `important`
+    /// and `caution` work too. Enables containers.
+    #[must_use]
+    pub fn with_admonitions(mut self, enable: bool) -> Self {
+        self.admonitions = enable;
+        self.containers |= enable;
+        self
+    }
+
+    /// Render `:::tabs` containers as tab groups with one tab per `== Title` line, and group
+    /// consecutive ```` ```sh tab="Cargo" ```` code fences the same way, e.g. for install
+    /// commands per package manager. Enables containers.
+    #[must_use]
+    pub fn with_tabs(mut self, enable: bool) -> Self {
+        self.tabs = enable;
+        self.containers |= enable;
+        self
+    }
+
+    /// Render `:::details Summary text` containers as collapsed `<details>` blocks, the rest
+    /// of the marker line as their `<summary>` ("Details" without one). Enables containers.
+    #[must_use]
+    pub fn with_details(mut self, enable: bool) -> Self {
+        self.details = enable;
+        self.containers |= enable;
+        self
+    }
+
+    /// Leave headings inside `:::name` containers out of the table of contents, e.g. to
+    /// keep an appendix out of the primary navigation. Enables containers.
+    #[must_use]
+    pub fn with_toc_excluded_container(mut self, name: impl Into<String>) -> Self {
+        self.containers = true;
+        self.toc_excluded_containers.push(name.into());
+        self
+    }
+
+    /// Show a placeholder for, or hide, content the renderer deliberately doesn't render
+    /// (see [`UnsupportedKind`]). Math is parsed even with `enable_math` off so it can be
+    /// replaced.
+    #[must_use]
+    pub fn with_unsupported_content(mut self, unsupported: UnsupportedContent) -> Self {
+        self.unsupported = unsupported;
+        self
+    }
+
+    /// Parse only inline constructs (emphasis, code, links) for single-line strings like
+    /// card titles: the input is joined into one line, block syntax such as a leading
+    /// `#` stays literal, and no `<p>` or other block wrapper is emitted
+    #[must_use]
+    pub fn with_inline_only(mut self, enable: bool) -> Self {
+        self.inline_only = enable;
+        self
+    }
+
+    /// Link bare URLs (`https://…`, `www.…`) and e-mail addresses in text like GitHub's
+    /// autolink extension; trailing punctuation stays outside the link
+    #[must_use]
+    pub fn with_autolinks(mut self, enable: bool) -> Self {
+        self.autolinks = enable;
+        self
+    }
+
+    /// Parse `[[Page Name]]` and `[[page|label]]` wikilinks. Without a resolver the target
+    /// is percent-encoded as a relative href; targets with a URL scheme stay unresolved.
+    /// Takes precedence over `[[Key]]` key caps under `with_kbd`.
+    #[must_use]
+    pub fn with_wikilinks(mut self, enable: bool) -> Self {
+        self.wikilinks = enable;
+        self
+    }
+
+    /// Map wikilink targets to hrefs with `resolver`; targets it returns `None` for render
+    /// as unresolved (`MarkdownClassMap::wikilink_unresolved`). Enables wikilinks.
+    #[must_use]
+    pub fn with_wikilink_resolver(
+        mut self,
+        resolver: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.wikilink_resolver = Some(WikilinkResolver::from_arc(Arc::new(resolver)));
+        self.wikilinks = true;
+        self
+    }
+
+    /// Render `==highlighted==` text as `<mark>` (`MarkdownClasses::MARK`)
+    #[must_use]
+    pub fn with_highlight(mut self, enable: bool) -> Self {
+        self.highlight = enable;
+        self
+    }
+
+    /// Render `++inserted++` text as `<ins>`, the counterpart of `~~deleted~~` text for
+    /// redlines and edit tracking
+    #[must_use]
+    pub fn with_insertions(mut self, enable: bool) -> Self {
+        self.insertions = enable;
+        self
+    }
+
+    /// Render keyboard keys as `<kbd>` key caps: `` `kbd:Ctrl+Shift+P` `` code spans, and
+    /// `[[Ctrl]]+[[C]]` while wikilinks (which use the same brackets) are off
+    #[must_use]
+    pub fn with_kbd(mut self, enable: bool) -> Self {
+        self.kbd = enable;
+        self
+    }
+
+    /// Render `||spoiler||` text blurred until clicked (`MarkdownClasses::SPOILER`)
+    #[must_use]
+    pub fn with_spoilers(mut self, enable: bool) -> Self {
+        self.spoilers = enable;
+        self
+    }
+
+    /// Render `__text__` as `<u>` as chat apps do; `**text**` stays strong
+    #[must_use]
+    pub fn with_underline(mut self, enable: bool) -> Self {
+        self.underline = enable;
+        self
+    }
+
+    /// Enable or disable headings. Disabled, `# lines` render as paragraphs with the `#`
+    /// kept, for chat messages where a leading `#` is rarely meant as a heading.
+    #[must_use]
+    pub fn with_headings(mut self, enable: bool) -> Self {
+        self.enable_headings = enable;
+        self
+    }
+
+    /// Replace `:shortcode:`s such as `:sparkles:` in text with their emoji (needs the
+    /// `emoji` feature for the gemoji table)
+    #[must_use]
+    pub fn with_emoji_shortcodes(mut self, enable: bool) -> Self {
+        self.emoji_shortcodes = enable;
+        self
+    }
+
+    /// Render shortcodes `resolver` maps to a URL as that image, e.g. a custom emoji pack;
+    /// other shortcodes fall back to the gemoji table. Enables emoji shortcodes.
+    #[must_use]
+    pub fn with_custom_emoji(
+        mut self,
+        resolver: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.emoji_resolver = Some(EmojiResolver::from_arc(Arc::new(resolver)));
+        self.emoji_shortcodes = true;
+        self
+    }
+
+    /// Link `@name` mentions in text to the target `resolver` returns for `name`; names it
+    /// doesn't know, e-mail addresses and mentions inside links stay text
+    #[must_use]
+    pub fn with_mentions(
+        mut self,
+        resolver: impl Fn(&str) -> Option<MentionTarget> + Send + Sync + 'static,
+    ) -> Self {
+        self.mention_resolver = Some(MentionResolver::from_arc(Arc::new(resolver)));
+        self
+    }
+
+    /// Link `#topic` hashtags in text to the target `resolver` returns for `topic`.
+    /// All-digit tags (`#123`) are never hashtags.
+    #[must_use]
+    pub fn with_hashtags(
+        mut self,
+        resolver: impl Fn(&str) -> Option<MentionTarget> + Send + Sync + 'static,
+    ) -> Self {
+        self.hashtag_resolver = Some(MentionResolver::from_arc(Arc::new(resolver)));
+        self
+    }
+
+    /// Link GitHub-style references in text to the repository at `repo_url`
+    /// (`https://github.com/owner/repo`): `#123` to its issues, `owner/repo#123` to the
+    /// same host and 7–40 digit commit SHAs to its commits
+    #[must_use]
+    pub fn with_repo_references(self, repo_url: impl Into<String>) -> Self {
+        let repo_url = repo_url.into();
+        self.with_reference_linker(move |reference| Some(reference.url(&repo_url)))
+    }
+
+    /// Link issue, pull request and commit references to the URL `linker` returns;
+    /// references it returns `None` for stay text
+    #[must_use]
+    pub fn with_reference_linker(
+        mut self,
+        linker: impl Fn(&RepoReference) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.reference_linker = Some(ReferenceLinker::from_arc(Arc::new(linker)));
         self
     }
 }
 
-/// Tailwind CSS class names for markdown elements
+/// Tailwind CSS class names for markdown elements.
+///
+/// The main colors read `--md-*` custom properties (`--md-link-color`, `--md-code-bg`, …)
+/// and fall back to the built-in palette, so a single `:root` rule can retheme them.
 pub struct MarkdownClasses;
 
 impl MarkdownClasses {
@@ -107,55 +1657,186 @@ impl MarkdownClasses {
 
     // Headings
     pub const H1: &'static str =
-        "text-3xl font-bold text-gray-900 dark:text-gray-100 mt-6 mb-4 first:mt-0";
+        "text-3xl font-bold text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-6 mb-4 first:mt-0";
     pub const H2: &'static str =
-        "text-2xl font-semibold text-gray-900 dark:text-gray-100 mt-5 mb-3";
-    pub const H3: &'static str = "text-xl font-semibold text-gray-900 dark:text-gray-100 mt-4 mb-2";
-    pub const H4: &'static str = "text-lg font-medium text-gray-900 dark:text-gray-100 mt-3 mb-2";
-    pub const H5: &'static str = "text-base font-medium text-gray-900 dark:text-gray-100 mt-3 mb-2";
-    pub const H6: &'static str = "text-sm font-medium text-gray-600 dark:text-gray-400 mt-3 mb-2";
+        "text-2xl font-semibold text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-5 mb-3";
+    pub const H3: &'static str = "text-xl font-semibold text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-4 mb-2";
+    pub const H4: &'static str = "text-lg font-medium text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-3 mb-2";
+    pub const H5: &'static str = "text-base font-medium text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-3 mb-2";
+    pub const H6: &'static str = "text-sm font-medium text-[color:var(--md-muted-color,#4b5563)] dark:text-[color:var(--md-muted-color,#9ca3af)] mt-3 mb-2";
 
     // Text elements
-    pub const PARAGRAPH: &'static str = "mb-4 leading-relaxed text-gray-700 dark:text-gray-300";
-    pub const BLOCKQUOTE: &'static str = "border-l-4 border-blue-500 pl-4 py-2 my-4 bg-blue-50 dark:bg-blue-950/30 text-gray-700 dark:text-gray-300 italic";
+    pub const PARAGRAPH: &'static str = "mb-4 leading-relaxed text-[color:var(--md-text-color,#374151)] dark:text-[color:var(--md-text-color,#d1d5db)]";
+    pub const BLOCKQUOTE: &'static str = "border-l-4 border-[color:var(--md-quote-border-color,#3b82f6)] pl-4 py-2 my-4 bg-[color:var(--md-quote-bg,#eff6ff)] dark:bg-[color:var(--md-quote-bg,#1725544d)] text-[color:var(--md-text-color,#374151)] dark:text-[color:var(--md-text-color,#d1d5db)] italic";
 
     // Code
-    pub const INLINE_CODE: &'static str = "bg-gray-100 dark:bg-gray-800 text-gray-800 dark:text-gray-200 px-1.5 py-0.5 rounded text-sm font-mono";
-    pub const CODE_BLOCK: &'static str = "bg-gray-50 dark:bg-gray-900 border border-gray-200 dark:border-gray-700 rounded-lg p-4 my-4 overflow-x-auto";
+    pub const INLINE_CODE: &'static str = "bg-[color:var(--md-code-bg,#f3f4f6)] dark:bg-[color:var(--md-code-bg,#1f2937)] text-[color:var(--md-code-color,#1f2937)] dark:text-[color:var(--md-code-color,#e5e7eb)] px-1.5 py-0.5 rounded text-sm font-mono";
+    pub const CODE_BLOCK: &'static str = "bg-[color:var(--md-pre-bg,#f9fafb)] dark:bg-[color:var(--md-pre-bg,#111827)] border border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] rounded-lg p-4 my-4";
     pub const CODE_BLOCK_CODE: &'static str =
-        "font-mono text-sm leading-relaxed text-gray-800 dark:text-gray-200";
+        "font-mono text-sm leading-relaxed text-[color:var(--md-code-color,#1f2937)] dark:text-[color:var(--md-code-color,#e5e7eb)]";
 
     // Lists
     pub const UL: &'static str =
-        "list-disc list-inside mb-4 space-y-1 text-gray-700 dark:text-gray-300";
+        "list-disc list-inside mb-4 space-y-1 text-[color:var(--md-text-color,#374151)] dark:text-[color:var(--md-text-color,#d1d5db)]";
     pub const OL: &'static str =
-        "list-decimal list-inside mb-4 space-y-1 text-gray-700 dark:text-gray-300";
+        "list-decimal list-inside mb-4 space-y-1 text-[color:var(--md-text-color,#374151)] dark:text-[color:var(--md-text-color,#d1d5db)]";
     pub const LI: &'static str = "leading-relaxed";
 
     // Links and images
-    pub const LINK: &'static str = "text-blue-600 dark:text-blue-400 hover:text-blue-800 dark:hover:text-blue-300 underline underline-offset-2 hover:underline-offset-4 transition-all";
+    pub const LINK: &'static str = "text-[color:var(--md-link-color,#2563eb)] dark:text-[color:var(--md-link-color,#60a5fa)] hover:text-[color:var(--md-link-hover-color,#1e40af)] dark:hover:text-[color:var(--md-link-hover-color,#93c5fd)] underline underline-offset-2 hover:underline-offset-4 transition-all";
     pub const IMAGE: &'static str = "max-w-full h-auto rounded-lg shadow-sm my-4";
 
     // Tables
-    pub const TABLE: &'static str = "min-w-full divide-y divide-gray-200 dark:divide-gray-700 my-4 border border-gray-200 dark:border-gray-700 rounded-lg overflow-hidden";
+    pub const TABLE: &'static str = "min-w-full divide-y divide-[color:var(--md-border-color,#e5e7eb)] dark:divide-[color:var(--md-border-color,#374151)] my-4 border border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] rounded-lg overflow-hidden";
     pub const THEAD: &'static str = "bg-gray-50 dark:bg-gray-800";
     pub const TR: &'static str =
         "bg-white dark:bg-gray-900 even:bg-gray-50 dark:even:bg-gray-800/50";
-    pub const TD: &'static str = "px-6 py-4 text-sm text-gray-900 dark:text-gray-100";
-    pub const TH: &'static str = "px-6 py-3 text-left text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider";
+    pub const TD: &'static str = "px-6 py-4 text-sm text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)]";
+    pub const TH: &'static str = "px-6 py-3 text-left text-xs font-medium text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)] uppercase tracking-wider";
 
     // Other elements
     pub const HR: &'static str = "border-0 h-px bg-gradient-to-r from-transparent via-gray-300 dark:via-gray-600 to-transparent my-8";
     pub const CHECKBOX: &'static str = "mr-2 accent-blue-600";
+    pub const ANCHOR_TARGET: &'static str = "scroll-mt-20 rounded transition-colors duration-700 target:bg-yellow-100 dark:target:bg-yellow-900/40";
+    pub const BREAK_LONG_TOKENS: &'static str = "break-words [overflow-wrap:anywhere]";
+    pub const TRUNCATION: &'static str = "text-gray-400 dark:text-gray-500 select-none";
+    pub const PRESERVE_LINES: &'static str = "whitespace-pre-line";
+
+    // Code block actions
+    pub const CODE_BLOCK_WRAPPER: &'static str = "relative group";
+    /// Long code lines wrap under `code_wrap`, otherwise the block scrolls sideways
+    pub const CODE_WRAP: &'static str = "whitespace-pre-wrap break-words";
+    pub const CODE_SCROLL: &'static str = "overflow-x-auto";
+    pub const CODE_EXPAND_BUTTON: &'static str = "absolute top-2 right-2 rounded px-2 py-1 text-xs bg-gray-800/70 text-gray-100 opacity-0 group-hover:opacity-100 focus-visible:opacity-100 transition-opacity";
+    /// Copy button without its horizontal position, which depends on the expand button
+    pub const CODE_COPY_BUTTON: &'static str = "absolute top-2 rounded px-2 py-1 text-xs bg-gray-800/70 text-gray-100 hover:bg-gray-700 data-[copied]:bg-green-700 opacity-0 group-hover:opacity-100 focus-visible:opacity-100 data-[copied]:opacity-100 transition";
+    pub const CODE_COPY_ALONE: &'static str = "right-2";
+    pub const CODE_COPY_BESIDE_EXPAND: &'static str = "right-12";
+    /// Lines picked by a `{1,4-6}` fence range
+    pub const CODE_LINE_HIGHLIGHT: &'static str =
+        "inline-block min-w-full -mx-4 px-4 bg-yellow-100/70 dark:bg-yellow-400/10";
+    /// Lines of ```` ```diff ```` blocks under `diff_highlighting`
+    pub const CODE_DIFF_ADDED: &'static str = "inline-block min-w-full -mx-4 px-4 bg-green-100/70 dark:bg-green-500/15 text-green-900 dark:text-green-200";
+    pub const CODE_DIFF_REMOVED: &'static str = "inline-block min-w-full -mx-4 px-4 bg-red-100/70 dark:bg-red-500/15 text-red-900 dark:text-red-200";
+    pub const CODE_DIFF_HUNK: &'static str = "text-blue-700 dark:text-blue-300";
+    /// Long code blocks clamped under `code_max_lines`; the state lives in a checkbox
+    pub const CODE_CLAMP: &'static str = "relative group/clamp";
+    pub const CODE_CLAMP_TOGGLE: &'static str = "peer sr-only";
+    pub const CODE_CLAMPED: &'static str = "overflow-hidden group-has-[:checked]/clamp:!max-h-none";
+    pub const CODE_CLAMP_FADE: &'static str = "pointer-events-none absolute inset-x-0 bottom-7 h-16 bg-gradient-to-t from-white dark:from-gray-950 to-transparent group-has-[:checked]/clamp:hidden";
+    pub const CODE_CLAMP_BUTTON: &'static str = "block cursor-pointer py-1 text-center text-xs font-medium text-[color:var(--md-link-color,#2563eb)] dark:text-[color:var(--md-link-color,#60a5fa)] hover:underline peer-focus-visible:ring-2 peer-focus-visible:ring-blue-500 rounded";
+    pub const CODE_CLAMP_EXPAND: &'static str = "group-has-[:checked]/clamp:hidden";
+    pub const CODE_CLAMP_COLLAPSE: &'static str = "hidden group-has-[:checked]/clamp:inline";
+    pub const CODE_MODAL: &'static str = "fixed inset-0 m-0 h-full w-full max-h-none max-w-none overflow-auto bg-gray-950/95 p-6 backdrop:bg-black/60";
+    pub const CODE_MODAL_CLOSE: &'static str = "sticky top-0 float-right rounded px-3 py-1 text-sm bg-gray-800 text-gray-100 hover:bg-gray-700";
+    pub const CODE_MODAL_PRE: &'static str = "text-base whitespace-pre overflow-x-auto";
+    pub const MERMAID: &'static str = "my-4 flex justify-center overflow-x-auto";
+    pub const HEADING_ANCHOR: &'static str = "ml-2 font-normal text-gray-400 dark:text-gray-500 no-underline opacity-0 group-hover:opacity-100 focus-visible:opacity-100 focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-blue-500 rounded-sm hover:text-[color:var(--md-link-color,#2563eb)] dark:hover:text-[color:var(--md-link-color,#60a5fa)] transition-opacity";
+
+    // Table of contents
+    pub const TOC: &'static str = "text-sm";
+    pub const TOC_ITEM: &'static str = "py-0.5";
+    pub const TOC_NESTED: &'static str =
+        "mt-1 ml-3 pl-3 border-l border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)]";
+    pub const TOC_LINK: &'static str = "text-[color:var(--md-muted-color,#4b5563)] dark:text-[color:var(--md-muted-color,#9ca3af)] hover:text-[color:var(--md-link-color,#2563eb)] dark:hover:text-[color:var(--md-link-color,#60a5fa)] transition-colors";
+
+    // Emoji
+    pub const EMOJI: &'static str = "inline-block h-[1.2em] w-[1.2em] align-text-bottom";
+
+    // Inline markdown
+    pub const INLINE: &'static str = "leptos-md-inline";
+
+    // `==highlighted==` text
+    pub const MARK: &'static str = "bg-[color:var(--md-mark-bg,#fef08a)] dark:bg-[color:var(--md-mark-bg,#713f12)] text-inherit rounded-sm px-0.5";
+
+    // `||spoilers||`: the label wraps a hidden checkbox that reveals the text
+    pub const SPOILER: &'static str = "cursor-pointer rounded bg-[color:var(--md-code-bg,#e5e7eb)] dark:bg-[color:var(--md-code-bg,#374151)] px-0.5";
+    pub const SPOILER_TEXT: &'static str =
+        "blur-[5px] select-none transition peer-checked:blur-none peer-checked:select-auto";
+
+    // `__underline__` in chat content
+    pub const UNDERLINE: &'static str = "underline underline-offset-2";
+
+    // `![[embed]]` placeholders, for the app to fill in
+    pub const EMBED: &'static str = "inline-flex items-center gap-1 rounded border border-dashed border-[color:var(--md-border-color,#d1d5db)] dark:border-[color:var(--md-border-color,#4b5563)] px-2 py-0.5 text-sm";
+
+    // Wikilinks whose page doesn't exist
+    pub const WIKILINK_UNRESOLVED: &'static str = "text-[color:var(--md-muted-color,#4b5563)] dark:text-[color:var(--md-muted-color,#9ca3af)] underline decoration-dashed cursor-help";
+
+    // Editor
+    pub const EDITOR: &'static str = "flex flex-col gap-2";
+    pub const EDITOR_TOOLBAR: &'static str = "flex flex-wrap items-center gap-1";
+    pub const EDITOR_SPLIT: &'static str = "grid grid-cols-1 md:grid-cols-2 gap-4 h-[32rem]";
+    pub const EDITOR_INPUT: &'static str = "w-full h-full min-h-[16rem] resize-none rounded-lg border border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] bg-transparent p-4 font-mono text-sm focus:outline-none focus:ring-2 focus:ring-blue-500";
+    pub const EDITOR_PREVIEW: &'static str = "h-full min-h-[16rem] overflow-y-auto rounded-lg border border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] p-4";
+    pub const EDITOR_TABS: &'static str = "flex gap-1 border-b border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)]";
+    pub const EDITOR_TAB: &'static str = "px-3 py-1.5 text-sm -mb-px border-b-2 border-transparent text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)] aria-selected:border-blue-500 aria-selected:text-[color:var(--md-heading-color,#111827)] dark:aria-selected:text-[color:var(--md-heading-color,#f3f4f6)]";
+
+    // Figures and listings
+    pub const FIGURE: &'static str = "my-6";
+    pub const FIGCAPTION: &'static str =
+        "my-2 text-sm text-center text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)]";
+    pub const LOF: &'static str = "my-4 text-sm";
+    pub const CONTAINER: &'static str = "my-6";
+
+    // `:::details` containers
+    pub const DETAILS: &'static str = "my-4 rounded-lg border border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] px-4 py-2";
+    pub const SUMMARY: &'static str = "cursor-pointer py-1 font-semibold text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] marker:text-[color:var(--md-muted-color,#6b7280)]";
+
+    // Tab groups from `:::tabs` containers and `tab=` code fences
+    pub const TABS: &'static str = "my-6";
+    pub const TAB_LIST: &'static str = "flex flex-wrap gap-1 border-b border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)]";
+    pub const TAB: &'static str = "px-3 py-1.5 text-sm -mb-px border-b-2 border-transparent text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)] hover:text-[color:var(--md-heading-color,#111827)] dark:hover:text-[color:var(--md-heading-color,#f3f4f6)] aria-selected:border-[color:var(--md-link-color,#2563eb)] aria-selected:text-[color:var(--md-heading-color,#111827)] dark:aria-selected:text-[color:var(--md-heading-color,#f3f4f6)] focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-blue-500 rounded-t";
+    pub const TAB_PANEL: &'static str = "pt-2";
+
+    // Document diff
+    pub const DIFF_ADDED: &'static str =
+        "block no-underline border-l-4 border-green-500 bg-green-50 dark:bg-green-950/30 pl-3";
+    pub const DIFF_REMOVED: &'static str =
+        "block border-l-4 border-red-500 bg-red-50 dark:bg-red-950/30 pl-3 opacity-70";
+    pub const DIFF_WORD_ADDED: &'static str =
+        "no-underline rounded-sm bg-green-100 dark:bg-green-900/50 text-green-900 dark:text-green-100";
+    pub const DIFF_WORD_REMOVED: &'static str =
+        "rounded-sm bg-red-100 dark:bg-red-900/50 text-red-900 dark:text-red-100";
+
+    // Citations
+    pub const CITATION: &'static str = "text-[color:var(--md-link-color,#2563eb)] dark:text-[color:var(--md-link-color,#60a5fa)] hover:underline";
+    pub const BIBLIOGRAPHY: &'static str =
+        "mt-8 pt-4 border-t border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] text-sm space-y-2";
+
+    // Unsupported content placeholders
+    pub const UNSUPPORTED: &'static str = "inline-block my-1 px-2 py-0.5 rounded border border-dashed border-gray-300 dark:border-gray-600 text-xs text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)] select-none";
+
+    // Author mode annotations
+    pub const ANNOTATED_BLOCK: &'static str =
+        "relative pl-3 border-l-2 border-dashed border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)]";
+    pub const ANNOTATION: &'static str =
+        "flex flex-wrap items-center gap-1 font-mono text-[10px] leading-4 text-gray-400 dark:text-gray-500 select-none";
+    pub const DIAGNOSTIC: &'static str =
+        "rounded px-1 bg-red-100 dark:bg-red-900/50 text-red-800 dark:text-red-200";
 
     // Math
-    pub const MATH_INLINE: &'static str = "font-serif italic text-gray-800 dark:text-gray-200";
-    pub const MATH_DISPLAY: &'static str = "font-serif italic text-center my-4 p-3 bg-gray-50 dark:bg-gray-800 rounded-lg text-gray-800 dark:text-gray-200";
+    pub const MATH_INLINE: &'static str = "font-serif italic text-[color:var(--md-code-color,#1f2937)] dark:text-[color:var(--md-code-color,#e5e7eb)]";
+    pub const MATH_DISPLAY: &'static str = "font-serif italic text-center my-4 p-3 bg-gray-50 dark:bg-gray-800 rounded-lg text-[color:var(--md-code-color,#1f2937)] dark:text-[color:var(--md-code-color,#e5e7eb)]";
 
     // Definition lists
     pub const DL: &'static str = "my-4";
-    pub const DT: &'static str = "font-semibold text-gray-900 dark:text-gray-100 mt-4 first:mt-0";
-    pub const DD: &'static str = "ml-6 mb-2 text-gray-700 dark:text-gray-300";
+    pub const DT: &'static str = "font-semibold text-[color:var(--md-heading-color,#111827)] dark:text-[color:var(--md-heading-color,#f3f4f6)] mt-4 first:mt-0";
+    pub const DD: &'static str = "ml-6 mb-2 text-[color:var(--md-text-color,#374151)] dark:text-[color:var(--md-text-color,#d1d5db)]";
+
+    // Alerts
+    pub const ALERT: &'static str = "my-4 border-l-4 rounded-r-lg px-4 py-3";
+    pub const ALERT_TITLE: &'static str = "flex items-center gap-2 font-semibold mb-1";
+    pub const ALERT_NOTE: &'static str =
+        "border-blue-500 bg-blue-50 dark:bg-blue-950/30 text-blue-900 dark:text-blue-100";
+    pub const ALERT_TIP: &'static str =
+        "border-green-500 bg-green-50 dark:bg-green-950/30 text-green-900 dark:text-green-100";
+    pub const ALERT_IMPORTANT: &'static str =
+        "border-purple-500 bg-purple-50 dark:bg-purple-950/30 text-purple-900 dark:text-purple-100";
+    pub const ALERT_WARNING: &'static str =
+        "border-amber-500 bg-amber-50 dark:bg-amber-950/30 text-amber-900 dark:text-amber-100";
+    pub const ALERT_CAUTION: &'static str =
+        "border-red-500 bg-red-50 dark:bg-red-950/30 text-red-900 dark:text-red-100";
 
     // Superscript/Subscript
     pub const SUP: &'static str = "text-xs align-super";
@@ -164,21 +1845,568 @@ impl MarkdownClasses {
     // Emphasis
     pub const EM: &'static str = "italic";
     pub const STRONG: &'static str = "font-bold";
-    pub const DEL: &'static str = "line-through text-gray-500 dark:text-gray-400";
+    pub const DEL: &'static str = "line-through text-[color:var(--md-muted-color,#6b7280)] dark:text-[color:var(--md-muted-color,#9ca3af)]";
+    pub const KBD: &'static str = "inline-block min-w-[1.75em] px-1.5 py-px font-mono text-[0.8em] font-medium text-center whitespace-nowrap rounded border border-b-2 border-[color:var(--md-border-color,#d1d5db)] dark:border-[color:var(--md-border-color,#4b5563)] bg-[color:var(--md-code-bg,#f9fafb)] dark:bg-[color:var(--md-code-bg,#1f2937)] text-[color:var(--md-code-color,#1f2937)] dark:text-[color:var(--md-code-color,#e5e7eb)]";
+    pub const INS: &'static str = "underline decoration-2 underline-offset-2 decoration-[color:var(--md-ins-color,#16a34a)] dark:decoration-[color:var(--md-ins-color,#4ade80)]";
 
     // Special elements
-    pub const FOOTNOTE_REF: &'static str = "text-xs align-super text-blue-600 dark:text-blue-400 hover:text-blue-800 dark:hover:text-blue-300";
-    pub const FOOTNOTE_DEF: &'static str = "text-sm border-t border-gray-200 dark:border-gray-700 mt-8 pt-4 text-gray-600 dark:text-gray-400";
+    pub const FOOTNOTE_REF: &'static str = "text-xs align-super text-[color:var(--md-link-color,#2563eb)] dark:text-[color:var(--md-link-color,#60a5fa)] hover:text-[color:var(--md-link-hover-color,#1e40af)] dark:hover:text-[color:var(--md-link-hover-color,#93c5fd)]";
+    pub const FOOTNOTES: &'static str = "mt-8 pt-4 border-t border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)]";
+    pub const FOOTNOTE_LIST: &'static str = "list-decimal pl-6 space-y-2";
+    pub const FOOTNOTE_DEF: &'static str = "text-sm text-[color:var(--md-muted-color,#4b5563)] dark:text-[color:var(--md-muted-color,#9ca3af)]";
+    pub const FOOTNOTE_BACKREF: &'static str = "ml-1 text-[color:var(--md-link-color,#2563eb)] dark:text-[color:var(--md-link-color,#60a5fa)] hover:text-[color:var(--md-link-hover-color,#1e40af)] dark:hover:text-[color:var(--md-link-hover-color,#93c5fd)] no-underline";
     pub const RAW_HTML_BLOCK: &'static str = "bg-yellow-50 dark:bg-yellow-950/30 border border-yellow-200 dark:border-yellow-800 rounded-lg p-3 my-4 font-mono text-sm text-yellow-800 dark:text-yellow-200 whitespace-pre-wrap";
     pub const INLINE_HTML: &'static str = "bg-yellow-100 dark:bg-yellow-900/50 text-yellow-800 dark:text-yellow-200 px-2 py-1 rounded text-xs font-mono border border-yellow-300 dark:border-yellow-700";
 
+    // Code block title bar (```` ```rust title="main.rs" ````), colored per theme below
+    pub const CODE_TITLE: &'static str = "rounded-t-lg border border-b-0 border-[color:var(--md-border-color,#e5e7eb)] dark:border-[color:var(--md-border-color,#374151)] px-4 py-1.5 font-mono text-xs";
+    pub const CODE_TITLED: &'static str = "my-4 [&_pre]:mt-0 [&_pre]:rounded-t-none";
+
     // Theme-specific code block classes
-    pub const THEME_DEFAULT: &'static str = "bg-gray-50 dark:bg-gray-900";
+    pub const THEME_DEFAULT: &'static str =
+        "bg-[color:var(--md-pre-bg,#f9fafb)] dark:bg-[color:var(--md-pre-bg,#111827)]";
     pub const THEME_DARK: &'static str = "bg-gray-900 text-gray-100";
     pub const THEME_LIGHT: &'static str = "bg-white text-gray-900 border";
     pub const THEME_GITHUB: &'static str =
         "bg-[#f6f8fa] dark:bg-[#0d1117] text-[#24292f] dark:text-[#f0f6fc]";
     pub const THEME_MONOKAI: &'static str = "bg-[#272822] text-[#f8f8f2]";
+
+    // Theme-specific code title bar classes
+    pub const TITLE_THEME_DEFAULT: &'static str =
+        "bg-[color:var(--md-code-bg,#f3f4f6)] dark:bg-[color:var(--md-code-bg,#1f2937)] text-gray-600 dark:text-gray-300";
+    pub const TITLE_THEME_DARK: &'static str = "bg-gray-800 text-gray-300";
+    pub const TITLE_THEME_LIGHT: &'static str = "bg-gray-50 text-gray-600";
+    pub const TITLE_THEME_GITHUB: &'static str =
+        "bg-[#eaeef2] dark:bg-[#161b22] text-[#57606a] dark:text-[#8b949e]";
+    pub const TITLE_THEME_MONOKAI: &'static str = "bg-[#1e1f1c] text-[#a59f85]";
+
+    // Code block classes for a theme paired as the dark half (`with_code_theme_pair`),
+    // spelled out so Tailwind's content scan generates them
+    pub const DARK_THEME_DEFAULT: &'static str = "dark:bg-[color:var(--md-pre-bg,#111827)]";
+    pub const DARK_THEME_DARK: &'static str = "dark:bg-gray-900 dark:text-gray-100";
+    pub const DARK_THEME_LIGHT: &'static str = "dark:bg-white dark:text-gray-900 dark:border";
+    pub const DARK_THEME_GITHUB: &'static str = "dark:bg-[#0d1117] dark:text-[#f0f6fc]";
+    pub const DARK_THEME_MONOKAI: &'static str = "dark:bg-[#272822] dark:text-[#f8f8f2]";
+
+    // Code title bar classes for a theme paired as the dark half
+    pub const DARK_TITLE_THEME_DEFAULT: &'static str =
+        "dark:bg-[color:var(--md-code-bg,#1f2937)] dark:text-gray-300";
+    pub const DARK_TITLE_THEME_DARK: &'static str = "dark:bg-gray-800 dark:text-gray-300";
+    pub const DARK_TITLE_THEME_LIGHT: &'static str = "dark:bg-gray-50 dark:text-gray-600";
+    pub const DARK_TITLE_THEME_GITHUB: &'static str = "dark:bg-[#161b22] dark:text-[#8b949e]";
+    pub const DARK_TITLE_THEME_MONOKAI: &'static str = "dark:bg-[#1e1f1c] dark:text-[#a59f85]";
+}
+
+/// Stable semantic class names emitted next to the utilities in explicit-classes mode.
+///
+/// Unlike [`MarkdownClasses`], these never change with themes, so downstream CSS and
+/// tests can rely on them (e.g. `.md-code-block`).
+pub struct MarkdownClassHooks;
+
+impl MarkdownClassHooks {
+    pub const H1: &'static str = "md-h1";
+    pub const H2: &'static str = "md-h2";
+    pub const H3: &'static str = "md-h3";
+    pub const H4: &'static str = "md-h4";
+    pub const H5: &'static str = "md-h5";
+    pub const H6: &'static str = "md-h6";
+
+    pub const PARAGRAPH: &'static str = "md-p";
+    pub const BLOCKQUOTE: &'static str = "md-blockquote";
+    pub const ALERT: &'static str = "md-alert";
+    pub const ALERT_TITLE: &'static str = "md-alert-title";
+
+    pub const INLINE_CODE: &'static str = "md-code";
+    pub const CODE_BLOCK: &'static str = "md-code-block";
+    pub const CODE_BLOCK_CODE: &'static str = "md-code-block-code";
+    pub const CODE_TITLE: &'static str = "md-code-title";
+    pub const CODE_TITLED: &'static str = "md-code-titled";
+    pub const MERMAID: &'static str = "md-mermaid";
+    pub const CODE_BLOCK_WRAPPER: &'static str = "md-code-block-wrapper";
+    pub const CODE_COPY_BUTTON: &'static str = "md-code-copy";
+    pub const CODE_EXPAND_BUTTON: &'static str = "md-code-expand";
+    pub const CODE_MODAL: &'static str = "md-code-modal";
+    pub const CODE_MODAL_CLOSE: &'static str = "md-code-modal-close";
+    pub const CODE_MODAL_PRE: &'static str = "md-code-modal-pre";
+    pub const CODE_CLAMP: &'static str = "md-code-clamp";
+    pub const CODE_CLAMP_TOGGLE: &'static str = "md-code-clamp-toggle";
+    pub const CODE_CLAMPED: &'static str = "md-code-clamped";
+    pub const CODE_CLAMP_FADE: &'static str = "md-code-clamp-fade";
+    pub const CODE_CLAMP_BUTTON: &'static str = "md-code-clamp-button";
+    pub const CODE_CLAMP_EXPAND: &'static str = "md-code-clamp-expand";
+    pub const CODE_CLAMP_COLLAPSE: &'static str = "md-code-clamp-collapse";
+    pub const CODE_LINE_HIGHLIGHT: &'static str = "md-code-line-highlight";
+    pub const CODE_DIFF_ADDED: &'static str = "md-code-diff-added";
+    pub const CODE_DIFF_REMOVED: &'static str = "md-code-diff-removed";
+    pub const CODE_DIFF_HUNK: &'static str = "md-code-diff-hunk";
+
+    pub const UL: &'static str = "md-ul";
+    pub const OL: &'static str = "md-ol";
+    pub const LI: &'static str = "md-li";
+
+    pub const LINK: &'static str = "md-link";
+    pub const IMAGE: &'static str = "md-image";
+    pub const WIKILINK_UNRESOLVED: &'static str = "md-wikilink-unresolved";
+    pub const EMBED: &'static str = "md-embed";
+
+    pub const TABLE: &'static str = "md-table";
+    pub const THEAD: &'static str = "md-thead";
+    pub const TR: &'static str = "md-tr";
+    pub const TD: &'static str = "md-td";
+    pub const TH: &'static str = "md-th";
+
+    pub const HR: &'static str = "md-hr";
+    pub const CHECKBOX: &'static str = "md-task-checkbox";
+    pub const HEADING_ANCHOR: &'static str = "md-heading-anchor";
+    pub const TOC: &'static str = "md-toc";
+    pub const TOC_ITEM: &'static str = "md-toc-item";
+    pub const TOC_LINK: &'static str = "md-toc-link";
+    pub const TOC_NESTED: &'static str = "md-toc-nested";
+    pub const ANCHOR_TARGET: &'static str = "md-anchor-target";
+    pub const TRUNCATION: &'static str = "md-truncation";
+    pub const FIGURE: &'static str = "md-figure";
+    pub const FIGCAPTION: &'static str = "md-figcaption";
+    pub const LOF: &'static str = "md-lof";
+    pub const CONTAINER: &'static str = "md-container";
+    pub const DETAILS: &'static str = "md-details";
+    pub const SUMMARY: &'static str = "md-summary";
+    pub const TABS: &'static str = "md-tabs";
+    pub const TAB_LIST: &'static str = "md-tab-list";
+    pub const TAB: &'static str = "md-tab";
+    pub const TAB_PANEL: &'static str = "md-tab-panel";
+    pub const CITATION: &'static str = "md-citation";
+    pub const MARK: &'static str = "md-mark";
+    pub const BIBLIOGRAPHY: &'static str = "md-bibliography";
+    pub const UNSUPPORTED: &'static str = "md-unsupported";
+    pub const ANNOTATED_BLOCK: &'static str = "md-annotated";
+    pub const ANNOTATION: &'static str = "md-annotation";
+    pub const DIAGNOSTIC: &'static str = "md-diagnostic";
+    pub const DIFF_ADDED: &'static str = "md-diff-added";
+    pub const DIFF_REMOVED: &'static str = "md-diff-removed";
+    pub const DIFF_WORD_ADDED: &'static str = "md-diff-word-added";
+    pub const DIFF_WORD_REMOVED: &'static str = "md-diff-word-removed";
+
+    pub const MATH_INLINE: &'static str = "md-math-inline";
+    pub const MATH_DISPLAY: &'static str = "md-math-display";
+
+    pub const DL: &'static str = "md-dl";
+    pub const DT: &'static str = "md-dt";
+    pub const DD: &'static str = "md-dd";
+
+    pub const SUP: &'static str = "md-sup";
+    pub const SUB: &'static str = "md-sub";
+
+    pub const EM: &'static str = "md-em";
+    pub const STRONG: &'static str = "md-strong";
+    pub const DEL: &'static str = "md-del";
+    pub const INS: &'static str = "md-ins";
+    pub const KBD: &'static str = "md-kbd";
+    pub const SPOILER: &'static str = "md-spoiler";
+    pub const SPOILER_TEXT: &'static str = "md-spoiler-text";
+    pub const UNDERLINE: &'static str = "md-underline";
+    pub const EMOJI: &'static str = "md-emoji";
+
+    pub const FOOTNOTE_REF: &'static str = "md-footnote-ref";
+    pub const FOOTNOTE_DEF: &'static str = "md-footnote-def";
+    pub const FOOTNOTES: &'static str = "md-footnotes";
+    pub const FOOTNOTE_LIST: &'static str = "md-footnote-list";
+    pub const FOOTNOTE_BACKREF: &'static str = "md-footnote-backref";
+    pub const RAW_HTML_BLOCK: &'static str = "md-raw-html";
+    pub const INLINE_HTML: &'static str = "md-inline-html";
+}
+
+/// Per-element utility classes, defaulting to the [`MarkdownClasses`] constants, so one
+/// element can be restyled at runtime:
+///
+/// ```rust,ignore
+/// let classes = MarkdownClassMap {
+///     blockquote: "border-l-4 border-amber-500 pl-4 italic".into(),
+///     ..Default::default()
+/// };
+/// let options = MarkdownOptions::new().with_class_map(classes);
+/// ```
+///
+/// In explicit-classes mode every element uses its entry. In prose mode entries changed
+/// from their default are added to the element, on top of the prose styling.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MarkdownClassMap {
+    pub h1: Cow<'static, str>,
+    pub h2: Cow<'static, str>,
+    pub h3: Cow<'static, str>,
+    pub h4: Cow<'static, str>,
+    pub h5: Cow<'static, str>,
+    pub h6: Cow<'static, str>,
+    pub paragraph: Cow<'static, str>,
+    pub blockquote: Cow<'static, str>,
+    pub alert: Cow<'static, str>,
+    pub alert_title: Cow<'static, str>,
+    pub inline_code: Cow<'static, str>,
+    pub code_block: Cow<'static, str>,
+    pub code_block_code: Cow<'static, str>,
+    pub code_title: Cow<'static, str>,
+    pub mermaid: Cow<'static, str>,
+    pub ul: Cow<'static, str>,
+    pub ol: Cow<'static, str>,
+    pub li: Cow<'static, str>,
+    pub em: Cow<'static, str>,
+    pub strong: Cow<'static, str>,
+    pub del: Cow<'static, str>,
+    pub mark: Cow<'static, str>,
+    pub ins: Cow<'static, str>,
+    pub kbd: Cow<'static, str>,
+    pub sup: Cow<'static, str>,
+    pub sub: Cow<'static, str>,
+    pub link: Cow<'static, str>,
+    pub image: Cow<'static, str>,
+    pub table: Cow<'static, str>,
+    pub thead: Cow<'static, str>,
+    pub tr: Cow<'static, str>,
+    pub th: Cow<'static, str>,
+    pub td: Cow<'static, str>,
+    pub hr: Cow<'static, str>,
+    pub checkbox: Cow<'static, str>,
+    pub heading_anchor: Cow<'static, str>,
+    pub truncation: Cow<'static, str>,
+    pub math_inline: Cow<'static, str>,
+    pub math_display: Cow<'static, str>,
+    pub dl: Cow<'static, str>,
+    pub dt: Cow<'static, str>,
+    pub dd: Cow<'static, str>,
+    pub footnote_ref: Cow<'static, str>,
+    pub footnote_def: Cow<'static, str>,
+    pub footnotes: Cow<'static, str>,
+    pub footnote_list: Cow<'static, str>,
+    pub footnote_backref: Cow<'static, str>,
+    pub raw_html_block: Cow<'static, str>,
+    pub figure: Cow<'static, str>,
+    pub figcaption: Cow<'static, str>,
+    pub container: Cow<'static, str>,
+    pub details: Cow<'static, str>,
+    pub summary: Cow<'static, str>,
+    pub citation: Cow<'static, str>,
+    pub bibliography: Cow<'static, str>,
+    pub unsupported: Cow<'static, str>,
+    pub annotated_block: Cow<'static, str>,
+    pub annotation: Cow<'static, str>,
+    pub diagnostic: Cow<'static, str>,
+    pub spoiler: Cow<'static, str>,
+    pub spoiler_text: Cow<'static, str>,
+    pub underline: Cow<'static, str>,
+    pub tabs: Cow<'static, str>,
+    pub tab_list: Cow<'static, str>,
+    pub tab: Cow<'static, str>,
+    pub tab_panel: Cow<'static, str>,
+    pub code_block_wrapper: Cow<'static, str>,
+    pub code_copy_button: Cow<'static, str>,
+    pub code_expand_button: Cow<'static, str>,
+    pub code_modal: Cow<'static, str>,
+    pub code_modal_close: Cow<'static, str>,
+    pub code_modal_pre: Cow<'static, str>,
+    pub code_clamp: Cow<'static, str>,
+    pub code_clamp_toggle: Cow<'static, str>,
+    pub code_clamped: Cow<'static, str>,
+    pub code_clamp_fade: Cow<'static, str>,
+    pub code_clamp_button: Cow<'static, str>,
+    pub code_clamp_expand: Cow<'static, str>,
+    pub code_clamp_collapse: Cow<'static, str>,
+    pub wikilink_unresolved: Cow<'static, str>,
+    pub embed: Cow<'static, str>,
+    pub anchor_target: Cow<'static, str>,
+    pub code_line_highlight: Cow<'static, str>,
+    pub code_diff_added: Cow<'static, str>,
+    pub code_diff_removed: Cow<'static, str>,
+    pub code_diff_hunk: Cow<'static, str>,
+    pub code_titled: Cow<'static, str>,
+    pub emoji: Cow<'static, str>,
+    pub toc: Cow<'static, str>,
+    pub toc_item: Cow<'static, str>,
+    pub toc_link: Cow<'static, str>,
+    pub toc_nested: Cow<'static, str>,
+    pub lof: Cow<'static, str>,
+    pub diff_added: Cow<'static, str>,
+    pub diff_removed: Cow<'static, str>,
+    pub diff_word_added: Cow<'static, str>,
+    pub diff_word_removed: Cow<'static, str>,
+}
+
+impl Default for MarkdownClassMap {
+    fn default() -> Self {
+        Self {
+            h1: MarkdownClasses::H1.into(),
+            h2: MarkdownClasses::H2.into(),
+            h3: MarkdownClasses::H3.into(),
+            h4: MarkdownClasses::H4.into(),
+            h5: MarkdownClasses::H5.into(),
+            h6: MarkdownClasses::H6.into(),
+            paragraph: MarkdownClasses::PARAGRAPH.into(),
+            blockquote: MarkdownClasses::BLOCKQUOTE.into(),
+            alert: MarkdownClasses::ALERT.into(),
+            alert_title: MarkdownClasses::ALERT_TITLE.into(),
+            inline_code: MarkdownClasses::INLINE_CODE.into(),
+            code_block: MarkdownClasses::CODE_BLOCK.into(),
+            code_block_code: MarkdownClasses::CODE_BLOCK_CODE.into(),
+            code_title: MarkdownClasses::CODE_TITLE.into(),
+            mermaid: MarkdownClasses::MERMAID.into(),
+            ul: MarkdownClasses::UL.into(),
+            ol: MarkdownClasses::OL.into(),
+            li: MarkdownClasses::LI.into(),
+            em: MarkdownClasses::EM.into(),
+            strong: MarkdownClasses::STRONG.into(),
+            del: MarkdownClasses::DEL.into(),
+            mark: MarkdownClasses::MARK.into(),
+            ins: MarkdownClasses::INS.into(),
+            kbd: MarkdownClasses::KBD.into(),
+            sup: MarkdownClasses::SUP.into(),
+            sub: MarkdownClasses::SUB.into(),
+            link: MarkdownClasses::LINK.into(),
+            image: MarkdownClasses::IMAGE.into(),
+            table: MarkdownClasses::TABLE.into(),
+            thead: MarkdownClasses::THEAD.into(),
+            tr: MarkdownClasses::TR.into(),
+            th: MarkdownClasses::TH.into(),
+            td: MarkdownClasses::TD.into(),
+            hr: MarkdownClasses::HR.into(),
+            checkbox: MarkdownClasses::CHECKBOX.into(),
+            heading_anchor: MarkdownClasses::HEADING_ANCHOR.into(),
+            truncation: MarkdownClasses::TRUNCATION.into(),
+            math_inline: MarkdownClasses::MATH_INLINE.into(),
+            math_display: MarkdownClasses::MATH_DISPLAY.into(),
+            dl: MarkdownClasses::DL.into(),
+            dt: MarkdownClasses::DT.into(),
+            dd: MarkdownClasses::DD.into(),
+            footnote_ref: MarkdownClasses::FOOTNOTE_REF.into(),
+            footnote_def: MarkdownClasses::FOOTNOTE_DEF.into(),
+            footnotes: MarkdownClasses::FOOTNOTES.into(),
+            footnote_list: MarkdownClasses::FOOTNOTE_LIST.into(),
+            footnote_backref: MarkdownClasses::FOOTNOTE_BACKREF.into(),
+            raw_html_block: MarkdownClasses::RAW_HTML_BLOCK.into(),
+            figure: MarkdownClasses::FIGURE.into(),
+            figcaption: MarkdownClasses::FIGCAPTION.into(),
+            container: MarkdownClasses::CONTAINER.into(),
+            details: MarkdownClasses::DETAILS.into(),
+            summary: MarkdownClasses::SUMMARY.into(),
+            citation: MarkdownClasses::CITATION.into(),
+            bibliography: MarkdownClasses::BIBLIOGRAPHY.into(),
+            unsupported: MarkdownClasses::UNSUPPORTED.into(),
+            annotated_block: MarkdownClasses::ANNOTATED_BLOCK.into(),
+            annotation: MarkdownClasses::ANNOTATION.into(),
+            diagnostic: MarkdownClasses::DIAGNOSTIC.into(),
+            spoiler: MarkdownClasses::SPOILER.into(),
+            spoiler_text: MarkdownClasses::SPOILER_TEXT.into(),
+            underline: MarkdownClasses::UNDERLINE.into(),
+            tabs: MarkdownClasses::TABS.into(),
+            tab_list: MarkdownClasses::TAB_LIST.into(),
+            tab: MarkdownClasses::TAB.into(),
+            tab_panel: MarkdownClasses::TAB_PANEL.into(),
+            code_block_wrapper: MarkdownClasses::CODE_BLOCK_WRAPPER.into(),
+            code_copy_button: MarkdownClasses::CODE_COPY_BUTTON.into(),
+            code_expand_button: MarkdownClasses::CODE_EXPAND_BUTTON.into(),
+            code_modal: MarkdownClasses::CODE_MODAL.into(),
+            code_modal_close: MarkdownClasses::CODE_MODAL_CLOSE.into(),
+            code_modal_pre: MarkdownClasses::CODE_MODAL_PRE.into(),
+            code_clamp: MarkdownClasses::CODE_CLAMP.into(),
+            code_clamp_toggle: MarkdownClasses::CODE_CLAMP_TOGGLE.into(),
+            code_clamped: MarkdownClasses::CODE_CLAMPED.into(),
+            code_clamp_fade: MarkdownClasses::CODE_CLAMP_FADE.into(),
+            code_clamp_button: MarkdownClasses::CODE_CLAMP_BUTTON.into(),
+            code_clamp_expand: MarkdownClasses::CODE_CLAMP_EXPAND.into(),
+            code_clamp_collapse: MarkdownClasses::CODE_CLAMP_COLLAPSE.into(),
+            wikilink_unresolved: MarkdownClasses::WIKILINK_UNRESOLVED.into(),
+            embed: MarkdownClasses::EMBED.into(),
+            anchor_target: MarkdownClasses::ANCHOR_TARGET.into(),
+            code_line_highlight: MarkdownClasses::CODE_LINE_HIGHLIGHT.into(),
+            code_diff_added: MarkdownClasses::CODE_DIFF_ADDED.into(),
+            code_diff_removed: MarkdownClasses::CODE_DIFF_REMOVED.into(),
+            code_diff_hunk: MarkdownClasses::CODE_DIFF_HUNK.into(),
+            code_titled: MarkdownClasses::CODE_TITLED.into(),
+            emoji: MarkdownClasses::EMOJI.into(),
+            toc: MarkdownClasses::TOC.into(),
+            toc_item: MarkdownClasses::TOC_ITEM.into(),
+            toc_link: MarkdownClasses::TOC_LINK.into(),
+            toc_nested: MarkdownClasses::TOC_NESTED.into(),
+            lof: MarkdownClasses::LOF.into(),
+            diff_added: MarkdownClasses::DIFF_ADDED.into(),
+            diff_removed: MarkdownClasses::DIFF_REMOVED.into(),
+            diff_word_added: MarkdownClasses::DIFF_WORD_ADDED.into(),
+            diff_word_removed: MarkdownClasses::DIFF_WORD_REMOVED.into(),
+        }
+    }
+}
+
+impl MarkdownClassMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Bundled looks swapping the wrapper classes and the whole [`MarkdownClassMap`] at once.
+///
+/// Every preset but `Prose` styles elements directly rather than through the Tailwind
+/// typography plugin, so selecting one turns on explicit classes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarkdownTheme {
+    /// Tailwind `prose` with the crate's color tweaks
+    #[default]
+    Prose,
+    /// GitHub's README styling
+    GitHub,
+    /// Notion's page styling: quiet headings, muted links, red inline code
+    Notion,
+    /// DaisyUI component and semantic color classes (`link`, `table`, `bg-base-200`), so
+    /// the output follows the active DaisyUI theme without the typography plugin
+    Daisy,
+    /// Spacing and typography only, inheriting colors from the page
+    Minimal,
+}
+
+impl MarkdownTheme {
+    /// Classes on the `<Markdown>` wrapper
+    pub fn wrapper_classes(&self) -> &'static str {
+        match self {
+            MarkdownTheme::Prose => get_enhanced_prose_classes(),
+            MarkdownTheme::GitHub => {
+                "leptos-mdx-content text-base leading-normal text-[#1f2328] dark:text-[#f0f6fc]"
+            }
+            MarkdownTheme::Notion => {
+                "leptos-mdx-content text-base leading-relaxed text-[#37352f] dark:text-[#ffffffcf]"
+            }
+            MarkdownTheme::Daisy => "leptos-mdx-content text-base-content",
+            MarkdownTheme::Minimal => "leptos-mdx-content leading-relaxed",
+        }
+    }
+
+    /// Code block theme the preset is designed around, if any
+    pub fn code_theme(&self) -> Option<CodeBlockTheme> {
+        match self {
+            MarkdownTheme::GitHub => Some(CodeBlockTheme::GitHub),
+            MarkdownTheme::Prose
+            | MarkdownTheme::Notion
+            | MarkdownTheme::Daisy
+            | MarkdownTheme::Minimal => None,
+        }
+    }
+
+    /// Element classes of the preset
+    pub fn class_map(&self) -> MarkdownClassMap {
+        match self {
+            MarkdownTheme::Prose => MarkdownClassMap::default(),
+            MarkdownTheme::GitHub => MarkdownClassMap {
+                h1: "text-[2em] font-semibold leading-tight mt-6 mb-4 pb-[.3em] border-b border-[#d1d9e0] dark:border-[#3d444d] first:mt-0".into(),
+                h2: "text-[1.5em] font-semibold leading-tight mt-6 mb-4 pb-[.3em] border-b border-[#d1d9e0] dark:border-[#3d444d]".into(),
+                h3: "text-[1.25em] font-semibold leading-tight mt-6 mb-4".into(),
+                h4: "text-base font-semibold leading-tight mt-6 mb-4".into(),
+                h5: "text-sm font-semibold leading-tight mt-6 mb-4".into(),
+                h6: "text-[.85em] font-semibold leading-tight mt-6 mb-4 text-[#59636e] dark:text-[#9198a1]".into(),
+                paragraph: "mt-0 mb-4".into(),
+                blockquote: "mb-4 px-4 border-l-[.25em] border-[#d1d9e0] dark:border-[#3d444d] text-[#59636e] dark:text-[#9198a1]".into(),
+                inline_code: "px-[.4em] py-[.2em] rounded-md bg-[#818b981f] dark:bg-[#656c7633] text-[85%] font-mono".into(),
+                code_block: "p-4 mb-4 rounded-md text-[85%] leading-[1.45]".into(),
+                code_block_code: "font-mono".into(),
+                ul: "list-disc pl-8 mb-4".into(),
+                ol: "list-decimal pl-8 mb-4".into(),
+                li: "mt-1".into(),
+                link: "text-[#0969da] dark:text-[#4493f8] hover:underline".into(),
+                image: "max-w-full".into(),
+                table: "block w-max max-w-full overflow-auto mb-4 border-collapse".into(),
+                thead: "".into(),
+                tr: "bg-white dark:bg-[#0d1117] border-t border-[#d1d9e0] dark:border-[#3d444d] even:bg-[#f6f8fa] dark:even:bg-[#151b23]".into(),
+                th: "px-[13px] py-1.5 border border-[#d1d9e0] dark:border-[#3d444d] font-semibold".into(),
+                td: "px-[13px] py-1.5 border border-[#d1d9e0] dark:border-[#3d444d]".into(),
+                hr: "h-[.25em] my-6 p-0 border-0 bg-[#d1d9e0] dark:bg-[#3d444d]".into(),
+                ..MarkdownClassMap::default()
+            },
+            MarkdownTheme::Notion => MarkdownClassMap {
+                h1: "text-[1.875em] font-bold mt-8 mb-1 first:mt-0".into(),
+                h2: "text-[1.5em] font-semibold mt-6 mb-px".into(),
+                h3: "text-[1.25em] font-semibold mt-4 mb-px".into(),
+                h4: "text-base font-semibold mt-3 mb-px".into(),
+                h5: "text-base font-semibold mt-3 mb-px".into(),
+                h6: "text-base font-semibold mt-3 mb-px".into(),
+                paragraph: "my-1 py-[3px]".into(),
+                blockquote: "my-1 px-[14px] py-[3px] border-l-[3px] border-current text-[1.2em]".into(),
+                inline_code: "px-[.4em] py-[.2em] rounded bg-[#87837826] text-[#eb5757] text-[85%] font-mono".into(),
+                code_block: "my-1 p-8 rounded bg-[#f7f6f3] dark:bg-[#ffffff08] text-[85%]".into(),
+                code_block_code: "font-mono".into(),
+                ul: "list-disc pl-6 my-1".into(),
+                ol: "list-decimal pl-6 my-1".into(),
+                li: "py-[3px]".into(),
+                link: "underline decoration-[#37352f66] dark:decoration-[#ffffff66] underline-offset-2 opacity-70 hover:opacity-100".into(),
+                image: "max-w-full rounded my-1".into(),
+                table: "my-1 border-collapse".into(),
+                thead: "".into(),
+                tr: "".into(),
+                th: "px-2 py-1.5 border border-[#e9e9e7] dark:border-[#ffffff21] bg-[#f7f6f3] dark:bg-[#ffffff08] font-medium text-left".into(),
+                td: "px-2 py-1.5 border border-[#e9e9e7] dark:border-[#ffffff21]".into(),
+                hr: "my-2 border-0 border-t border-[#37352f29] dark:border-[#ffffff21]".into(),
+                ..MarkdownClassMap::default()
+            },
+            MarkdownTheme::Daisy => MarkdownClassMap {
+                h1: "text-4xl font-bold mt-6 mb-4 first:mt-0".into(),
+                h2: "text-3xl font-bold mt-5 mb-3".into(),
+                h3: "text-2xl font-semibold mt-4 mb-2".into(),
+                h4: "text-xl font-semibold mt-3 mb-2".into(),
+                h5: "text-lg font-semibold mt-3 mb-2".into(),
+                h6: "text-base font-semibold text-base-content/70 mt-3 mb-2".into(),
+                paragraph: "mb-4 leading-relaxed".into(),
+                blockquote: "my-4 pl-4 border-l-4 border-primary italic text-base-content/80".into(),
+                inline_code: "px-1.5 py-0.5 rounded bg-base-200 font-mono text-sm".into(),
+                code_block: "my-4 p-4 rounded-box bg-base-200".into(),
+                code_block_code: "font-mono text-sm".into(),
+                ul: "list-disc pl-6 mb-4 space-y-1".into(),
+                ol: "list-decimal pl-6 mb-4 space-y-1".into(),
+                li: "".into(),
+                link: "link link-primary".into(),
+                image: "max-w-full rounded-box my-4".into(),
+                table: "table table-zebra my-4".into(),
+                thead: "".into(),
+                tr: "".into(),
+                th: "".into(),
+                td: "".into(),
+                hr: "divider".into(),
+                checkbox: "checkbox checkbox-sm checkbox-primary mr-2".into(),
+                ..MarkdownClassMap::default()
+            },
+            MarkdownTheme::Minimal => MarkdownClassMap {
+                h1: "text-2xl font-bold mt-8 mb-4 first:mt-0".into(),
+                h2: "text-xl font-bold mt-8 mb-3".into(),
+                h3: "text-lg font-semibold mt-6 mb-2".into(),
+                h4: "font-semibold mt-4 mb-2".into(),
+                h5: "font-semibold mt-4 mb-2".into(),
+                h6: "font-semibold mt-4 mb-2".into(),
+                paragraph: "my-4".into(),
+                blockquote: "my-4 pl-4 border-l-2 border-current opacity-80".into(),
+                inline_code: "font-mono text-[0.9em]".into(),
+                code_block: "my-4 p-4 rounded border border-gray-200 dark:border-gray-800".into(),
+                code_block_code: "font-mono text-sm".into(),
+                ul: "list-disc pl-6 my-4".into(),
+                ol: "list-decimal pl-6 my-4".into(),
+                li: "my-1".into(),
+                link: "underline underline-offset-2".into(),
+                image: "max-w-full my-4".into(),
+                table: "my-4 border-collapse".into(),
+                thead: "".into(),
+                tr: "".into(),
+                th: "px-3 py-2 border-b-2 border-gray-300 dark:border-gray-700 text-left font-semibold".into(),
+                td: "px-3 py-2 border-b border-gray-200 dark:border-gray-800".into(),
+                hr: "my-8 border-0 border-t border-gray-200 dark:border-gray-800".into(),
+                ..MarkdownClassMap::default()
+            },
+        }
+    }
+}
+
+/// Get kind-specific classes for alerts
+pub fn get_alert_classes(kind: AlertKind) -> &'static str {
+    match kind {
+        AlertKind::Note => MarkdownClasses::ALERT_NOTE,
+        AlertKind::Tip => MarkdownClasses::ALERT_TIP,
+        AlertKind::Important => MarkdownClasses::ALERT_IMPORTANT,
+        AlertKind::Warning => MarkdownClasses::ALERT_WARNING,
+        AlertKind::Caution => MarkdownClasses::ALERT_CAUTION,
+    }
 }
 
 /// Get theme-specific classes for code blocks
@@ -192,14 +2420,54 @@ pub fn get_code_theme_classes(theme: &CodeBlockTheme) -> &'static str {
     }
 }
 
+/// Get theme-specific classes for code block title bars
+pub fn get_code_title_theme_classes(theme: &CodeBlockTheme) -> &'static str {
+    match theme {
+        CodeBlockTheme::Default => MarkdownClasses::TITLE_THEME_DEFAULT,
+        CodeBlockTheme::Dark => MarkdownClasses::TITLE_THEME_DARK,
+        CodeBlockTheme::Light => MarkdownClasses::TITLE_THEME_LIGHT,
+        CodeBlockTheme::GitHub => MarkdownClasses::TITLE_THEME_GITHUB,
+        CodeBlockTheme::Monokai => MarkdownClasses::TITLE_THEME_MONOKAI,
+    }
+}
+
+/// Get `dark:` classes for code blocks when `theme` is paired as the dark theme
+pub fn get_dark_code_theme_classes(theme: &CodeBlockTheme) -> &'static str {
+    match theme {
+        CodeBlockTheme::Default => MarkdownClasses::DARK_THEME_DEFAULT,
+        CodeBlockTheme::Dark => MarkdownClasses::DARK_THEME_DARK,
+        CodeBlockTheme::Light => MarkdownClasses::DARK_THEME_LIGHT,
+        CodeBlockTheme::GitHub => MarkdownClasses::DARK_THEME_GITHUB,
+        CodeBlockTheme::Monokai => MarkdownClasses::DARK_THEME_MONOKAI,
+    }
+}
+
+/// Get `dark:` classes for code block title bars when `theme` is paired as the dark theme
+pub fn get_dark_code_title_theme_classes(theme: &CodeBlockTheme) -> &'static str {
+    match theme {
+        CodeBlockTheme::Default => MarkdownClasses::DARK_TITLE_THEME_DEFAULT,
+        CodeBlockTheme::Dark => MarkdownClasses::DARK_TITLE_THEME_DARK,
+        CodeBlockTheme::Light => MarkdownClasses::DARK_TITLE_THEME_LIGHT,
+        CodeBlockTheme::GitHub => MarkdownClasses::DARK_TITLE_THEME_GITHUB,
+        CodeBlockTheme::Monokai => MarkdownClasses::DARK_TITLE_THEME_MONOKAI,
+    }
+}
+
 /// Enhanced Tailwind prose configuration for better markdown styling
 pub fn get_enhanced_prose_classes() -> &'static str {
-    "leptos-mdx-content prose prose-gray max-w-none dark:prose-invert prose-headings:font-bold prose-headings:text-gray-900 dark:prose-headings:text-gray-100 prose-p:text-gray-700 dark:prose-p:text-gray-300 prose-a:text-blue-600 dark:prose-a:text-blue-400 prose-strong:text-gray-900 dark:prose-strong:text-gray-100 prose-code:text-gray-800 dark:prose-code:text-gray-200 prose-pre:bg-gray-50 dark:prose-pre:bg-gray-900"
+    "leptos-mdx-content prose prose-gray max-w-none dark:prose-invert prose-headings:font-bold prose-headings:text-[color:var(--md-heading-color,#111827)] dark:prose-headings:text-[color:var(--md-heading-color,#f3f4f6)] prose-p:text-[color:var(--md-text-color,#374151)] dark:prose-p:text-[color:var(--md-text-color,#d1d5db)] prose-a:text-[color:var(--md-link-color,#2563eb)] dark:prose-a:text-[color:var(--md-link-color,#60a5fa)] prose-strong:text-[color:var(--md-heading-color,#111827)] dark:prose-strong:text-[color:var(--md-heading-color,#f3f4f6)] prose-code:text-[color:var(--md-code-color,#1f2937)] dark:prose-code:text-[color:var(--md-code-color,#e5e7eb)] prose-pre:bg-[color:var(--md-pre-bg,#f9fafb)] dark:prose-pre:bg-[color:var(--md-pre-bg,#111827)]"
+}
+
+/// Stylesheet replicating the default look for projects without Tailwind, scoped to
+/// `.leptos-mdx-content`. Selectors have zero specificity, so any class overrides them.
+pub fn get_markdown_css() -> &'static str {
+    include_str!("styles.css")
 }
 
-/// Placeholder component - Tailwind handles all styling
+/// `<style>` block with [`get_markdown_css`], for projects not using Tailwind and its
+/// typography plugin. Pair it with `without_prose()`; with Tailwind it's harmless but
+/// unnecessary.
 #[component]
 pub fn MarkdownStyles() -> impl IntoView {
-    // With Tailwind 4, no custom styles needed
-    ""
+    view! { <style inner_html=get_markdown_css()></style> }
 }
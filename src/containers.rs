@@ -0,0 +1,274 @@
+use crate::tabs::tab_title;
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::collections::VecDeque;
+
+/// A `:::` line opening or closing a container
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ContainerMarker<'a> {
+    /// `:::name rest of line`
+    Open { name: &'a str, title: &'a str },
+    /// `:::`
+    Close,
+}
+
+/// The marker a paragraph line consists of, if any
+fn line_marker<'a>(line: &'a [Event]) -> Option<ContainerMarker<'a>> {
+    let [Event::Text(text)] = line else {
+        return None;
+    };
+    let text = text.trim();
+    let rest = text.trim_start_matches(':');
+    if text.len() - rest.len() < 3 {
+        return None;
+    }
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return Some(ContainerMarker::Close);
+    }
+    let name_len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(rest.len());
+    if name_len == 0 {
+        return None;
+    }
+    Some(ContainerMarker::Open {
+        name: &rest[..name_len],
+        title: rest[name_len..].trim(),
+    })
+}
+
+/// The marker held by a standalone marker paragraph starting `events`
+pub(crate) fn container_marker<'a>(events: &'a [Event]) -> Option<ContainerMarker<'a>> {
+    match events {
+        [Event::Start(Tag::Paragraph), line @ .., Event::End(TagEnd::Paragraph)] => {
+            line_marker(line)
+        }
+        _ => None,
+    }
+}
+
+/// Index of the paragraph closing the container whose content starts `events`, at the
+/// same nesting level. Nested containers are skipped.
+pub(crate) fn container_close(events: &[Event]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut containers = 0usize;
+    for (i, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(Tag::Paragraph) if depth == 0 => {
+                let marker = events.get(i..i + 3).and_then(container_marker);
+                match marker {
+                    Some(ContainerMarker::Open { .. }) => containers += 1,
+                    Some(ContainerMarker::Close) if containers == 0 => return Some(i),
+                    Some(ContainerMarker::Close) => containers -= 1,
+                    None => {}
+                }
+                depth += 1;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Give every `:::` marker line its own paragraph, and with `tabs` every `== Title` line
+/// directly inside a `:::tabs` container. Without blank lines around them, markers share
+/// a paragraph with the neighbouring text.
+///
+/// A closing `:::` right after a list item or quote line is a lazy continuation of that
+/// block's last paragraph (or of a tight item's text). It is lifted out, with the lines
+/// after it, to just after the blocks nested inside its container.
+pub(crate) fn split_container_markers(events: Vec<Event<'_>>, tabs: bool) -> Vec<Event<'_>> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut events: VecDeque<Event> = events.into();
+    // Names of the containers enclosing the current paragraph, with the block depth they
+    // were opened at
+    let mut open_containers: Vec<(String, usize)> = Vec::new();
+    // Enclosing blocks other than paragraphs, `true` for list items
+    let mut blocks: Vec<bool> = Vec::new();
+    // Lifted lines as a paragraph, with the block depth to put them back at
+    let mut lifted: Vec<(usize, Vec<Event>)> = Vec::new();
+
+    while let Some(event) = events.pop_front() {
+        let paragraph = matches!(event, Event::Start(Tag::Paragraph));
+        let tight_item = !paragraph && blocks.last() == Some(&true) && is_inline(&event);
+        if !paragraph && !tight_item {
+            match event {
+                Event::Start(ref tag) => blocks.push(matches!(tag, Tag::Item)),
+                Event::End(_) => {
+                    blocks.pop();
+                }
+                _ => {}
+            }
+            output.push(event);
+            while lifted
+                .last()
+                .is_some_and(|(depth, _)| blocks.len() <= *depth)
+            {
+                if let Some((_, paragraph)) = lifted.pop() {
+                    for event in paragraph.into_iter().rev() {
+                        events.push_front(event);
+                    }
+                }
+            }
+            continue;
+        }
+        if tight_item {
+            events.push_front(event);
+        }
+
+        // breaks[k] separates lines[k] and lines[k + 1]
+        let mut lines = vec![Vec::new()];
+        let mut breaks = Vec::new();
+        while let Some(inner) = events.pop_front() {
+            match inner {
+                Event::End(TagEnd::Paragraph) if paragraph => break,
+                Event::SoftBreak | Event::HardBreak => {
+                    breaks.push(inner);
+                    lines.push(Vec::new());
+                }
+                other if paragraph || is_inline(&other) => {
+                    if let Some(line) = lines.last_mut() {
+                        line.push(other);
+                    }
+                }
+                other => {
+                    events.push_front(other);
+                    break;
+                }
+            }
+        }
+
+        if let Some(k) = lazy_close(&lines, blocks.len(), &open_containers) {
+            let tail = lines.split_off(k);
+            // The break before the marker goes, the ones after it move along
+            let mut tail_breaks = breaks.split_off(k.saturating_sub(1)).into_iter();
+            if k > 0 {
+                tail_breaks.next();
+            }
+            let mut paragraph = vec![Event::Start(Tag::Paragraph)];
+            for (j, line) in tail.into_iter().enumerate() {
+                paragraph.extend((j > 0).then(|| tail_breaks.next()).flatten());
+                paragraph.extend(line);
+            }
+            paragraph.push(Event::End(TagEnd::Paragraph));
+            let depth = open_containers.last().map_or(0, |(_, depth)| *depth);
+            lifted.push((depth, paragraph));
+            if lines.is_empty() {
+                continue;
+            }
+        }
+
+        if tight_item {
+            let mut breaks = breaks.into_iter();
+            for (k, line) in lines.into_iter().enumerate() {
+                output.extend((k > 0).then(|| breaks.next()).flatten());
+                output.extend(line);
+            }
+            continue;
+        }
+
+        let mut open = false;
+        let mut breaks = breaks.into_iter();
+        for (k, line) in lines.into_iter().enumerate() {
+            let separator = (k > 0).then(|| breaks.next()).flatten();
+            let standalone = match line_marker(&line) {
+                Some(ContainerMarker::Open { name, .. }) => {
+                    open_containers.push((name.to_string(), blocks.len()));
+                    true
+                }
+                Some(ContainerMarker::Close) => {
+                    open_containers.pop();
+                    true
+                }
+                None => {
+                    tabs && open_containers
+                        .last()
+                        .is_some_and(|(name, _)| name == "tabs")
+                        && tab_title(&line).is_some()
+                }
+            };
+            if standalone {
+                if open {
+                    output.push(Event::End(TagEnd::Paragraph));
+                    open = false;
+                }
+                output.push(Event::Start(Tag::Paragraph));
+                output.extend(line);
+                output.push(Event::End(TagEnd::Paragraph));
+                continue;
+            }
+            if open {
+                output.extend(separator);
+            } else {
+                output.push(Event::Start(Tag::Paragraph));
+                open = true;
+            }
+            output.extend(line);
+        }
+        if open {
+            output.push(Event::End(TagEnd::Paragraph));
+        }
+    }
+
+    output
+}
+
+/// Index of the first line closing the innermost container from deeper inside than it
+/// was opened at, unless a container opens first
+fn lazy_close(
+    lines: &[Vec<Event>],
+    depth: usize,
+    open_containers: &[(String, usize)],
+) -> Option<usize> {
+    let (_, opened_at) = open_containers.last()?;
+    if *opened_at >= depth {
+        return None;
+    }
+    // Inline tags spanning lines, a marker inside one stays put
+    let mut nested = 0isize;
+    for (k, line) in lines.iter().enumerate() {
+        match line_marker(line) {
+            Some(ContainerMarker::Open { .. }) => return None,
+            Some(ContainerMarker::Close) if nested == 0 => return Some(k),
+            _ => {}
+        }
+        for event in line {
+            match event {
+                Event::Start(_) => nested += 1,
+                Event::End(_) => nested -= 1,
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// Whether `event` belongs inside a paragraph
+fn is_inline(event: &Event) -> bool {
+    match event {
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Superscript
+                | Tag::Subscript
+                | Tag::Link { .. }
+                | Tag::Image { .. }
+        ),
+        Event::End(tag) => matches!(
+            tag,
+            TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Superscript
+                | TagEnd::Subscript
+                | TagEnd::Link
+                | TagEnd::Image
+        ),
+        Event::Rule | Event::Html(_) => false,
+        _ => true,
+    }
+}
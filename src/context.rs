@@ -0,0 +1,19 @@
+use crate::compat::*;
+use crate::components::MarkdownOptions;
+
+/// Context wrapper so the defaults don't collide with other `MarkdownOptions` in context
+#[derive(Clone, Debug)]
+struct DefaultMarkdownOptions(MarkdownOptions);
+
+/// Set the options every `<Markdown>` below this point uses when it has no `options` prop.
+///
+/// Call it once near the app root for crate-wide defaults (theme, link policy,
+/// sanitization); an explicit `options` prop replaces them entirely.
+pub fn provide_markdown_options(options: MarkdownOptions) {
+    provide_context(DefaultMarkdownOptions(options));
+}
+
+/// The options set by the nearest [`provide_markdown_options`], if any
+pub fn use_markdown_options() -> Option<MarkdownOptions> {
+    use_context::<DefaultMarkdownOptions>().map(|defaults| defaults.0)
+}
@@ -0,0 +1,167 @@
+use crate::compat::*;
+use crate::error::MarkdownError;
+use crate::renderer::{MarkdownRenderer, RenderState};
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+/// One step of an edit script between two sequences
+enum Change<'a, T> {
+    Same(&'a T),
+    Removed(&'a T),
+    Added(&'a T),
+}
+
+/// Largest longest-common-subsequence table [`diff`] builds; a bigger changed run is
+/// reported as removed and added as a whole
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// Edit script turning `old` into `new`; within a replaced run, removals come before
+/// additions
+fn diff<'a, T: PartialEq>(old: &'a [T], new: &'a [T]) -> Vec<Change<'a, T>> {
+    // Unchanged runs at both ends need no table
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_changed = &old[prefix..old.len() - suffix];
+    let new_changed = &new[prefix..new.len() - suffix];
+
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    changes.extend(old[..prefix].iter().map(Change::Same));
+    if old_changed.len().saturating_mul(new_changed.len()) > MAX_DIFF_CELLS {
+        changes.extend(old_changed.iter().map(Change::Removed));
+        changes.extend(new_changed.iter().map(Change::Added));
+    } else {
+        lcs_changes(old_changed, new_changed, &mut changes);
+    }
+    changes.extend(old[old.len() - suffix..].iter().map(Change::Same));
+    changes
+}
+
+/// Longest-common-subsequence edit script of `old` and `new`, pushed to `changes`
+fn lcs_changes<'a, T: PartialEq>(old: &'a [T], new: &'a [T], changes: &mut Vec<Change<'a, T>>) {
+    // lengths[i * width + j]: LCS length of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            changes.push(Change::Same(&old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            changes.push(Change::Removed(&old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(&new[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[i..].iter().map(Change::Removed));
+    changes.extend(new[j..].iter().map(Change::Added));
+}
+
+/// A paragraph of plain text, which can be diffed word by word without losing markup
+fn is_plain_paragraph(block: &[Event]) -> bool {
+    match block {
+        [Event::Start(Tag::Paragraph), inner @ .., Event::End(TagEnd::Paragraph)] => inner
+            .iter()
+            .all(|event| matches!(event, Event::Text(_) | Event::SoftBreak)),
+        _ => false,
+    }
+}
+
+impl MarkdownRenderer {
+    /// Review view of the changes from `old` to `new`: unchanged blocks render as usual,
+    /// removed and added blocks are wrapped in `<del>` / `<ins>`, and a changed plain-text
+    /// paragraph shows word-level insertions and deletions. Paragraphs with inline markup
+    /// are replaced as whole blocks so their formatting survives.
+    pub fn render_diff(&self, old: &str, new: &str) -> Result<AnyView, MarkdownError> {
+        let (old_blocks, new_blocks) = (self.parse_blocks(old)?, self.parse_blocks(new)?);
+        let mut state = self.block_state(old_blocks.iter().chain(&new_blocks));
+        let mut views = Vec::new();
+        let mut removed: Vec<&Vec<Event>> = Vec::new();
+        let mut added: Vec<&Vec<Event>> = Vec::new();
+
+        for change in diff(&old_blocks, &new_blocks) {
+            match change {
+                Change::Same(block) => {
+                    views.extend(self.diff_hunk(&mut removed, &mut added, &mut state));
+                    views.push(self.render_block(block, &mut state));
+                }
+                Change::Removed(block) => removed.push(block),
+                Change::Added(block) => added.push(block),
+            }
+        }
+        views.extend(self.diff_hunk(&mut removed, &mut added, &mut state));
+        Ok(views.into_iter().collect_view().into_any())
+    }
+
+    /// Render one run of replaced blocks, pairing removed and added plain-text paragraphs
+    /// in order for a word-level diff
+    fn diff_hunk(
+        &self,
+        removed: &mut Vec<&Vec<Event>>,
+        added: &mut Vec<&Vec<Event>>,
+        state: &mut RenderState,
+    ) -> Vec<AnyView> {
+        let mut views = Vec::new();
+        let mut added_blocks = std::mem::take(added).into_iter().peekable();
+        for old in std::mem::take(removed) {
+            match added_blocks.peek() {
+                Some(new) if is_plain_paragraph(old) && is_plain_paragraph(new) => {
+                    views.push(self.word_diff(old, new));
+                    added_blocks.next();
+                }
+                _ => views.push(
+                    view! { <del class=self.classes().diff_removed.clone()>{self.render_block(old, state)}</del> }
+                        .into_any(),
+                ),
+            }
+        }
+        views.extend(added_blocks.map(|new| {
+            view! { <ins class=self.classes().diff_added.clone()>{self.render_block(new, state)}</ins> }
+                .into_any()
+        }));
+        views
+    }
+
+    fn word_diff(&self, old: &[Event], new: &[Event]) -> AnyView {
+        let old_text = self.extract_text_content(old);
+        let new_text = self.extract_text_content(new);
+        let old_words: Vec<&str> = old_text.split_whitespace().collect();
+        let new_words: Vec<&str> = new_text.split_whitespace().collect();
+
+        let words = diff(&old_words, &new_words)
+            .into_iter()
+            .enumerate()
+            .map(|(i, change)| {
+                let space = if i > 0 { " " } else { "" };
+                match change {
+                    Change::Same(word) => format!("{}{}", space, word).into_any(),
+                    Change::Removed(word) => view! {
+                        {space}<del class=self.classes().diff_word_removed.clone()>{word.to_string()}</del>
+                    }
+                    .into_any(),
+                    Change::Added(word) => view! {
+                        {space}<ins class=self.classes().diff_word_added.clone()>{word.to_string()}</ins>
+                    }
+                    .into_any(),
+                }
+            })
+            .collect_view();
+        view! { <p>{words}</p> }.into_any()
+    }
+}
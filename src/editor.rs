@@ -0,0 +1,126 @@
+use crate::compat::*;
+use crate::components::{EditorLayout, MarkdownClasses, MarkdownOptions};
+use crate::Markdown;
+
+/// Markdown textarea with a live `<Markdown>` preview, for CMS admin screens and comment
+/// forms. Typing updates `value`, and the preview re-renders from it.
+#[component]
+pub fn MarkdownEditor(
+    /// The markdown being edited
+    value: RwSignal<String>,
+    /// Side-by-side or tabbed arrangement
+    #[prop(optional)]
+    layout: EditorLayout,
+    /// Options for the preview, falling back to [`provide_markdown_options`](crate::provide_markdown_options)
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+    /// Rendered above the editor, e.g. formatting buttons that update `value`
+    #[prop(optional, into)]
+    toolbar: Option<ViewFn>,
+    /// Scroll the preview along with the textarea in the split layout
+    #[prop(default = true)]
+    sync_scroll: bool,
+    /// Placeholder shown in the empty textarea
+    #[prop(optional, into)]
+    placeholder: Option<String>,
+    /// Optional CSS class for the editor container
+    #[prop(optional)]
+    class: Option<String>,
+) -> impl IntoView {
+    let options = options
+        .or_else(crate::use_markdown_options)
+        .unwrap_or_default();
+    let container_class = match class {
+        Some(c) => format!("{} {}", MarkdownClasses::EDITOR, c),
+        None => MarkdownClasses::EDITOR.to_string(),
+    };
+    let toolbar = toolbar.map(|toolbar| {
+        view! { <div class=MarkdownClasses::EDITOR_TOOLBAR role="toolbar">{toolbar.run()}</div> }
+    });
+
+    let input = NodeRef::<html::Textarea>::new();
+    let preview = NodeRef::<html::Div>::new();
+    let textarea = move || {
+        view! {
+            <textarea
+                class=MarkdownClasses::EDITOR_INPUT
+                node_ref=input
+                placeholder=placeholder.clone()
+                prop:value=move || value.get()
+                on:input=move |ev| value.set(event_target_value(&ev))
+                on:scroll=move |_| {
+                    if sync_scroll {
+                        sync_preview_scroll(input, preview);
+                    }
+                }
+            />
+        }
+    };
+    let preview_pane = move || {
+        view! {
+            <div class=MarkdownClasses::EDITOR_PREVIEW node_ref=preview>
+                <Markdown content=value options=options.clone() />
+            </div>
+        }
+    };
+
+    let body = match layout {
+        EditorLayout::Split => view! {
+            <div class=MarkdownClasses::EDITOR_SPLIT>{textarea()}{preview_pane()}</div>
+        }
+        .into_any(),
+        EditorLayout::Tabs => {
+            let previewing = RwSignal::new(false);
+            let tab = move |label: &'static str, shows_preview: bool| {
+                view! {
+                    <button
+                        type="button"
+                        role="tab"
+                        class=MarkdownClasses::EDITOR_TAB
+                        aria-selected=move || (previewing.get() == shows_preview).to_string()
+                        on:click=move |_| previewing.set(shows_preview)
+                    >
+                        {label}
+                    </button>
+                }
+            };
+            view! {
+                <div class=MarkdownClasses::EDITOR_TABS role="tablist">
+                    {tab("Write", false)}
+                    {tab("Preview", true)}
+                </div>
+                <div role="tabpanel">
+                    {move || {
+                        if previewing.get() {
+                            preview_pane().into_any()
+                        } else {
+                            textarea().into_any()
+                        }
+                    }}
+                </div>
+            }
+            .into_any()
+        }
+    };
+
+    view! {
+        <div class=container_class>
+            {toolbar}
+            {body}
+        </div>
+    }
+}
+
+/// Scroll the preview to the same relative position as the textarea
+fn sync_preview_scroll(input: NodeRef<html::Textarea>, preview: NodeRef<html::Div>) {
+    let (Some(input), Some(preview)) = (input.get_untracked(), preview.get_untracked()) else {
+        return;
+    };
+    let scrollable = input.scroll_height() - input.client_height();
+    if scrollable <= 0 {
+        return;
+    }
+    let ratio = f64::from(input.scroll_top()) / f64::from(scrollable);
+    let target = ratio * f64::from(preview.scroll_height() - preview.client_height());
+    preview.set_scroll_top(target.round() as i32);
+}
@@ -0,0 +1,79 @@
+use crate::callback::EmojiResolver;
+
+/// A piece of text split around `:shortcode:`s
+pub(crate) enum EmojiSegment<'a> {
+    Text(&'a str),
+    /// Unicode emoji from the gemoji table
+    Emoji(&'static str),
+    /// Custom emoji image
+    Image {
+        shortcode: &'a str,
+        src: String,
+    },
+}
+
+/// Split `text` around the shortcodes `resolver` or the gemoji table know; unknown
+/// shortcodes stay text and are added to `unknown`
+pub(crate) fn emoji_segments<'a>(
+    text: &'a str,
+    resolver: Option<&EmojiResolver>,
+    unknown: &mut Vec<&'a str>,
+) -> Vec<EmojiSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut search = 0;
+
+    while let Some(found) = text[search..].find(':') {
+        let open = search + found;
+        let Some(len) = text[open + 1..].find(':') else {
+            break;
+        };
+        let close = open + 1 + len;
+        let shortcode = &text[open + 1..close];
+        let segment = if is_shortcode(shortcode) {
+            match resolver.and_then(|resolver| resolver(shortcode)) {
+                Some(src) => Some(EmojiSegment::Image { shortcode, src }),
+                None => gemoji(shortcode).map(EmojiSegment::Emoji),
+            }
+        } else {
+            None
+        };
+        let Some(segment) = segment else {
+            // Digits between colons are times and ratios rather than misspelled emoji
+            if is_shortcode(shortcode) && shortcode.chars().any(|c| c.is_ascii_alphabetic()) {
+                unknown.push(shortcode);
+            }
+            // The closing colon may open the next shortcode
+            search = close;
+            continue;
+        };
+        if open > text_start {
+            segments.push(EmojiSegment::Text(&text[text_start..open]));
+        }
+        segments.push(segment);
+        text_start = close + 1;
+        search = text_start;
+    }
+    if text_start < text.len() {
+        segments.push(EmojiSegment::Text(&text[text_start..]));
+    }
+
+    segments
+}
+
+fn is_shortcode(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'))
+}
+
+#[cfg(feature = "emoji")]
+fn gemoji(shortcode: &str) -> Option<&'static str> {
+    emojis::get_by_shortcode(shortcode).map(|emoji| emoji.as_str())
+}
+
+#[cfg(not(feature = "emoji"))]
+fn gemoji(_shortcode: &str) -> Option<&'static str> {
+    None
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// Error produced while rendering markdown
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MarkdownError {
+    /// The document could not be rendered
+    Render(String),
+    /// The input is longer than `max_input_len` bytes
+    InputTooLong { len: usize, max: usize },
+    /// Elements nest deeper than `max_nesting_depth`
+    NestingTooDeep { max: usize },
+}
+
+impl fmt::Display for MarkdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkdownError::Render(message) => write!(f, "failed to render markdown: {}", message),
+            MarkdownError::InputTooLong { len, max } => write!(
+                f,
+                "markdown input is {} bytes, more than the limit of {}",
+                len, max
+            ),
+            MarkdownError::NestingTooDeep { max } => {
+                write!(f, "markdown elements nest more than {} levels deep", max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MarkdownError {}
@@ -0,0 +1,171 @@
+use crate::classes::ClassBundle;
+use crate::sanitize::escape_html;
+use std::ops::RangeInclusive;
+
+/// 1-based lines listed in a `{1,4-6}` range set of a fence info string (```` ```rust {1,4-6} ````).
+/// Braces holding anything but line numbers are skipped.
+pub(crate) fn highlighted_lines(info: &str) -> Vec<RangeInclusive<usize>> {
+    let mut rest = info;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            break;
+        };
+        if let Some(ranges) = parse_line_ranges(&after[..close]) {
+            return ranges;
+        }
+        rest = &after[close + 1..];
+    }
+    Vec::new()
+}
+
+fn parse_line_ranges(spec: &str) -> Option<Vec<RangeInclusive<usize>>> {
+    spec.split(',')
+        .map(|part| match part.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start.trim().parse().ok()?;
+                let end: usize = end.trim().parse().ok()?;
+                (start <= end).then_some(start..=end)
+            }
+            None => {
+                let line: usize = part.trim().parse().ok()?;
+                Some(line..=line)
+            }
+        })
+        .collect()
+}
+
+/// File name from a `title="main.rs"` or `file="main.rs"` fence attribute (quotes are
+/// optional for names without spaces)
+pub(crate) fn fence_title(info: &str) -> Option<String> {
+    fence_attribute(info, &["title=", "file="])
+}
+
+/// Tab label from a `tab="Cargo"` fence attribute
+pub(crate) fn fence_tab(info: &str) -> Option<String> {
+    fence_attribute(info, &["tab="])
+}
+
+/// Value of the first of `keys` set in a fence info string
+fn fence_attribute(info: &str, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        let start = info.match_indices(key).find_map(|(i, _)| {
+            let at_word_start = info[..i].ends_with(char::is_whitespace);
+            at_word_start.then_some(i + key.len())
+        })?;
+        let value = &info[start..];
+        let title = match value.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"')?.0,
+            None => value.split_whitespace().next().unwrap_or(""),
+        };
+        let title = title.trim();
+        (!title.is_empty()).then(|| title.to_string())
+    })
+}
+
+/// Code HTML with each line in `ranges` wrapped in a `code_line_highlight` span and,
+/// with `diff` set, `+`/`-` lines in `code_diff_added`/`code_diff_removed` spans (a
+/// highlighted range wins). `highlighted` is syntect's output; plain `code` is escaped
+/// otherwise.
+pub(crate) fn highlight_lines(
+    code: &str,
+    highlighted: Option<&str>,
+    ranges: &[RangeInclusive<usize>],
+    diff: bool,
+    classes: &ClassBundle,
+) -> String {
+    let lines = match highlighted {
+        Some(html) => html_lines(html),
+        None => code.lines().map(escape_html).collect(),
+    };
+
+    let mut html = String::with_capacity(code.len() * 2);
+    for (index, (line, source)) in lines.iter().zip(code.lines()).enumerate() {
+        let class = if ranges.iter().any(|range| range.contains(&(index + 1))) {
+            Some(classes.code_line_highlight.as_ref())
+        } else if diff {
+            diff_line_class(source, classes)
+        } else {
+            None
+        };
+        match class {
+            Some(class) => {
+                html.push_str("<span class=\"");
+                html.push_str(&escape_html(class));
+                html.push_str("\">");
+                html.push_str(line);
+                html.push_str("</span>");
+            }
+            None => html.push_str(line),
+        }
+        html.push('\n');
+    }
+    html
+}
+
+/// Class of a unified diff line: added, removed or hunk header. File headers
+/// (`+++`/`---`) stay plain.
+fn diff_line_class<'c>(line: &str, classes: &'c ClassBundle) -> Option<&'c str> {
+    if line.starts_with("+++") || line.starts_with("---") {
+        None
+    } else if line.starts_with('+') {
+        Some(&classes.code_diff_added)
+    } else if line.starts_with('-') {
+        Some(&classes.code_diff_removed)
+    } else if line.starts_with("@@") {
+        Some(&classes.code_diff_hunk)
+    } else {
+        None
+    }
+}
+
+/// Split highlighted HTML into self-contained lines: token spans open across a line
+/// break are closed at its end and reopened on the next line
+fn html_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut line = String::new();
+    let mut rest = html;
+
+    while let Some(i) = rest.find(['<', '\n']) {
+        line.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix('\n') {
+            line.push_str(&"</span>".repeat(open.len()));
+            lines.push(std::mem::take(&mut line));
+            line.extend(open.iter().copied());
+            rest = after;
+        } else {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..end];
+            if tag.starts_with("</") {
+                open.pop();
+            } else {
+                open.push(tag);
+            }
+            line.push_str(tag);
+            rest = &rest[end..];
+        }
+    }
+    line.push_str(rest);
+
+    // After the final newline only reopened and closing tags remain
+    if has_text(&line) {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Whether `html` has content outside its tags
+fn has_text(html: &str) -> bool {
+    let mut in_tag = false;
+    html.chars().any(|c| {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => return true,
+            _ => {}
+        }
+        false
+    })
+}
@@ -0,0 +1,175 @@
+use crate::compat::*;
+use crate::error::MarkdownError;
+use crate::matching::{Events, MatchTable};
+use crate::renderer::MarkdownRenderer;
+use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd};
+
+/// Paragraph text replaced by the list of figures
+pub(crate) const LOF_PLACEHOLDER: &str = "[[lof]]";
+
+/// What a numbered caption belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FigureKind {
+    /// A titled image standing alone in its paragraph
+    Figure,
+    /// A fenced code block with a `caption="..."` attribute
+    Listing,
+}
+
+impl FigureKind {
+    /// Caption prefix (`Figure`, `Listing`)
+    pub fn label(self) -> &'static str {
+        match self {
+            FigureKind::Figure => "Figure",
+            FigureKind::Listing => "Listing",
+        }
+    }
+
+    fn id_prefix(self) -> &'static str {
+        match self {
+            FigureKind::Figure => "figure",
+            FigureKind::Listing => "listing",
+        }
+    }
+}
+
+/// A numbered figure or listing, as listed in the list of figures
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownFigure {
+    pub kind: FigureKind,
+    /// 1-based number, counted separately for figures and listings
+    pub number: usize,
+    pub caption: String,
+    /// Id of the rendered `<figure>` element
+    pub id: String,
+}
+
+impl MarkdownFigure {
+    fn new(kind: FigureKind, number: usize, caption: String) -> Self {
+        Self {
+            kind,
+            number,
+            caption,
+            id: format!("{}-{}", kind.id_prefix(), number),
+        }
+    }
+
+    /// Caption as rendered, e.g. `Figure 2: Architecture`
+    pub fn title(&self) -> String {
+        format!("{} {}: {}", self.kind.label(), self.number, self.caption)
+    }
+}
+
+/// Numbers figures and listings in render order
+#[derive(Default)]
+pub(crate) struct FigureCounter {
+    figures: usize,
+    listings: usize,
+}
+
+impl FigureCounter {
+    pub(crate) fn next(&mut self, kind: FigureKind, caption: String) -> MarkdownFigure {
+        let count = match kind {
+            FigureKind::Figure => &mut self.figures,
+            FigureKind::Listing => &mut self.listings,
+        };
+        *count += 1;
+        MarkdownFigure::new(kind, *count, caption)
+    }
+}
+
+impl MarkdownRenderer {
+    /// Numbered figures and captioned listings of `content` in document order (empty
+    /// unless `figures` or `captioned_listings` is enabled)
+    pub fn extract_figures(&self, content: &str) -> Result<Vec<MarkdownFigure>, MarkdownError> {
+        let events = self.parse_within_limits(content, |c| self.parse_events(c))?;
+        Ok(self.collect_figures(&events))
+    }
+
+    pub(crate) fn collect_figures(&self, events: &[Event]) -> Vec<MarkdownFigure> {
+        let mut counter = FigureCounter::default();
+        let mut figures = Vec::new();
+        let table = MatchTable::new(events);
+        let indexed = Events::new(events, &table);
+
+        for (i, event) in events.iter().enumerate() {
+            match event {
+                Event::Start(Tag::Paragraph) if self.options().figures => {
+                    let (end_index, _) = indexed.end_of(i);
+                    if let Some(caption) = figure_caption(&events[i + 1..i + end_index]) {
+                        figures.push(counter.next(FigureKind::Figure, caption));
+                    }
+                }
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))
+                    if self.options().captioned_listings =>
+                {
+                    if let Some(caption) = listing_caption(info) {
+                        figures.push(counter.next(FigureKind::Listing, caption));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        figures
+    }
+
+    /// Whether `[[lof]]` placeholders are replaced
+    pub(crate) fn lof_enabled(&self) -> bool {
+        self.options().figures || self.options().captioned_listings
+    }
+
+    /// The list of figures rendered in place of a `[[lof]]` placeholder
+    pub(crate) fn render_figure_list(&self, figures: &[MarkdownFigure]) -> AnyView {
+        let classes = self.classes();
+        let items = figures
+            .iter()
+            .map(|figure| {
+                let href = self.anchor_href(&figure.id);
+                view! {
+                    <li class=classes.toc_item.clone()>
+                        <a class=classes.toc_link.clone() href=href>{figure.title()}</a>
+                    </li>
+                }
+            })
+            .collect_view();
+        view! {
+            <nav class=classes.lof.clone() aria-label="List of figures">
+                <ol>{items}</ol>
+            </nav>
+        }
+        .into_any()
+    }
+}
+
+/// Title of an image standing alone in a paragraph, which then renders as a figure
+pub(crate) fn figure_caption(paragraph: &[Event]) -> Option<String> {
+    let [Event::Start(Tag::Image { title, .. }), .., Event::End(TagEnd::Image)] = paragraph else {
+        return None;
+    };
+    let image_end = paragraph
+        .iter()
+        .position(|event| matches!(event, Event::End(TagEnd::Image)))?;
+    (image_end == paragraph.len() - 1 && !title.trim().is_empty()).then(|| title.trim().to_string())
+}
+
+/// `caption="..."` attribute following the language in a fence info string
+pub(crate) fn listing_caption(info: &str) -> Option<String> {
+    let (_, rest) = info.split_once("caption=\"")?;
+    let (caption, _) = rest.split_once('"')?;
+    let caption = caption.trim();
+    (!caption.is_empty()).then(|| caption.to_string())
+}
+
+/// Whether a paragraph holds only the `[[lof]]` placeholder (the parser may split the
+/// brackets into several text events)
+pub(crate) fn is_lof_placeholder(paragraph: &[Event]) -> bool {
+    let mut text = String::new();
+    for event in paragraph {
+        match event {
+            Event::Text(t) => text.push_str(t),
+            _ => return false,
+        }
+    }
+    text.trim() == LOF_PLACEHOLDER
+}
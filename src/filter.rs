@@ -0,0 +1,130 @@
+use crate::callback::BlockFilter;
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+
+/// Text shown in place of a redacted section
+pub(crate) const REDACTION_NOTICE: &str = "This section is hidden.";
+
+/// A heading section offered to the block filter: the heading and every block up to the
+/// next heading of the same or a higher level
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockContext {
+    /// Heading level, 1–6
+    pub level: u8,
+    /// Plain text of the heading
+    pub text: String,
+    /// Explicit `{#id}`, if any
+    pub id: Option<String>,
+    /// Classes from heading attributes (`{.internal}`)
+    pub classes: Vec<String>,
+    /// Other heading attributes (`{audience=staff}`)
+    pub attrs: Vec<(String, Option<String>)>,
+}
+
+impl BlockContext {
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+
+    /// Value of attribute `name` (`Some(None)` for a bare attribute)
+    pub fn attr(&self, name: &str) -> Option<Option<&str>> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_deref())
+    }
+}
+
+/// What happens to a heading section
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlockAction {
+    #[default]
+    Keep,
+    /// Replace the section, heading included, with a short notice
+    Redact,
+    /// Leave the section out entirely
+    Drop,
+}
+
+/// Run `filter` on every top-level heading section, removing or redacting sections it
+/// rejects. Subsections of a kept section are offered separately.
+pub(crate) fn apply_block_filter<'a>(
+    events: Vec<Event<'a>>,
+    filter: &BlockFilter,
+) -> Vec<Event<'a>> {
+    let mut hidden = vec![false; events.len()];
+    let mut redacted = vec![false; events.len()];
+    // Level of the section being removed
+    let mut removing: Option<u8> = None;
+    let mut depth = 0usize;
+
+    for (i, event) in events.iter().enumerate() {
+        if let (
+            0,
+            Event::Start(Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            }),
+        ) = (depth, event)
+        {
+            let level = *level as u8;
+            if removing.is_some_and(|removed| level <= removed) {
+                removing = None;
+            }
+            if removing.is_none() {
+                let context = BlockContext {
+                    level,
+                    text: heading_text(&events[i + 1..]),
+                    id: id.as_ref().map(|id| id.to_string()),
+                    classes: classes.iter().map(|c| c.to_string()).collect(),
+                    attrs: attrs
+                        .iter()
+                        .map(|(key, value)| {
+                            (key.to_string(), value.as_ref().map(|v| v.to_string()))
+                        })
+                        .collect(),
+                };
+                match filter(&context) {
+                    BlockAction::Keep => {}
+                    BlockAction::Redact => {
+                        redacted[i] = true;
+                        removing = Some(level);
+                    }
+                    BlockAction::Drop => removing = Some(level),
+                }
+            }
+        }
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        hidden[i] = removing.is_some();
+    }
+
+    let mut output = Vec::with_capacity(events.len());
+    for (i, event) in events.into_iter().enumerate() {
+        if redacted[i] {
+            output.push(Event::Start(Tag::Paragraph));
+            output.push(Event::Text(CowStr::Borrowed(REDACTION_NOTICE)));
+            output.push(Event::End(TagEnd::Paragraph));
+        }
+        if !hidden[i] {
+            output.push(event);
+        }
+    }
+    output
+}
+
+/// Plain text of the heading whose content starts `events`
+fn heading_text(events: &[Event]) -> String {
+    events
+        .iter()
+        .take_while(|event| !matches!(event, Event::End(TagEnd::Heading(_))))
+        .filter_map(|event| match event {
+            Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,222 @@
+use crate::renderer::content_hash;
+use crate::slug::Slugger;
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Footnote numbering for one document: labels are numbered in order of first reference
+/// (unreferenced definitions follow in definition order), like GitHub
+#[derive(Default)]
+pub(crate) struct Footnotes {
+    numbers: HashMap<String, usize>,
+    /// Labels with a reference anywhere in the document
+    cited: HashSet<String>,
+    /// Labels whose first reference has been rendered
+    referenced: HashSet<String>,
+    /// Slugged label used in ids, unique within the document
+    slugs: HashMap<String, String>,
+    slugger: Slugger,
+    /// Per-document part of the ids, so footnotes of documents sharing a page don't clash
+    prefix: String,
+}
+
+impl Footnotes {
+    pub(crate) fn collect(events: &[Event]) -> Self {
+        let mut footnotes = Self::with_prefix(document_prefix(events));
+        footnotes.extend(events);
+        footnotes
+    }
+
+    /// Empty numbering whose ids start with `prefix`
+    pub(crate) fn with_prefix(prefix: String) -> Self {
+        Self {
+            prefix,
+            ..Self::default()
+        }
+    }
+
+    /// Number the labels of `events` that have no number yet, for documents parsed
+    /// section by section
+    pub(crate) fn extend(&mut self, events: &[Event]) {
+        let references: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::FootnoteReference(label) => Some(label),
+                _ => None,
+            })
+            .collect();
+        self.cited
+            .extend(references.iter().map(|label| label.to_string()));
+        let definitions = events.iter().filter_map(|event| match event {
+            Event::Start(Tag::FootnoteDefinition(label)) => Some(label),
+            _ => None,
+        });
+        for label in references.into_iter().chain(definitions) {
+            if self.numbers.contains_key(label.as_ref()) {
+                continue;
+            }
+            let next = self.numbers.len() + 1;
+            let slug = self.slugger.slug(label);
+            let slug = if slug.is_empty() {
+                next.to_string()
+            } else {
+                slug
+            };
+            self.numbers.insert(label.to_string(), next);
+            self.slugs.insert(label.to_string(), slug);
+        }
+    }
+
+    /// Ordinal shown for `label`
+    pub(crate) fn number(&self, label: &str) -> usize {
+        self.numbers.get(label).copied().unwrap_or_default()
+    }
+
+    /// Whether the document references `label`, so its definition has a reference to
+    /// link back to
+    pub(crate) fn is_cited(&self, label: &str) -> bool {
+        self.cited.contains(label)
+    }
+
+    /// Record a reference to `label`; `true` for the first one, which carries the
+    /// backlink target id
+    pub(crate) fn mark_referenced(&mut self, label: &str) -> bool {
+        self.referenced.insert(label.to_string())
+    }
+
+    /// Id of the definition of footnote `label`
+    pub(crate) fn id(&self, label: &str) -> String {
+        format!("fn-{}-{}", self.prefix, self.slug(label))
+    }
+
+    /// Id of the first reference to footnote `label`
+    pub(crate) fn ref_id(&self, label: &str) -> String {
+        format!("fnref-{}-{}", self.prefix, self.slug(label))
+    }
+
+    fn slug<'a>(&'a self, label: &'a str) -> &'a str {
+        self.slugs.get(label).map_or(label, String::as_str)
+    }
+}
+
+/// Id prefix derived from the document's text, so it matches between SSR and hydration
+fn document_prefix(events: &[Event]) -> String {
+    let hash = events.iter().fold(0u64, |hash, event| match event {
+        Event::Text(text) | Event::Code(text) | Event::FootnoteReference(text) => {
+            hash.rotate_left(5) ^ content_hash(text)
+        }
+        _ => hash,
+    });
+    format!("{:08x}", hash as u32)
+}
+
+/// Rewrite inline footnotes (`^[note text]`) into references plus definitions appended
+/// to the document, labelled `inline-1`, `inline-2`, ... Expects adjacent text events to
+/// be merged; a note may contain inline markup but not span blocks.
+pub(crate) fn extract_inline_footnotes(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut queue: VecDeque<Event> = events.into();
+    let mut output = Vec::with_capacity(queue.len());
+    let mut definitions = Vec::new();
+    let mut count = 0;
+
+    while let Some(event) = queue.pop_front() {
+        // Code block text is literal
+        if matches!(event, Event::Start(Tag::CodeBlock(_))) {
+            output.push(event);
+            while let Some(code) = queue.pop_front() {
+                let end = matches!(code, Event::End(TagEnd::CodeBlock));
+                output.push(code);
+                if end {
+                    break;
+                }
+            }
+            continue;
+        }
+        let Event::Text(text) = &event else {
+            output.push(event);
+            continue;
+        };
+        let Some(open) = text.find("^[") else {
+            output.push(event);
+            continue;
+        };
+        let (before, after) = (text[..open].to_string(), text[open + 2..].to_string());
+
+        let mut depth = 1;
+        let mut note = Vec::new();
+        let rest = if let Some(close) = closing_bracket(&after, &mut depth) {
+            note.push(Event::Text(CowStr::from(after[..close].to_string())));
+            after[close + 1..].to_string()
+        } else {
+            // The note continues past inline markup; it closes in a later text event
+            // at the same nesting level, before its block ends
+            let mut nesting = 0usize;
+            let mut found = None;
+            for (i, next) in queue.iter().enumerate() {
+                match next {
+                    Event::Start(_) => nesting += 1,
+                    Event::End(_) if nesting == 0 => break,
+                    Event::End(_) => nesting -= 1,
+                    Event::Text(next_text) if nesting == 0 => {
+                        if let Some(close) = closing_bracket(next_text, &mut depth) {
+                            found = Some((i, close));
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let Some((i, close)) = found else {
+                output.push(event);
+                continue;
+            };
+            if !after.is_empty() {
+                note.push(Event::Text(CowStr::from(after)));
+            }
+            note.extend(queue.drain(..i));
+            let Some(Event::Text(closing)) = queue.pop_front() else {
+                unreachable!("closing bracket was found in a text event");
+            };
+            if close > 0 {
+                note.push(Event::Text(CowStr::from(closing[..close].to_string())));
+            }
+            closing[close + 1..].to_string()
+        };
+
+        count += 1;
+        let label = CowStr::from(format!("inline-{}", count));
+        if !before.is_empty() {
+            output.push(Event::Text(CowStr::from(before)));
+        }
+        output.push(Event::FootnoteReference(label.clone()));
+        if !rest.is_empty() {
+            // May hold further inline notes
+            queue.push_front(Event::Text(CowStr::from(rest)));
+        }
+
+        definitions.push(Event::Start(Tag::FootnoteDefinition(label)));
+        definitions.push(Event::Start(Tag::Paragraph));
+        definitions.extend(note);
+        definitions.push(Event::End(TagEnd::Paragraph));
+        definitions.push(Event::End(TagEnd::FootnoteDefinition));
+    }
+
+    output.extend(definitions);
+    output
+}
+
+/// Byte offset of the `]` bringing the bracket `depth` to zero, tracking nested brackets
+fn closing_bracket(text: &str, depth: &mut usize) -> Option<usize> {
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => *depth += 1,
+            ']' => {
+                *depth -= 1;
+                if *depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,78 @@
+use pulldown_cmark::MetadataBlockKind;
+
+/// Metadata block opening a document: YAML between `---` lines or TOML between `+++`
+/// lines
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub kind: MetadataBlockKind,
+    /// The block's source, without the delimiter lines
+    pub raw: String,
+}
+
+impl Frontmatter {
+    /// Split the frontmatter off the start of `content`, returning it and the body that
+    /// follows. Content without a closed metadata block is returned whole.
+    pub fn split(content: &str) -> (Option<Frontmatter>, &str) {
+        let (kind, delimiter) = if content.starts_with("---") {
+            (MetadataBlockKind::YamlStyle, "---")
+        } else if content.starts_with("+++") {
+            (MetadataBlockKind::PlusesStyle, "+++")
+        } else {
+            return (None, content);
+        };
+
+        let mut lines = content.split_inclusive('\n');
+        let Some(opening) = lines.next() else {
+            return (None, content);
+        };
+        if opening.trim_end() != delimiter {
+            return (None, content);
+        }
+
+        let mut offset = opening.len();
+        for line in lines {
+            let closes = match kind {
+                // YAML documents may also end with `...`
+                MetadataBlockKind::YamlStyle => matches!(line.trim_end(), "---" | "..."),
+                MetadataBlockKind::PlusesStyle => line.trim_end() == "+++",
+            };
+            if closes {
+                let raw = content[opening.len()..offset].to_string();
+                // An empty `---` pair is a thematic break, not metadata
+                if raw.trim().is_empty() {
+                    return (None, content);
+                }
+                let body = &content[offset + line.len()..];
+                return (Some(Frontmatter { kind, raw }), body);
+            }
+            offset += line.len();
+        }
+        (None, content)
+    }
+
+    /// Deserialize the block into `T` with serde (YAML or TOML by kind)
+    #[cfg(feature = "frontmatter")]
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T, String> {
+        match self.kind {
+            MetadataBlockKind::YamlStyle => {
+                serde_yaml::from_str(&self.raw).map_err(|err| err.to_string())
+            }
+            MetadataBlockKind::PlusesStyle => {
+                toml::from_str(&self.raw).map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "frontmatter")]
+impl crate::renderer::MarkdownRenderer {
+    /// Typed frontmatter of `content` and the body after it. The data is `None` when the
+    /// document has no frontmatter or it does not deserialize into `T`.
+    pub fn parse_frontmatter<T: serde::de::DeserializeOwned>(content: &str) -> (Option<T>, &str) {
+        let (frontmatter, body) = Frontmatter::split(content);
+        (
+            frontmatter.and_then(|frontmatter| frontmatter.deserialize().ok()),
+            body,
+        )
+    }
+}
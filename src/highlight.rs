@@ -0,0 +1,103 @@
+use crate::components::{CodeBlockTheme, MarkdownOptions};
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Token classes are prefixed (`hl-keyword`, `hl-string`, ...) so they cannot collide
+/// with Tailwind utilities
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Highlighted HTML (nested classed `<span>`s) for `code`, or `None` when no syntax
+/// matches the fence language
+pub(crate) fn highlight_code(code: &str, language: &str) -> Option<String> {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes.find_syntax_by_token(language)?;
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+    Some(generator.finalize())
+}
+
+impl CodeBlockTheme {
+    /// Name of the bundled syntect theme matching this code block theme
+    pub fn syntect_theme_name(&self) -> &'static str {
+        match self {
+            CodeBlockTheme::Default => "InspiredGitHub",
+            CodeBlockTheme::Dark => "base16-ocean.dark",
+            CodeBlockTheme::Light => "base16-ocean.light",
+            CodeBlockTheme::GitHub => "InspiredGitHub",
+            CodeBlockTheme::Monokai => "base16-mocha.dark",
+        }
+    }
+
+    /// Stylesheet coloring the `hl-*` token classes for this theme; include it once
+    /// per page (e.g. in a `<style>` tag)
+    pub fn syntect_css(&self) -> String {
+        theme_set()
+            .themes
+            .get(self.syntect_theme_name())
+            .and_then(|theme| css_for_theme_with_class_style(theme, CLASS_STYLE).ok())
+            .unwrap_or_default()
+    }
+}
+
+impl MarkdownOptions {
+    /// Stylesheet for the configured code themes: `code_theme` colors the `hl-*` classes,
+    /// and `dark_code_theme`, when set, overrides them under Tailwind's `.dark` class
+    pub fn syntect_css(&self) -> String {
+        let mut css = self
+            .code_theme
+            .as_ref()
+            .map(CodeBlockTheme::syntect_css)
+            .unwrap_or_default();
+        if let Some(dark) = &self.dark_code_theme {
+            css.push('\n');
+            css.push_str(&scope_css(&dark.syntect_css(), ".dark"));
+        }
+        css
+    }
+}
+
+/// Prefix every selector of `css` with `scope`, dropping comments
+fn scope_css(css: &str, scope: &str) -> String {
+    let mut uncommented = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        uncommented.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("*/")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    uncommented.push_str(rest);
+
+    let mut scoped = String::with_capacity(uncommented.len() * 2);
+    for rule in uncommented.split_inclusive('}') {
+        let Some((selectors, body)) = rule.split_once('{') else {
+            continue;
+        };
+        let selectors: Vec<String> = selectors
+            .split(',')
+            .map(|selector| format!("{} {}", scope, selector.trim()))
+            .collect();
+        scoped.push_str(&selectors.join(", "));
+        scoped.push_str(" {");
+        scoped.push_str(body);
+        scoped.push('\n');
+    }
+    scoped
+}
@@ -0,0 +1,105 @@
+use crate::compat::*;
+use crate::components::{MarkdownClasses, MarkdownOptions};
+use crate::renderer::MarkdownRenderer;
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::sync::Arc;
+
+impl MarkdownRenderer {
+    /// Events of `content` under `inline_only`: one paragraph's inline content, unwrapped
+    pub(crate) fn parse_inline_events(&self, content: &str) -> Vec<Event<'static>> {
+        self.parse_events(&inline_source(content))
+            .into_iter()
+            .filter(|event| {
+                !matches!(
+                    event,
+                    Event::Start(Tag::Paragraph | Tag::HtmlBlock)
+                        | Event::End(TagEnd::Paragraph | TagEnd::HtmlBlock)
+                )
+            })
+            .map(Event::into_static)
+            .collect()
+    }
+}
+
+/// `content` on a single line, with any leading block marker escaped so it parses as
+/// one paragraph
+fn inline_source(content: &str) -> String {
+    let mut line = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some(at) = block_marker(&line) {
+        line.insert(at, '\\');
+    }
+    line
+}
+
+/// Where to escape a single line that would open something other than a paragraph: a
+/// heading, quote, list item, thematic break, code fence, container or reference
+/// definition. Ordered list markers are escaped at their `.` / `)`.
+fn block_marker(line: &str) -> Option<usize> {
+    let marker_then_space = |rest: &str| rest.is_empty() || rest.starts_with(' ');
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    if (1..=9).contains(&digits)
+        && line[digits..].starts_with(['.', ')'])
+        && marker_then_space(&line[digits + 1..])
+    {
+        return Some(digits);
+    }
+    let opens_block = ((1..=6).contains(&hashes) && marker_then_space(&line[hashes..]))
+        || line.starts_with('>')
+        || (line.starts_with(['-', '+', '*']) && marker_then_space(&line[1..]))
+        || is_thematic_break(line)
+        || line.starts_with("```")
+        || line.starts_with("~~~")
+        || line.starts_with(":::")
+        || (line.starts_with('[')
+            && line
+                .find(']')
+                .is_some_and(|close| line[close + 1..].starts_with(':')));
+    opens_block.then_some(0)
+}
+
+/// `***`, `- - -`, `___` and the like
+fn is_thematic_break(line: &str) -> bool {
+    let Some(marker) = line.chars().next().filter(|c| matches!(c, '*' | '-' | '_')) else {
+        return false;
+    };
+    line.chars().all(|c| c == marker || c == ' ') && line.matches(marker).count() >= 3
+}
+
+/// Markdown for single-line strings such as card titles or chat usernames: bold, code and
+/// links render, but nothing is wrapped in `<p>` or other blocks, so it sits inside any
+/// inline layout.
+///
+/// Without `options` the ones provided with
+/// [`provide_markdown_options`](crate::provide_markdown_options) apply; failing that the
+/// elements carry their own Tailwind utilities, since there is no prose wrapper to style
+/// them.
+#[component]
+pub fn MarkdownInline(
+    /// The markdown text, static or reactive
+    #[prop(into)]
+    content: Signal<String>,
+    /// Optional CSS class for the wrapping `<span>`
+    #[prop(optional)]
+    class: Option<String>,
+    /// Markdown rendering options; `inline_only` is always on
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+) -> impl IntoView {
+    let options = options
+        .or_else(crate::use_markdown_options)
+        .unwrap_or_else(|| MarkdownOptions::new().with_explicit_classes(true))
+        .with_inline_only(true);
+    let renderer = Arc::new(MarkdownRenderer::new(options));
+    let span_class = match class {
+        Some(c) => format!("{} {}", MarkdownClasses::INLINE, c),
+        None => MarkdownClasses::INLINE.to_string(),
+    };
+
+    view! {
+        <span class=span_class>
+            {move || content.with(|content| renderer.render(content).unwrap_or_else(|err| err.to_string().into_any()))}
+        </span>
+    }
+}
@@ -0,0 +1,56 @@
+/// Keys of a `` `kbd:Ctrl+Shift+P` `` code span, `None` for any other code
+pub(crate) fn kbd_code_keys(code: &str) -> Option<Vec<&str>> {
+    let combo = code.strip_prefix("kbd:")?.trim();
+    if combo.is_empty() {
+        return None;
+    }
+    // A trailing `++` is the plus key itself (`kbd:Ctrl++`)
+    let (combo, plus) = match combo.strip_suffix("++") {
+        Some(rest) if !rest.is_empty() => (rest, true),
+        _ if combo == "+" => ("", true),
+        _ => (combo, false),
+    };
+    let mut keys: Vec<&str> = combo
+        .split('+')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .collect();
+    if plus {
+        keys.push("+");
+    }
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// A piece of text split around `[[Key]]` key caps
+pub(crate) enum KbdSegment<'a> {
+    Text(&'a str),
+    Key(&'a str),
+}
+
+/// Split `text` around `[[Key]]`s: up to 24 characters without brackets or line breaks
+pub(crate) fn kbd_segments(text: &str) -> Vec<KbdSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find("[[") {
+        let after = &rest[open + 2..];
+        let key = after.find("]]").map(|close| &after[..close]).filter(|key| {
+            !key.trim().is_empty() && key.chars().count() <= 24 && !key.contains(['[', ']', '\n'])
+        });
+        let Some(key) = key else {
+            segments.push(KbdSegment::Text(&rest[..open + 2]));
+            rest = after;
+            continue;
+        };
+        if open > 0 {
+            segments.push(KbdSegment::Text(&rest[..open]));
+        }
+        segments.push(KbdSegment::Key(key.trim()));
+        rest = &after[key.len() + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(KbdSegment::Text(rest));
+    }
+
+    segments
+}
@@ -1,6 +1,7 @@
 //! # leptos-md
 //!
-//! A simple, signal-free Markdown renderer for [Leptos](https://leptos.dev) with Tailwind CSS styling.
+//! A simple, reactive Markdown renderer for [Leptos](https://leptos.dev) with Tailwind CSS
+//! styling: `content` can be a signal, and the output re-renders only when its text changes.
 //!
 //! ## Quick Start
 //!
@@ -43,61 +44,293 @@
 //! }
 //! ```
 
-use leptos::prelude::*;
+use crate::compat::*;
 
+mod annotate;
+mod ast;
+mod autolink;
+mod cache;
+mod callback;
+mod chat;
+mod chunked;
+mod citations;
+mod classes;
+mod code;
+mod compat;
 mod components;
+mod containers;
+mod context;
+mod diff;
+#[cfg(feature = "editor")]
+mod editor;
+mod emoji;
+mod error;
+mod fence;
+mod figures;
+mod filter;
+mod footnotes;
+mod frontmatter;
+#[cfg(feature = "syntect")]
+mod highlight;
+mod inline;
+mod kbd;
+mod matching;
+mod mentions;
+#[cfg(feature = "class-merge")]
+mod merge;
+mod overrides;
+mod parsed;
+mod plain;
+mod quote;
+mod references;
 mod renderer;
+mod routes;
+mod sanitize;
+mod slug;
+#[cfg(feature = "ssr")]
+mod snapshot;
+mod spans;
+mod stream;
+mod tabs;
+mod tasks;
+mod toc;
+mod warnings;
 
+pub use annotate::{BlockAnnotation, Diagnostic};
+pub use ast::{walk, MdNode, MdVisitor};
+pub use cache::{MarkdownCacheBackend, MemoryCache, RenderCache};
+pub use callback::{
+    AnchorHrefResolver, BlockFilter, CitationResolver, EmojiResolver, EventTransform,
+    FrontmatterHandler, HtmlCommentHandler, ImageDimensions, ImageSrcTransform, LinkRewriter,
+    MarkdownCallback, MentionResolver, ReferenceLinker, SanitizeHandler, WikilinkResolver,
+};
+pub use chunked::MarkdownChunked;
+pub use citations::Citation;
+pub use code::CodeBlock;
 pub use components::{
-    get_code_theme_classes, get_enhanced_prose_classes, CodeBlockTheme, MarkdownClasses,
-    MarkdownOptions, MarkdownStyles,
+    get_alert_classes, get_code_theme_classes, get_code_title_theme_classes,
+    get_dark_code_theme_classes, get_dark_code_title_theme_classes, get_enhanced_prose_classes,
+    get_markdown_css, AlertKind, CodeBlockTheme, EditorLayout, H1Policy, ImageSource,
+    LinkTargetPolicy, MarkdownClassHooks, MarkdownClassMap, MarkdownClasses, MarkdownFlavor,
+    MarkdownOptions, MarkdownStyles, MarkdownTheme, MarkdownWrapper, MediaLayout, RawHtmlPolicy,
+    ScopeClass, SoftBreakMode, StrikethroughElement, UnsupportedContent, UnsupportedKind,
+    UrlPolicy,
+};
+pub use context::{provide_markdown_options, use_markdown_options};
+#[cfg(feature = "editor")]
+pub use editor::MarkdownEditor;
+pub use error::MarkdownError;
+pub use figures::{FigureKind, MarkdownFigure};
+pub use filter::{BlockAction, BlockContext};
+pub use frontmatter::Frontmatter;
+pub use inline::MarkdownInline;
+pub use mentions::MentionTarget;
+#[cfg(feature = "class-merge")]
+pub use merge::merge_classes;
+pub use overrides::{
+    BlockquoteProps, CodeBlockProps, ComponentOverride, HeadingProps, ImageProps, LinkProps,
+    MarkdownComponents, PlaceholderProps, TableProps,
 };
-pub use renderer::MarkdownRenderer;
+pub use parsed::{MarkdownBody, MarkdownFootnotes, MarkdownHeading, MarkdownToc, ParsedMarkdown};
+pub use references::RepoReference;
+pub use renderer::{MarkdownRenderer, RenderedMarkdown};
+pub use routes::RouteMap;
+pub use stream::{MarkdownStream, StreamingMarkdownRenderer};
+pub use tasks::toggle_task;
+pub use toc::TableOfContents;
+pub use warnings::{RenderWarning, SanitizeEvent};
 
-/// Main component for rendering Markdown content with Tailwind CSS styling
+/// The parser behind the renderer, for writing event transforms against the same version
+pub use pulldown_cmark;
+
+/// Main component for rendering Markdown content with Tailwind CSS styling.
+///
+/// `content` accepts a plain `String` or any signal; the view re-renders only when the
+/// markdown text actually changes.
 #[component]
 pub fn Markdown(
-    /// The markdown content as a string
+    /// The markdown content, static or reactive
     #[prop(into)]
-    content: String,
+    content: Signal<String>,
     /// Optional CSS class for the wrapper (will be combined with Tailwind prose classes)
     #[prop(optional)]
     class: Option<String>,
-    /// Markdown rendering options
+    /// Markdown rendering options, falling back to [`provide_markdown_options`]
     #[prop(optional)]
     options: Option<MarkdownOptions>,
+    /// Set to whether `max_blocks` / `max_chars` cut the content short, e.g. to show a
+    /// "Show full message" button
+    #[prop(optional)]
+    truncated: Option<RwSignal<bool>>,
+    /// Custom element views; takes precedence over `options.components`
+    #[prop(optional)]
+    components: Option<MarkdownComponents>,
+    /// Set to the document's frontmatter (`None` without one), e.g. to show its title.
+    /// Enables metadata blocks.
+    #[prop(optional)]
+    frontmatter: Option<RwSignal<Option<Frontmatter>>>,
+    /// Editor preview: annotate each block with its source lines and diagnostics badges
+    /// (missing alt text, broken links)
+    #[prop(optional)]
+    author_mode: bool,
+    /// Wrapper element; `attr:` spreads on `<Markdown>` land on it
+    #[prop(optional)]
+    wrapper: MarkdownWrapper,
+    /// `id` of the wrapper
+    #[prop(optional, into)]
+    id: Option<String>,
+    /// Text direction of the wrapper (`ltr`, `rtl` or `auto`)
+    #[prop(optional, into)]
+    dir: Option<String>,
+    /// Language of the content, e.g. `de`
+    #[prop(optional, into)]
+    lang: Option<String>,
+    /// View shown instead of the built-in error card when rendering fails, e.g. to
+    /// localize the message or hide details in production
+    #[prop(optional, into)]
+    error_view: Option<Callback<MarkdownError, AnyView>>,
 ) -> impl IntoView {
-    let renderer = MarkdownRenderer::new(options.unwrap_or_default());
-
-    match renderer.render(&content) {
-        Ok(rendered_content) => {
-            let base_classes = get_enhanced_prose_classes();
-            let wrapper_class = match class {
-                Some(c) => format!("{} {}", base_classes, c),
-                None => base_classes.to_string(),
-            };
-
-            view! {
-                <div class=wrapper_class>
-                    {rendered_content}
-                </div>
+    let mut options = options
+        .or_else(context::use_markdown_options)
+        .unwrap_or_default();
+    if let Some(components) = components {
+        options.components = components;
+    }
+    if frontmatter.is_some() {
+        options.enable_metadata_blocks = true;
+    }
+    let renderer = MarkdownRenderer::new(options);
+
+    // Skips re-rendering when the signal fires with unchanged text
+    let content = Memo::new(move |_| content.get());
+
+    if renderer.options().highlight_anchor_target {
+        // Re-runs after each render, so a target that only appears in new content is found
+        Effect::new(move |_| {
+            content.track();
+            scroll_to_location_hash();
+        });
+    }
+
+    move || {
+        let content = content.get();
+
+        if let Some(frontmatter) = frontmatter {
+            let data = renderer
+                .options()
+                .enable_metadata_blocks
+                .then(|| Frontmatter::split(&content).0)
+                .flatten();
+            frontmatter.set(data);
+        }
+
+        match renderer.render_pass(&content, author_mode) {
+            Ok((rendered_content, warnings)) => {
+                if let Some(truncated) = truncated {
+                    truncated.set(
+                        warnings
+                            .iter()
+                            .any(|warning| matches!(warning, RenderWarning::Truncated { .. })),
+                    );
+                }
+                let wrapper_class = wrapper_class(&renderer, &content, class.clone());
+                let attrs = WrapperAttrs {
+                    class: wrapper_class,
+                    id: id.clone(),
+                    dir: dir.clone(),
+                    lang: lang.clone(),
+                };
+                wrap_content(wrapper, attrs, rendered_content)
+            }
+            Err(err) => {
+                log_error!("Failed to render markdown: {}", err);
+                if let Some(error_view) = error_view {
+                    return error_view.run(err);
+                }
+                error_card(&err)
             }
-            .into_any()
         }
-        Err(err) => {
-            leptos::logging::error!("Failed to render markdown: {}", err);
-            view! {
-                <div class="bg-red-50 dark:bg-red-950/30 border border-red-200 dark:border-red-800 rounded-lg p-4 text-red-800 dark:text-red-200">
-                    <p class="font-medium">"Failed to render markdown content"</p>
-                    <p class="text-sm mt-1">{err}</p>
-                </div>
-            }.into_any()
+    }
+}
+
+/// The built-in card shown in place of content that failed to render
+pub(crate) fn error_card(err: &MarkdownError) -> AnyView {
+    view! {
+        <div class="bg-red-50 dark:bg-red-950/30 border border-red-200 dark:border-red-800 rounded-lg p-4 text-red-800 dark:text-red-200">
+            <p class="font-medium">"Failed to render markdown content"</p>
+            <p class="text-sm mt-1">{err.to_string()}</p>
+        </div>
+    }
+    .into_any()
+}
+
+/// Wrapper classes: the base or theme classes, option-driven extras, then the user's `class`
+pub(crate) fn wrapper_class(
+    renderer: &MarkdownRenderer,
+    content: &str,
+    class: Option<String>,
+) -> String {
+    let mut base_classes = match &renderer.options().base_class {
+        Some(class) => class.clone(),
+        None => renderer.options().theme.wrapper_classes().to_string(),
+    };
+    if renderer.options().long_token_breaks.is_some() {
+        base_classes.push(' ');
+        base_classes.push_str(MarkdownClasses::BREAK_LONG_TOKENS);
+    }
+    if let Some(scope) = renderer.scope_class(content) {
+        base_classes.push(' ');
+        base_classes.push_str(&scope);
+    }
+    match class {
+        Some(c) => crate::classes::resolve(format!("{} {}", base_classes, c)),
+        None => base_classes,
+    }
+}
+
+/// Attributes of the `<Markdown>` wrapper element
+struct WrapperAttrs {
+    class: String,
+    id: Option<String>,
+    dir: Option<String>,
+    lang: Option<String>,
+}
+
+/// `content` inside the `wrapper` element
+fn wrap_content(wrapper: MarkdownWrapper, attrs: WrapperAttrs, content: AnyView) -> AnyView {
+    let WrapperAttrs {
+        class,
+        id,
+        dir,
+        lang,
+    } = attrs;
+    match wrapper {
+        MarkdownWrapper::Div => {
+            view! { <div class=class id=id dir=dir lang=lang>{content}</div> }.into_any()
+        }
+        MarkdownWrapper::Article => {
+            view! { <article class=class id=id dir=dir lang=lang>{content}</article> }.into_any()
+        }
+        MarkdownWrapper::Section => {
+            view! { <section class=class id=id dir=dir lang=lang>{content}</section> }.into_any()
         }
+        MarkdownWrapper::Fragment => content,
+    }
+}
+
+/// Scroll the element targeted by the URL hash into view once the content is rendered,
+/// since the browser's own jump happens before client-rendered content exists
+pub(crate) fn scroll_to_location_hash() {
+    let Some(id) = location_hash() else {
+        return;
+    };
+    if let Some(element) = document().get_element_by_id(&id) {
+        element.scroll_into_view();
     }
 }
 
 /// Utility function to render markdown string directly to AnyView with Tailwind styling
-pub fn render_markdown_string(content: &str) -> Result<AnyView, String> {
+pub fn render_markdown_string(content: &str) -> Result<AnyView, MarkdownError> {
     let renderer = MarkdownRenderer::new(MarkdownOptions::default());
     renderer.render(content)
 }
@@ -106,7 +339,37 @@ pub fn render_markdown_string(content: &str) -> Result<AnyView, String> {
 pub fn render_markdown_with_options(
     content: &str,
     options: MarkdownOptions,
-) -> Result<AnyView, String> {
+) -> Result<AnyView, MarkdownError> {
     let renderer = MarkdownRenderer::new(options);
     renderer.render(content)
 }
+
+/// Markdown stripped to plain text, e.g. for search indexing
+pub fn render_to_plain_text(content: &str) -> Result<String, MarkdownError> {
+    MarkdownRenderer::new(MarkdownOptions::default()).render_to_plain_text(content)
+}
+
+/// Single-line plain-text excerpt of at most `max_chars` characters, cut at a word
+/// boundary, for meta descriptions and previews
+pub fn excerpt(content: &str, max_chars: usize) -> Result<String, MarkdownError> {
+    MarkdownRenderer::new(MarkdownOptions::default()).excerpt(content, max_chars)
+}
+
+/// Render markdown with custom options straight to an HTML string
+#[cfg(feature = "ssr")]
+pub fn render_markdown_to_html(
+    content: &str,
+    options: MarkdownOptions,
+) -> Result<String, MarkdownError> {
+    MarkdownRenderer::new(options).render_to_html(content)
+}
+
+/// Render markdown with custom options to canonical HTML for snapshot tests, see
+/// [`MarkdownRenderer::render_to_canonical_html`]
+#[cfg(feature = "ssr")]
+pub fn render_markdown_snapshot(
+    content: &str,
+    options: MarkdownOptions,
+) -> Result<String, MarkdownError> {
+    MarkdownRenderer::new(options).render_to_canonical_html(content)
+}
@@ -0,0 +1,156 @@
+use pulldown_cmark::Event;
+use std::ops::{Deref, Range};
+
+/// Index of every start tag's matching end tag, computed in one pass over a document so
+/// nested blocks don't rescan their contents
+pub(crate) struct MatchTable {
+    /// Index of the end tag matching the start tag at each index; the document length
+    /// for unclosed start tags
+    ends: Vec<usize>,
+}
+
+impl MatchTable {
+    pub(crate) fn new(events: &[Event]) -> Self {
+        let mut ends = vec![events.len(); events.len()];
+        let mut open = Vec::new();
+        for (i, event) in events.iter().enumerate() {
+            match event {
+                Event::Start(_) => open.push(i),
+                Event::End(_) => {
+                    if let Some(start) = open.pop() {
+                        ends[start] = i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { ends }
+    }
+}
+
+/// A run of a document's events, with its index in the document and the document's
+/// [`MatchTable`]. Sub-runs are taken with [`Events::sub`], so their end tags are
+/// always looked up in the table of the document they came from.
+#[derive(Clone, Copy)]
+pub(crate) struct Events<'e, 'a> {
+    events: &'e [Event<'a>],
+    /// Index of `events[0]` in the document
+    offset: usize,
+    table: &'e MatchTable,
+}
+
+impl<'e, 'a> Events<'e, 'a> {
+    /// The whole document indexed by `table`
+    pub(crate) fn new(events: &'e [Event<'a>], table: &'e MatchTable) -> Self {
+        debug_assert_eq!(events.len(), table.ends.len());
+        Self {
+            events,
+            offset: 0,
+            table,
+        }
+    }
+
+    /// The events in `range`, relative to this run
+    pub(crate) fn sub(self, range: Range<usize>) -> Self {
+        Self {
+            events: &self.events[range.clone()],
+            offset: self.offset + range.start,
+            table: self.table,
+        }
+    }
+
+    /// `(end_index, consumed)` of the start tag at `start`, relative to it: the index of
+    /// its end tag and the number of events up to and including it. A start tag whose end
+    /// lies outside this run consumes the rest of it.
+    pub(crate) fn end_of(&self, start: usize) -> (usize, usize) {
+        let end = self.table.ends[self.offset + start] - self.offset;
+        if end < self.events.len() {
+            (end - start, end - start + 1)
+        } else {
+            let rest = self.events.len() - start;
+            (rest, rest)
+        }
+    }
+}
+
+impl<'a> Deref for Events<'_, 'a> {
+    type Target = [Event<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Tag, TagEnd};
+
+    // [0] <p> [1] <em> [2] a [3] </em> [4] b [5] </p> [6] <p> [7] c [8] </p>
+    fn document() -> Vec<Event<'static>> {
+        vec![
+            Event::Start(Tag::Paragraph),
+            Event::Start(Tag::Emphasis),
+            Event::Text("a".into()),
+            Event::End(TagEnd::Emphasis),
+            Event::Text("b".into()),
+            Event::End(TagEnd::Paragraph),
+            Event::Start(Tag::Paragraph),
+            Event::Text("c".into()),
+            Event::End(TagEnd::Paragraph),
+        ]
+    }
+
+    #[test]
+    fn test_matches_nested_end_tags() {
+        let events = document();
+        let table = MatchTable::new(&events);
+        assert_eq!(table.ends[0], 5);
+        assert_eq!(table.ends[1], 3);
+        assert_eq!(table.ends[6], 8);
+
+        let events = Events::new(&events, &table);
+        assert_eq!(events.end_of(0), (5, 6));
+        assert_eq!(events.end_of(1), (2, 3));
+        assert_eq!(events.end_of(6), (2, 3));
+    }
+
+    #[test]
+    fn test_sub_runs_look_up_ends_by_document_index() {
+        let events = document();
+        let table = MatchTable::new(&events);
+        let events = Events::new(&events, &table);
+
+        // Inside the first paragraph: `<em>` is at 0 of the run and ends at 2 of it
+        let inner = events.sub(1..5);
+        assert_eq!(inner.end_of(0), (2, 3));
+        // The second paragraph on its own
+        let second = events.sub(6..9);
+        assert_eq!(second.end_of(0), (2, 3));
+        // A nested run of a run keeps adding offsets
+        let emphasis = inner.sub(0..3);
+        assert_eq!(emphasis.end_of(0), (2, 3));
+    }
+
+    #[test]
+    fn test_unclosed_tags_consume_the_rest() {
+        let events = vec![
+            Event::Start(Tag::Paragraph),
+            Event::Start(Tag::Emphasis),
+            Event::Text("a".into()),
+            Event::End(TagEnd::Emphasis),
+            Event::Text("b".into()),
+        ];
+        let table = MatchTable::new(&events);
+        assert_eq!(table.ends[0], events.len());
+
+        let events = Events::new(&events, &table);
+        assert_eq!(events.end_of(0), (5, 5));
+        assert_eq!(events.end_of(1), (2, 3));
+
+        // An end outside the run also consumes the rest of the run
+        let head = events.sub(0..3);
+        assert_eq!(head.end_of(1), (2, 2));
+        assert_eq!(head.sub(1..3).end_of(0), (2, 2));
+    }
+}
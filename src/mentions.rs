@@ -0,0 +1,95 @@
+use crate::callback::MentionResolver;
+
+/// Where a `@mention` or `#hashtag` links to, as returned by its resolver
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MentionTarget {
+    pub href: String,
+    /// Link text replacing the matched `@name` / `#topic`, e.g. a display name
+    pub label: Option<String>,
+}
+
+impl MentionTarget {
+    pub fn new(href: impl Into<String>) -> Self {
+        Self {
+            href: href.into(),
+            label: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// A piece of text split around resolved mentions and hashtags
+pub(crate) enum MentionSegment<'a> {
+    Text(&'a str),
+    Mention {
+        /// The matched `@name` / `#topic`, sigil included
+        source: &'a str,
+        hashtag: bool,
+        target: MentionTarget,
+    },
+}
+
+/// Split `text` around the `@name`s and `#topic`s the resolvers link; unresolved ones
+/// stay text
+pub(crate) fn mention_segments<'a>(
+    text: &'a str,
+    mentions: Option<&MentionResolver>,
+    hashtags: Option<&MentionResolver>,
+) -> Vec<MentionSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+
+    for (at, sigil) in text.char_indices() {
+        let resolver = match sigil {
+            '@' => mentions,
+            '#' => hashtags,
+            _ => continue,
+        };
+        let Some(resolver) = resolver else {
+            continue;
+        };
+        // `a@b.com` and `issue#2` aren't mentions; skip matches inside the previous one
+        if at < text_start || text[..at].chars().next_back().is_some_and(is_name_char) {
+            continue;
+        }
+        let name = mention_name(&text[at + 1..]);
+        if name.is_empty() || (sigil == '#' && name.chars().all(|c| c.is_ascii_digit())) {
+            continue;
+        }
+        let Some(target) = resolver(name) else {
+            continue;
+        };
+        if at > text_start {
+            segments.push(MentionSegment::Text(&text[text_start..at]));
+        }
+        let end = at + 1 + name.len();
+        segments.push(MentionSegment::Mention {
+            source: &text[at..end],
+            hashtag: sigil == '#',
+            target,
+        });
+        text_start = end;
+    }
+    if text_start < text.len() {
+        segments.push(MentionSegment::Text(&text[text_start..]));
+    }
+
+    segments
+}
+
+/// The name after a sigil: word characters, with inner `.` and `-` (`@jane.doe`)
+fn mention_name(rest: &str) -> &str {
+    let end = rest
+        .find(|c: char| !(is_name_char(c) || c == '.' || c == '-'))
+        .unwrap_or(rest.len());
+    rest[..end].trim_end_matches(['.', '-'])
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -0,0 +1,338 @@
+//! Tailwind class conflict resolution in the spirit of `tailwind-merge`: of two utilities
+//! setting the same property under the same variants, the later one wins.
+
+use std::collections::HashSet;
+
+/// `classes` with earlier utilities dropped when a later one sets the same property under
+/// the same variants (`text-red-500 text-blue-600` keeps only `text-blue-600`). Classes
+/// the resolver doesn't recognize are only deduplicated.
+pub fn merge_classes(classes: &str) -> String {
+    let tokens: Vec<&str> = classes.split_whitespace().collect();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut kept = Vec::with_capacity(tokens.len());
+
+    for token in tokens.into_iter().rev() {
+        let (variants, utility) = split_variants(token);
+        let important = utility.starts_with('!');
+        let utility = utility.trim_start_matches('!');
+        let prefix = format!("{}{}", variants, if important { "!" } else { "" });
+
+        let Some(group) = utility_group(utility) else {
+            if taken.insert(format!("{}={}", prefix, utility)) {
+                kept.push(token);
+            }
+            continue;
+        };
+        if taken.contains(&format!("{}{}", prefix, group)) {
+            continue;
+        }
+        taken.insert(format!("{}{}", prefix, group));
+        // A later shorthand (`p-4`) also overrides the sides it covers (`px-2`)
+        for covered in covered_groups(group) {
+            taken.insert(format!("{}{}", prefix, covered));
+        }
+        kept.push(token);
+    }
+
+    kept.reverse();
+    kept.join(" ")
+}
+
+/// Variant prefix (`dark:hover:`, sorted so order doesn't matter) and the bare utility
+fn split_variants(token: &str) -> (String, &str) {
+    let mut depth = 0usize;
+    let mut last = None;
+    for (i, c) in token.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => last = Some(i),
+            _ => {}
+        }
+    }
+    match last {
+        Some(i) => {
+            let mut variants: Vec<&str> = token[..i].split(':').collect();
+            variants.sort_unstable();
+            (format!("{}:", variants.join(":")), &token[i + 1..])
+        }
+        None => (String::new(), token),
+    }
+}
+
+/// Groups whose properties a later utility of `group` also sets
+fn covered_groups(group: &str) -> &'static [&'static str] {
+    match group {
+        "p" => &["px", "py", "pt", "pr", "pb", "pl", "ps", "pe"],
+        "px" => &["pl", "pr", "ps", "pe"],
+        "py" => &["pt", "pb"],
+        "m" => &["mx", "my", "mt", "mr", "mb", "ml", "ms", "me"],
+        "mx" => &["ml", "mr", "ms", "me"],
+        "my" => &["mt", "mb"],
+        "overflow" => &["overflow-x", "overflow-y"],
+        "inset" => &["inset-x", "inset-y", "top", "right", "bottom", "left"],
+        "inset-x" => &["left", "right"],
+        "inset-y" => &["top", "bottom"],
+        "rounded" => &[
+            "rounded-t",
+            "rounded-r",
+            "rounded-b",
+            "rounded-l",
+            "rounded-tl",
+            "rounded-tr",
+            "rounded-br",
+            "rounded-bl",
+        ],
+        "border-w" => &[
+            "border-w-x",
+            "border-w-y",
+            "border-w-t",
+            "border-w-r",
+            "border-w-b",
+            "border-w-l",
+        ],
+        _ => &[],
+    }
+}
+
+const DISPLAY: &[&str] = &[
+    "block",
+    "inline-block",
+    "inline",
+    "flex",
+    "inline-flex",
+    "grid",
+    "inline-grid",
+    "table",
+    "table-row",
+    "table-cell",
+    "contents",
+    "list-item",
+    "hidden",
+];
+const POSITION: &[&str] = &["static", "fixed", "absolute", "relative", "sticky"];
+const FONT_WEIGHTS: &[&str] = &[
+    "thin",
+    "extralight",
+    "light",
+    "normal",
+    "medium",
+    "semibold",
+    "bold",
+    "extrabold",
+    "black",
+];
+const TEXT_SIZES: &[&str] = &[
+    "xs", "sm", "base", "lg", "xl", "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl", "9xl",
+];
+const TEXT_ALIGN: &[&str] = &["left", "center", "right", "justify", "start", "end"];
+const BORDER_STYLES: &[&str] = &["solid", "dashed", "dotted", "double", "hidden", "none"];
+/// Spacing, sizing and other utilities whose group is the part before the value
+const VALUE_PREFIXES: &[&str] = &[
+    "min-w",
+    "max-w",
+    "min-h",
+    "max-h",
+    "space-x",
+    "space-y",
+    "gap-x",
+    "gap-y",
+    "inset-x",
+    "inset-y",
+    "underline-offset",
+    "scroll-mt",
+    "line-clamp",
+    "px",
+    "py",
+    "pt",
+    "pr",
+    "pb",
+    "pl",
+    "ps",
+    "pe",
+    "mx",
+    "my",
+    "mt",
+    "mr",
+    "mb",
+    "ml",
+    "ms",
+    "me",
+    "p",
+    "m",
+    "w",
+    "h",
+    "size",
+    "gap",
+    "inset",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "z",
+    "opacity",
+    "leading",
+    "tracking",
+    "duration",
+    "delay",
+    "ease",
+    "cursor",
+    "select",
+    "justify",
+    "items",
+    "self",
+    "content",
+    "align",
+    "order",
+    "basis",
+    "grow",
+    "shrink",
+    "columns",
+    "aspect",
+    "from",
+    "via",
+    "to",
+    "decoration",
+    "list",
+    "whitespace",
+    "break",
+    "object",
+    "overflow-x",
+    "overflow-y",
+    "overflow",
+    "shadow",
+    "transition",
+    "outline",
+    "backdrop",
+];
+
+/// Conflict group of a bare utility, `None` when it isn't recognized
+fn utility_group(utility: &str) -> Option<&'static str> {
+    let utility = utility.strip_prefix('-').unwrap_or(utility);
+    if let Some(group) = exact_group(utility) {
+        return Some(group);
+    }
+
+    let (prefix, value) = match utility.split_once('-') {
+        Some(parts) => parts,
+        None => (utility, ""),
+    };
+    match prefix {
+        "text" => Some(if TEXT_SIZES.contains(&value) || is_length(value) {
+            "text-size"
+        } else if TEXT_ALIGN.contains(&value) {
+            "text-align"
+        } else {
+            "text-color"
+        }),
+        "font" => Some(if FONT_WEIGHTS.contains(&value) {
+            "font-weight"
+        } else {
+            "font-family"
+        }),
+        "bg" => Some(if value.starts_with("gradient") || value == "none" {
+            "bg-image"
+        } else {
+            "bg-color"
+        }),
+        "border" => Some(border_group(value)),
+        "rounded" => Some(rounded_group(value)),
+        "divide" => Some(match value.split_once('-') {
+            Some((axis @ ("x" | "y"), _)) => divide_width(axis),
+            _ if value == "x" || value == "y" => divide_width(value),
+            _ => "divide-color",
+        }),
+        "ring" => Some(if value.is_empty() || value.parse::<u32>().is_ok() {
+            "ring-width"
+        } else {
+            "ring-color"
+        }),
+        _ => VALUE_PREFIXES
+            .iter()
+            .filter(|candidate| {
+                utility == **candidate
+                    || utility
+                        .strip_prefix(**candidate)
+                        .is_some_and(|rest| rest.starts_with('-'))
+            })
+            .max_by_key(|candidate| candidate.len())
+            .copied(),
+    }
+}
+
+/// Utilities that are a whole group on their own (`block`, `italic`, …)
+fn exact_group(utility: &str) -> Option<&'static str> {
+    if DISPLAY.contains(&utility) {
+        return Some("display");
+    }
+    if POSITION.contains(&utility) {
+        return Some("position");
+    }
+    Some(match utility {
+        "italic" | "not-italic" => "font-style",
+        "underline" | "overline" | "line-through" | "no-underline" => "text-decoration",
+        "uppercase" | "lowercase" | "capitalize" | "normal-case" => "text-transform",
+        "truncate" | "text-ellipsis" | "text-clip" => "text-overflow",
+        "sr-only" | "not-sr-only" => "sr-only",
+        "list-inside" | "list-outside" => "list-position",
+        "visible" | "invisible" | "collapse" => "visibility",
+        _ => return None,
+    })
+}
+
+fn border_group(value: &str) -> &'static str {
+    if value.is_empty() || value.parse::<u32>().is_ok() || value.starts_with("[length:") {
+        return "border-w";
+    }
+    if BORDER_STYLES.contains(&value) {
+        return "border-style";
+    }
+    let (side, rest) = value.split_once('-').unwrap_or((value, ""));
+    let side_group = match side {
+        "x" => Some(("border-w-x", "border-color-x")),
+        "y" => Some(("border-w-y", "border-color-y")),
+        "t" => Some(("border-w-t", "border-color-t")),
+        "r" => Some(("border-w-r", "border-color-r")),
+        "b" => Some(("border-w-b", "border-color-b")),
+        "l" => Some(("border-w-l", "border-color-l")),
+        _ => None,
+    };
+    match side_group {
+        Some((width, _)) if rest.is_empty() || rest.parse::<u32>().is_ok() || is_length(rest) => {
+            width
+        }
+        Some((_, color)) => color,
+        None => "border-color",
+    }
+}
+
+fn rounded_group(value: &str) -> &'static str {
+    let side = value.split_once('-').map_or(value, |(side, _)| side);
+    match side {
+        "t" => "rounded-t",
+        "r" => "rounded-r",
+        "b" => "rounded-b",
+        "l" => "rounded-l",
+        "tl" => "rounded-tl",
+        "tr" => "rounded-tr",
+        "br" => "rounded-br",
+        "bl" => "rounded-bl",
+        _ => "rounded",
+    }
+}
+
+fn divide_width(axis: &str) -> &'static str {
+    if axis == "x" {
+        "divide-x"
+    } else {
+        "divide-y"
+    }
+}
+
+/// Arbitrary lengths like `[85%]`, `[2em]` or `[length:var(--size)]`
+fn is_length(value: &str) -> bool {
+    let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+        return false;
+    };
+    inner.starts_with("length:") || inner.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+}
@@ -0,0 +1,163 @@
+use crate::callback::MarkdownCallback;
+use crate::compat::*;
+use crate::components::UnsupportedKind;
+use std::sync::Arc;
+
+/// Replacement renderer for one element kind, receiving that element's props
+pub type ComponentOverride<P> = MarkdownCallback<dyn Fn(P) -> AnyView + Send + Sync>;
+
+/// A fenced or indented code block
+pub struct CodeBlockProps {
+    /// Fence language (`rust` in ```` ```rust ````), `None` for indented or bare fences
+    pub language: Option<String>,
+    /// The code, unescaped
+    pub code: String,
+    /// File name from a `title="..."` / `file="..."` fence attribute
+    pub title: Option<String>,
+}
+
+/// A link, with its already rendered content
+pub struct LinkProps {
+    /// Resolved href (in-document anchors honor `anchor_base`)
+    pub href: String,
+    pub title: Option<String>,
+    pub children: AnyView,
+}
+
+/// An image
+pub struct ImageProps {
+    /// Source after any `image_src_transform`
+    pub src: String,
+    pub srcset: Option<String>,
+    pub sizes: Option<String>,
+    /// Intrinsic size from `image_dimensions`
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Set when `lazy_images` is enabled
+    pub lazy: bool,
+    pub alt: String,
+    pub title: Option<String>,
+}
+
+/// A heading, with its already rendered content
+pub struct HeadingProps {
+    /// Level 1–6, after any `H1Policy` demotion
+    pub level: u8,
+    /// Slug or explicit id, when heading ids are enabled
+    pub id: Option<String>,
+    pub children: AnyView,
+}
+
+/// A table, with its already rendered head and body rows
+pub struct TableProps {
+    pub children: AnyView,
+}
+
+/// A plain blockquote (alerts keep their callout rendering)
+pub struct BlockquoteProps {
+    pub children: AnyView,
+}
+
+/// Content the renderer deliberately didn't render, shown under
+/// `UnsupportedContent::Placeholder`
+pub struct PlaceholderProps {
+    pub kind: UnsupportedKind,
+    /// The markdown or HTML that was left unrendered
+    pub source: String,
+    /// Whether it stood as a block (display math, an HTML block) rather than inline
+    pub block: bool,
+}
+
+/// Custom views replacing the default rendering of individual elements.
+///
+/// Every field left `None` keeps the built-in rendering:
+///
+/// ```rust,ignore
+/// let components = MarkdownComponents::new().with_code_block(|props: CodeBlockProps| {
+///     view! { <MyCodeBlock language=props.language code=props.code /> }.into_any()
+/// });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MarkdownComponents {
+    pub code_block: Option<ComponentOverride<CodeBlockProps>>,
+    pub link: Option<ComponentOverride<LinkProps>>,
+    pub image: Option<ComponentOverride<ImageProps>>,
+    pub heading: Option<ComponentOverride<HeadingProps>>,
+    pub table: Option<ComponentOverride<TableProps>>,
+    pub blockquote: Option<ComponentOverride<BlockquoteProps>>,
+    pub placeholder: Option<ComponentOverride<PlaceholderProps>>,
+}
+
+impl MarkdownComponents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render code blocks with `f`
+    #[must_use]
+    pub fn with_code_block(
+        mut self,
+        f: impl Fn(CodeBlockProps) -> AnyView + Send + Sync + 'static,
+    ) -> Self {
+        let f: Arc<dyn Fn(CodeBlockProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.code_block = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render links with `f`
+    #[must_use]
+    pub fn with_link(mut self, f: impl Fn(LinkProps) -> AnyView + Send + Sync + 'static) -> Self {
+        let f: Arc<dyn Fn(LinkProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.link = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render images with `f`
+    #[must_use]
+    pub fn with_image(mut self, f: impl Fn(ImageProps) -> AnyView + Send + Sync + 'static) -> Self {
+        let f: Arc<dyn Fn(ImageProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.image = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render headings with `f`
+    #[must_use]
+    pub fn with_heading(
+        mut self,
+        f: impl Fn(HeadingProps) -> AnyView + Send + Sync + 'static,
+    ) -> Self {
+        let f: Arc<dyn Fn(HeadingProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.heading = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render tables with `f`
+    #[must_use]
+    pub fn with_table(mut self, f: impl Fn(TableProps) -> AnyView + Send + Sync + 'static) -> Self {
+        let f: Arc<dyn Fn(TableProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.table = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render plain blockquotes with `f`
+    #[must_use]
+    pub fn with_blockquote(
+        mut self,
+        f: impl Fn(BlockquoteProps) -> AnyView + Send + Sync + 'static,
+    ) -> Self {
+        let f: Arc<dyn Fn(BlockquoteProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.blockquote = Some(ComponentOverride::from_arc(f));
+        self
+    }
+
+    /// Render unsupported content placeholders with `f`
+    #[must_use]
+    pub fn with_placeholder(
+        mut self,
+        f: impl Fn(PlaceholderProps) -> AnyView + Send + Sync + 'static,
+    ) -> Self {
+        let f: Arc<dyn Fn(PlaceholderProps) -> AnyView + Send + Sync> = Arc::new(f);
+        self.placeholder = Some(ComponentOverride::from_arc(f));
+        self
+    }
+}
@@ -0,0 +1,191 @@
+use crate::compat::*;
+use crate::components::{MarkdownClasses, MarkdownOptions};
+use crate::error::MarkdownError;
+use crate::figures::MarkdownFigure;
+use crate::plain::excerpt_of;
+#[cfg(feature = "ssr")]
+use crate::renderer::static_html;
+use crate::renderer::MarkdownRenderer;
+use crate::toc::toc_nav;
+use pulldown_cmark::{Event, Tag};
+use std::sync::Arc;
+
+/// A heading of a parsed document, as listed in its table of contents
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownHeading {
+    /// Heading level, 1–6 (after any `H1Policy` demotion)
+    pub level: u8,
+    /// Plain text of the heading
+    pub text: String,
+    /// Id of the heading element: its explicit `{#id}`, or its slug when `heading_ids` is
+    /// enabled. `None` when the heading renders without an id.
+    pub id: Option<String>,
+}
+
+/// A document parsed once and shared by [`MarkdownBody`], [`MarkdownFootnotes`] and
+/// [`MarkdownToc`], so layouts can place each part separately without reparsing.
+///
+/// Static site pipelines can render it to a view, HTML and plain text and extract its
+/// headings and figures from the same parse.
+///
+/// Cloning is cheap: the events and renderer are reference counted.
+#[derive(Clone)]
+pub struct ParsedMarkdown {
+    renderer: Arc<MarkdownRenderer>,
+    source: Arc<str>,
+    events: Arc<[Event<'static>]>,
+}
+
+impl MarkdownRenderer {
+    /// Parse `content` once for rendering it, or its parts, many times. Input over the
+    /// `max_input_len` and `max_nesting_depth` limits fails here, so the parts of a
+    /// [`ParsedMarkdown`] always render.
+    pub fn parse(self: &Arc<Self>, content: &str) -> Result<ParsedMarkdown, MarkdownError> {
+        let events: Vec<Event<'static>> = self
+            .parse_within_limits(content, |content| self.parse_events(content))?
+            .into_iter()
+            .map(Event::into_static)
+            .collect();
+
+        Ok(ParsedMarkdown {
+            renderer: Arc::clone(self),
+            source: content.into(),
+            events: events.into(),
+        })
+    }
+}
+
+impl ParsedMarkdown {
+    /// Parse `content` with the given options, see [`MarkdownRenderer::parse`]
+    pub fn new(content: &str, options: MarkdownOptions) -> Result<Self, MarkdownError> {
+        Arc::new(MarkdownRenderer::new(options)).parse(content)
+    }
+
+    /// The original markdown source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The renderer (and options) the document was parsed with
+    pub fn renderer(&self) -> &MarkdownRenderer {
+        &self.renderer
+    }
+
+    /// The parsed events, after the renderer's event transforms
+    pub fn events(&self) -> &[Event<'static>] {
+        &self.events
+    }
+
+    /// Headings in document order, with the ids used by the rendered body
+    pub fn headings(&self) -> Vec<MarkdownHeading> {
+        self.renderer.collect_headings(&self.events)
+    }
+
+    /// Numbered figures and listings in document order, with the ids used by the body
+    pub fn figures(&self) -> Vec<MarkdownFigure> {
+        self.renderer.collect_figures(&self.events)
+    }
+
+    /// Whether the document defines any footnotes
+    pub fn has_footnotes(&self) -> bool {
+        self.events
+            .iter()
+            .any(|event| matches!(event, Event::Start(Tag::FootnoteDefinition(_))))
+    }
+
+    /// The document with all formatting stripped, see
+    /// [`MarkdownRenderer::render_to_plain_text`]
+    pub fn plain_text(&self) -> String {
+        self.renderer.plain_text(&self.events)
+    }
+
+    /// Single-line plain text cut to at most `max_chars` characters, see
+    /// [`MarkdownRenderer::excerpt`]
+    pub fn excerpt(&self, max_chars: usize) -> String {
+        excerpt_of(&self.plain_text(), max_chars)
+    }
+
+    /// Render the whole document, footnotes section included, like
+    /// [`MarkdownRenderer::render`]
+    pub fn render_view(&self) -> AnyView {
+        self.renderer
+            .render_parsed(&self.source, &self.events, false)
+            .0
+    }
+
+    /// Render the whole document to an HTML string, like
+    /// [`MarkdownRenderer::render_to_html`] (and sharing its `render_cache` entries)
+    #[cfg(feature = "ssr")]
+    pub fn render_html(&self) -> Result<String, MarkdownError> {
+        self.renderer
+            .cached_html(&self.source, || Ok(static_html(self.render_view())))
+    }
+
+    /// Render the document without its footnote definitions
+    pub fn render_body(&self) -> AnyView {
+        self.renderer.render_body(&self.events)
+    }
+
+    /// Render only the footnote definitions
+    pub fn render_footnotes(&self) -> AnyView {
+        self.renderer.render_footnote_definitions(&self.events)
+    }
+}
+
+/// Body of a [`ParsedMarkdown`] document, without footnote definitions
+#[component]
+pub fn MarkdownBody(
+    /// The parsed document
+    parsed: ParsedMarkdown,
+    /// Optional CSS class for the wrapper (will be combined with Tailwind prose classes)
+    #[prop(optional)]
+    class: Option<String>,
+) -> impl IntoView {
+    let wrapper_class = crate::wrapper_class(&parsed.renderer, &parsed.source, class);
+
+    view! {
+        <div class=wrapper_class>
+            {parsed.render_body()}
+        </div>
+    }
+}
+
+/// Footnote definitions of a [`ParsedMarkdown`] document; renders nothing without footnotes
+#[component]
+pub fn MarkdownFootnotes(
+    /// The parsed document
+    parsed: ParsedMarkdown,
+    /// Optional CSS class for the section
+    #[prop(optional)]
+    class: Option<String>,
+) -> impl IntoView {
+    if !parsed.has_footnotes() {
+        return ().into_any();
+    }
+
+    let section_class = match class {
+        Some(c) => format!("{} {}", MarkdownClasses::FOOTNOTES, c),
+        None => MarkdownClasses::FOOTNOTES.to_string(),
+    };
+
+    view! {
+        <section class=section_class role="doc-endnotes">
+            {parsed.render_footnotes()}
+        </section>
+    }
+    .into_any()
+}
+
+/// Table of contents linking to the headings of a [`ParsedMarkdown`] document, with the
+/// same nested markup as [`TableOfContents`](crate::TableOfContents). Enable `heading_ids`
+/// so the body's headings have ids to link to.
+#[component]
+pub fn MarkdownToc(
+    /// The parsed document
+    parsed: ParsedMarkdown,
+    /// Optional CSS class for the `<nav>`
+    #[prop(optional)]
+    class: Option<String>,
+) -> impl IntoView {
+    toc_nav(&parsed.renderer, parsed.headings(), class)
+}
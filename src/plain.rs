@@ -0,0 +1,100 @@
+use crate::error::MarkdownError;
+use crate::renderer::MarkdownRenderer;
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+impl MarkdownRenderer {
+    /// `content` with all formatting stripped: blocks separated by blank lines, images
+    /// replaced by their alt text, raw HTML, frontmatter and footnote definitions left out
+    pub fn render_to_plain_text(&self, content: &str) -> Result<String, MarkdownError> {
+        let events = self.parse_within_limits(content, |c| self.parse_events(c))?;
+        Ok(self.plain_text(&events))
+    }
+
+    /// Plain text of already parsed events, see [`MarkdownRenderer::render_to_plain_text`]
+    pub(crate) fn plain_text(&self, events: &[Event]) -> String {
+        let mut text = String::new();
+        // Depth inside skipped content (footnote definitions, metadata)
+        let mut skipping = 0usize;
+
+        for event in events {
+            match event {
+                Event::Start(Tag::FootnoteDefinition(_) | Tag::MetadataBlock(_)) => skipping += 1,
+                Event::End(TagEnd::FootnoteDefinition | TagEnd::MetadataBlock(_)) => {
+                    skipping = skipping.saturating_sub(1)
+                }
+                _ if skipping > 0 => {}
+                Event::Text(t) | Event::Code(t) | Event::InlineMath(t) | Event::DisplayMath(t) => {
+                    text.push_str(t)
+                }
+                Event::SoftBreak => text.push(' '),
+                Event::HardBreak => text.push('\n'),
+                Event::End(
+                    TagEnd::Paragraph
+                    | TagEnd::Heading(_)
+                    | TagEnd::CodeBlock
+                    | TagEnd::Table
+                    | TagEnd::HtmlBlock,
+                ) => end_block(&mut text, "\n\n"),
+                Event::End(
+                    TagEnd::Item
+                    | TagEnd::TableHead
+                    | TagEnd::TableRow
+                    | TagEnd::DefinitionListTitle
+                    | TagEnd::DefinitionListDefinition,
+                ) => end_block(&mut text, "\n"),
+                Event::End(TagEnd::TableCell) => text.push('\t'),
+                Event::Rule => end_block(&mut text, "\n\n"),
+                _ => {}
+            }
+        }
+
+        text.trim().to_string()
+    }
+
+    /// Single-line plain text of `content`, cut at a word boundary to at most
+    /// `max_chars` characters (ellipsis included) for meta descriptions and previews
+    pub fn excerpt(&self, content: &str, max_chars: usize) -> Result<String, MarkdownError> {
+        Ok(excerpt_of(&self.render_to_plain_text(content)?, max_chars))
+    }
+}
+
+/// `text` on one line, cut at a word boundary to at most `max_chars` characters
+pub(crate) fn excerpt_of(text: &str, max_chars: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let full = words.join(" ");
+    if full.chars().count() <= max_chars {
+        return full;
+    }
+
+    let mut excerpt = String::new();
+    for word in words {
+        let len = excerpt.chars().count();
+        let extra = usize::from(len > 0) + word.chars().count();
+        // Room for the trailing ellipsis
+        if len + extra + 1 > max_chars {
+            break;
+        }
+        if len > 0 {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+    }
+    if excerpt.is_empty() {
+        // A single overlong word is cut mid-word
+        excerpt = full.chars().take(max_chars.saturating_sub(1)).collect();
+    }
+    excerpt.push('…');
+    excerpt
+}
+
+/// Close a block: trim trailing spaces and end with `separator`
+fn end_block(text: &mut String, separator: &str) {
+    let trimmed = text.trim_end_matches([' ', '\t']).len();
+    text.truncate(trimmed);
+    if !text.is_empty() && !text.ends_with(separator) {
+        while text.ends_with('\n') {
+            text.pop();
+        }
+        text.push_str(separator);
+    }
+}
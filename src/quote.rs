@@ -0,0 +1,136 @@
+use crate::error::MarkdownError;
+use crate::renderer::MarkdownRenderer;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use std::ops::Range;
+
+/// A character of the rendered plain text and the source bytes it came from
+struct SourceChar {
+    ch: char,
+    source: Range<usize>,
+}
+
+impl MarkdownRenderer {
+    /// Build a Markdown blockquote of the source behind a selection of rendered text.
+    ///
+    /// `selection` is the text the user highlighted in the rendered content (e.g. the
+    /// browser's `Selection::toString()`). It is located in the document's plain text
+    /// using source positions, widened so inline markup (`**bold**`, links, code spans)
+    /// is never cut in half, and returned with every line prefixed by `> `. Returns
+    /// `None` when the selection is empty or not part of the document, and an error when
+    /// `source` exceeds the configured input limits.
+    pub fn quote_reply(
+        &self,
+        source: &str,
+        selection: &str,
+    ) -> Result<Option<String>, MarkdownError> {
+        let needle = selection.split_whitespace().collect::<Vec<_>>().join(" ");
+        if needle.is_empty() {
+            return Ok(None);
+        }
+
+        self.check_input_len(source)?;
+        let parsed: Vec<_> = Parser::new_ext(source, self.parser_options())
+            .into_offset_iter()
+            .collect();
+        self.check_nesting_depth(parsed.iter().map(|(event, _)| event))?;
+
+        let mut chars: Vec<SourceChar> = Vec::new();
+        let mut inline_spans: Vec<Range<usize>> = Vec::new();
+
+        for (event, range) in parsed {
+            match event {
+                Event::Start(
+                    Tag::Emphasis
+                    | Tag::Strong
+                    | Tag::Strikethrough
+                    | Tag::Link { .. }
+                    | Tag::Superscript
+                    | Tag::Subscript,
+                ) => inline_spans.push(range),
+                Event::Code(code) => {
+                    inline_spans.push(range.clone());
+                    push_text(&mut chars, &code, source, range);
+                }
+                Event::Text(text) => push_text(&mut chars, &text, source, range),
+                Event::End(
+                    TagEnd::Emphasis
+                    | TagEnd::Strong
+                    | TagEnd::Strikethrough
+                    | TagEnd::Link
+                    | TagEnd::Superscript
+                    | TagEnd::Subscript,
+                ) => {}
+                Event::End(_) | Event::SoftBreak | Event::HardBreak => {
+                    push_space(&mut chars, range.start)
+                }
+                _ => {}
+            }
+        }
+
+        let plain: String = chars.iter().map(|c| c.ch).collect();
+        let Some(byte_start) = plain.find(&needle) else {
+            return Ok(None);
+        };
+        let first = plain[..byte_start].chars().count();
+        let last = first + needle.chars().count() - 1;
+
+        let mut start = chars[first].source.start;
+        let mut end = chars[last].source.end;
+
+        // Widen until no inline element is only partially covered
+        let mut widened = true;
+        while widened {
+            widened = false;
+            for span in &inline_spans {
+                let overlaps = span.start < end && start < span.end;
+                let partial = span.start < start || span.end > end;
+                if overlaps && partial {
+                    start = start.min(span.start);
+                    end = end.max(span.end);
+                    widened = true;
+                }
+            }
+        }
+
+        let quoted = source[start..end]
+            .trim()
+            .lines()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    ">".to_string()
+                } else {
+                    format!("> {}", line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(Some(quoted))
+    }
+}
+
+fn push_text(chars: &mut Vec<SourceChar>, text: &str, source: &str, range: Range<usize>) {
+    // Text matching its source verbatim maps per character; escaped or entity-encoded
+    // text (and code spans) map every character to the whole event range
+    let verbatim = source.get(range.clone()) == Some(text);
+    for (offset, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            push_space(chars, range.start + offset);
+            continue;
+        }
+        let source = if verbatim {
+            range.start + offset..range.start + offset + ch.len_utf8()
+        } else {
+            range.clone()
+        };
+        chars.push(SourceChar { ch, source });
+    }
+}
+
+fn push_space(chars: &mut Vec<SourceChar>, at: usize) {
+    if chars.last().is_some_and(|c| c.ch != ' ') {
+        chars.push(SourceChar {
+            ch: ' ',
+            source: at..at,
+        });
+    }
+}
@@ -0,0 +1,175 @@
+use crate::callback::ReferenceLinker;
+
+/// A GitHub-style reference found in text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepoReference {
+    /// `#123`, or `owner/repo#123` for another repository
+    Issue { repo: Option<String>, number: u64 },
+    /// A commit SHA of 7 to 40 lowercase hex digits
+    Commit { sha: String },
+}
+
+impl RepoReference {
+    /// Link text: the reference as written, SHAs shortened to 7 digits
+    pub fn label(&self) -> String {
+        match self {
+            RepoReference::Issue { repo: None, number } => format!("#{}", number),
+            RepoReference::Issue {
+                repo: Some(repo),
+                number,
+            } => format!("{}#{}", repo, number),
+            RepoReference::Commit { sha } => sha.get(..7).unwrap_or(sha).to_string(),
+        }
+    }
+
+    /// URL on a GitHub-like host, given the repository's URL (`https://github.com/o/r`)
+    pub fn url(&self, repo_url: &str) -> String {
+        let repo_url = repo_url.trim_end_matches('/');
+        match self {
+            RepoReference::Issue { repo: None, number } => {
+                format!("{}/issues/{}", repo_url, number)
+            }
+            RepoReference::Issue {
+                repo: Some(repo),
+                number,
+            } => {
+                // Swap `owner/repo` at the end of the URL for the referenced one
+                let host = repo_url.rsplitn(3, '/').nth(2).unwrap_or(repo_url);
+                format!("{}/{}/issues/{}", host, repo, number)
+            }
+            RepoReference::Commit { sha } => format!("{}/commit/{}", repo_url, sha),
+        }
+    }
+}
+
+/// A piece of text split around linked references
+pub(crate) enum ReferenceSegment<'a> {
+    Text(&'a str),
+    Link {
+        label: String,
+        href: String,
+        /// `issue` or `commit`, for `data-reference`
+        kind: &'static str,
+    },
+}
+
+/// Split `text` around the references `linker` resolves; the rest stays text
+pub(crate) fn reference_segments<'a>(
+    text: &'a str,
+    linker: &ReferenceLinker,
+) -> Vec<ReferenceSegment<'a>> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let found = match bytes[i] {
+            b'#' => issue_at(text, i, text_start),
+            b'0'..=b'9' | b'a'..=b'f' if i == 0 || !is_word_byte(bytes[i - 1]) => {
+                commit_at(text, i)
+            }
+            _ => None,
+        };
+        let Some((start, end, reference)) = found else {
+            i += 1;
+            continue;
+        };
+        let Some(href) = linker(&reference) else {
+            i = end;
+            continue;
+        };
+        if start > text_start {
+            segments.push(ReferenceSegment::Text(&text[text_start..start]));
+        }
+        let kind = match reference {
+            RepoReference::Issue { .. } => "issue",
+            RepoReference::Commit { .. } => "commit",
+        };
+        segments.push(ReferenceSegment::Link {
+            label: reference.label(),
+            href,
+            kind,
+        });
+        text_start = end;
+        i = end;
+    }
+    if text_start < text.len() {
+        segments.push(ReferenceSegment::Text(&text[text_start..]));
+    }
+
+    segments
+}
+
+/// `#123` or `owner/repo#123` whose `#` is at `hash`, as `(start, end, reference)`
+fn issue_at(text: &str, hash: usize, min_start: usize) -> Option<(usize, usize, RepoReference)> {
+    let bytes = text.as_bytes();
+    let digits = bytes[hash + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    let end = hash + 1 + digits;
+    if digits == 0 || bytes.get(end).is_some_and(|b| is_word_byte(*b)) {
+        return None;
+    }
+    let number = text[hash + 1..end].parse().ok()?;
+
+    let repo_start = scan_back(bytes, hash);
+    if repo_start == hash {
+        return Some((hash, end, RepoReference::Issue { repo: None, number }));
+    }
+    // `owner/repo` right before the `#`
+    let slash = repo_start.checked_sub(1)?;
+    if bytes[slash] != b'/' {
+        return None;
+    }
+    let owner_start = scan_back(bytes, slash);
+    if owner_start == slash
+        || owner_start < min_start
+        || owner_start > 0 && matches!(bytes[owner_start - 1], b'/' | b'@')
+    {
+        return None;
+    }
+    let repo = text[owner_start..hash].to_string();
+    Some((
+        owner_start,
+        end,
+        RepoReference::Issue {
+            repo: Some(repo),
+            number,
+        },
+    ))
+}
+
+/// Start of the run of name characters (`\w`, `.`, `-`) ending at `end`
+fn scan_back(bytes: &[u8], end: usize) -> usize {
+    let mut start = end;
+    while start > 0 && (is_word_byte(bytes[start - 1]) || matches!(bytes[start - 1], b'.' | b'-')) {
+        start -= 1;
+    }
+    start
+}
+
+/// A commit SHA word starting at `start`
+fn commit_at(text: &str, start: usize) -> Option<(usize, usize, RepoReference)> {
+    let bytes = text.as_bytes();
+    let len = bytes[start..]
+        .iter()
+        .take_while(|b| is_word_byte(**b))
+        .count();
+    let word = &bytes[start..start + len];
+    let is_sha = (7..=40).contains(&len)
+        && word.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        // Plain numbers and words like `defaced` aren't SHAs
+        && word.iter().any(u8::is_ascii_digit)
+        && word.iter().any(u8::is_ascii_alphabetic);
+    is_sha.then(|| {
+        let sha = text[start..start + len].to_string();
+        (start, start + len, RepoReference::Commit { sha })
+    })
+}
+
+/// Letters, digits, `_` and any non-ASCII byte count as part of a word
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii()
+}
@@ -1,38 +1,896 @@
-use crate::components::{get_code_theme_classes, MarkdownClasses, MarkdownOptions};
-use leptos::prelude::*;
-use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
+use crate::annotate::{annotated_blocks, is_annotated, BlockAnnotation};
+use crate::autolink::autolink_events;
+use crate::chat::ChatSyntax;
+use crate::citations::{citation_segments, reference_id, Citation, CiteItem, TextSegment};
+use crate::classes::{Class, ClassBundle};
+use crate::compat::*;
+use crate::components::{
+    AlertKind, H1Policy, ImageSource, MarkdownOptions, RawHtmlPolicy, ScopeClass, SoftBreakMode,
+    StrikethroughElement, UnsupportedContent, UnsupportedKind,
+};
+use crate::containers::{
+    container_close, container_marker, split_container_markers, ContainerMarker,
+};
+use crate::emoji::{emoji_segments, EmojiSegment};
+use crate::error::MarkdownError;
+use crate::fence::{fence_title, highlight_lines, highlighted_lines};
+use crate::figures::{
+    figure_caption, is_lof_placeholder, listing_caption, FigureCounter, FigureKind, MarkdownFigure,
+};
+use crate::filter::apply_block_filter;
+use crate::footnotes::{extract_inline_footnotes, Footnotes};
+use crate::frontmatter::Frontmatter;
+use crate::kbd::{kbd_code_keys, kbd_segments, KbdSegment};
+use crate::matching::{Events, MatchTable};
+use crate::mentions::{mention_segments, MentionSegment};
+use crate::overrides::{
+    BlockquoteProps, CodeBlockProps, HeadingProps, ImageProps, LinkProps, PlaceholderProps,
+    TableProps,
+};
+use crate::parsed::MarkdownHeading;
+use crate::references::{reference_segments, ReferenceSegment};
+use crate::routes::{route_segments, RouteDirective, RouteSegment};
+use crate::sanitize::inline_html_element;
+#[cfg(feature = "ammonia")]
+use crate::sanitize::sanitize_html;
+use crate::slug::Slugger;
+use crate::spans::{span_close, split_span_delimiters, SpanKind};
+use crate::tabs::{code_tabs, container_tabs, Tab};
+use crate::warnings::{sanitize_events, RenderWarning};
+use pulldown_cmark::{
+    BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Options, Parser, Tag,
+    TagEnd,
+};
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+use std::time::Duration;
 
+/// Renders markdown to Leptos views with a fixed set of options.
+///
+/// The renderer is `Send + Sync` and holds no per-document state, so an SSR server can
+/// build one at startup and share it (e.g. as `Arc<MarkdownRenderer>` in app state)
+/// across request handlers.
 pub struct MarkdownRenderer {
     options: MarkdownOptions,
+    classes: ClassBundle,
+}
+
+// Sharing renderers across threads is part of the public contract: adding an `Rc` or
+// `Cell` to any of these types must fail the build rather than break downstream servers.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MarkdownRenderer>();
+    assert_send_sync::<MarkdownOptions>();
+    assert_send_sync::<crate::MarkdownComponents>();
+    assert_send_sync::<crate::CodeBlockTheme>();
+    assert_send_sync::<crate::MarkdownFlavor>();
+    assert_send_sync::<crate::ParsedMarkdown>();
+};
+
+/// A rendered document with its warnings and cache metadata, from
+/// [`MarkdownRenderer::render_with_metadata`]
+pub struct RenderedMarkdown {
+    pub view: AnyView,
+    pub warnings: Vec<RenderWarning>,
+    /// FNV-1a hash of the markdown source, stable across platforms and processes
+    pub content_hash: u64,
+    /// [`MarkdownRenderer::cache_key`] of the source: changes with the content or options
+    pub cache_key: String,
+}
+
+impl RenderedMarkdown {
+    /// Strong HTTP `ETag` value (quoted) for the rendered output
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.cache_key)
+    }
+}
+
+/// Render state carried from one section of a document to the next
+pub(crate) struct SectionState {
+    state: RenderState,
+    /// Footnote definitions of the sections rendered so far
+    footnote_definitions: Vec<Event<'static>>,
+}
+
+/// Mutable state threaded through a single render pass
+#[derive(Default)]
+pub(crate) struct RenderState {
+    warnings: Vec<RenderWarning>,
+    /// Assigns heading ids when set
+    slugger: Option<Slugger>,
+    /// Leave footnote definitions out (they are rendered separately)
+    skip_footnote_definitions: bool,
+    /// Numbered footnotes with backlinks; `None` renders definitions in place
+    footnotes: Option<Footnotes>,
+    /// Number of links enclosing the current event (context mask for text rewrites)
+    link_depth: usize,
+    /// Inside `<thead>`: cells render as `<th scope="col">`
+    in_table_head: bool,
+    /// Code blocks rendered so far (numbers the expand modals and collapse toggles)
+    code_blocks: usize,
+    /// Tab groups rendered so far (numbers their tab and panel ids)
+    tab_groups: usize,
+    /// Numbers figures and listings as they render
+    figure_counter: FigureCounter,
+    /// The document's figures, rendered in place of `[[lof]]` placeholders
+    figures: Vec<MarkdownFigure>,
+    /// Cited entries in first-citation order, listed in the bibliography
+    citations: Vec<(String, Citation)>,
+    /// Author mode: annotations of the document's top-level blocks, wrapped around them
+    block_annotations: Option<Vec<BlockAnnotation>>,
 }
 
 impl MarkdownRenderer {
     pub fn new(options: MarkdownOptions) -> Self {
-        Self { options }
+        let classes = ClassBundle::new(&options);
+        Self { options, classes }
     }
 
-    pub fn render(&self, content: &str) -> Result<AnyView, String> {
-        let mut parser_options = Options::empty();
+    /// Register a transform run on the parsed events before rendering, after any
+    /// already registered (see [`MarkdownOptions::with_event_transform`])
+    #[must_use]
+    pub fn with_transform(
+        mut self,
+        transform: impl for<'a> Fn(Vec<Event<'a>>) -> Vec<Event<'a>> + Send + Sync + 'static,
+    ) -> Self {
+        self.options = self.options.with_event_transform(transform);
+        self
+    }
+
+    /// Parse `content` and run the registered event transforms
+    pub(crate) fn parse_events<'a>(&self, content: &'a str) -> Vec<Event<'a>> {
+        let mut events = Vec::new();
+        self.push_parsed(content, self.parser_options(), &mut events);
+        self.apply_transforms(events)
+    }
+
+    /// Push the parser events of `content` to `events`, resolving chat syntax
+    fn push_parsed<'a>(&self, content: &'a str, options: Options, events: &mut Vec<Event<'a>>) {
+        let parser = Parser::new_ext(content, options);
+        match ChatSyntax::new(&self.options) {
+            Some(mut chat) => {
+                for (event, range) in parser.into_offset_iter() {
+                    chat.push(event, &content[range], events);
+                }
+            }
+            None => events.extend(parser),
+        }
+    }
+
+    fn apply_transforms<'a>(&self, events: Vec<Event<'a>>) -> Vec<Event<'a>> {
+        let inline_footnotes = self.options.enable_footnotes && self.options.inline_footnotes;
+        // `[@key]`, `[:route:...]`, `^[note]`, `:::` markers, `:emoji_codes:`, `@user_names`,
+        // `owner/repo#1` and bare URLs reach the renderer split over several text events
+        let mut events = if self.options.citation_resolver.is_some()
+            || !self.options.routes.is_empty()
+            || inline_footnotes
+            || self.options.containers
+            || self.options.emoji_shortcodes
+            || self.options.mention_resolver.is_some()
+            || self.options.hashtag_resolver.is_some()
+            || self.options.reference_linker.is_some()
+            || self.options.autolinks
+            || self.options.callouts
+            || self.options.highlight
+            || self.options.spoilers
+            || self.options.underline
+            || self.options.insertions
+            || self.options.kbd
+        {
+            merge_text_events(events)
+        } else {
+            events
+        };
+        if self.options.containers {
+            events = split_container_markers(events, self.options.tabs);
+        }
+        if inline_footnotes {
+            events = extract_inline_footnotes(events);
+        }
+        if self.options.autolinks {
+            events = autolink_events(events);
+        }
+        let span_kinds: Vec<SpanKind> = SpanKind::ALL
+            .into_iter()
+            .filter(|kind| self.span_enabled(*kind))
+            .collect();
+        if !span_kinds.is_empty() {
+            events = split_span_delimiters(events, &span_kinds);
+        }
+        let events = self
+            .options
+            .event_transforms
+            .iter()
+            .fold(events, |events, transform| transform(events));
+        match &self.options.block_filter {
+            Some(filter) => apply_block_filter(events, filter),
+            None => events,
+        }
+    }
+
+    pub fn render(&self, content: &str) -> Result<AnyView, MarkdownError> {
+        self.render_with_warnings(content).map(|(view, _)| view)
+    }
+
+    /// Render `content`, also returning everything that was altered or withheld
+    pub fn render_with_warnings(
+        &self,
+        content: &str,
+    ) -> Result<(AnyView, Vec<RenderWarning>), MarkdownError> {
+        self.render_pass(content, false)
+    }
+
+    /// Render `content` for an editor preview: every top-level block is wrapped with its
+    /// source line range (`data-source-lines="3-5"`) and badges for its diagnostics
+    /// (missing alt text, broken links), see [`MarkdownRenderer::annotate`]
+    pub fn render_annotated(&self, content: &str) -> Result<AnyView, MarkdownError> {
+        self.render_pass(content, true).map(|(view, _)| view)
+    }
+
+    pub(crate) fn render_pass(
+        &self,
+        content: &str,
+        annotate: bool,
+    ) -> Result<(AnyView, Vec<RenderWarning>), MarkdownError> {
+        let events = self.parse_within_limits(content, |content| {
+            if self.options.inline_only {
+                self.parse_inline_events(content)
+            } else {
+                self.parse_events(content)
+            }
+        })?;
+        Ok(self.render_parsed(content, &events, annotate))
+    }
+
+    /// Parse `content` with `parse` under the `max_input_len` and `max_nesting_depth`
+    /// limits. Every public entry point that renders or keeps parsed events goes through
+    /// here, so over-long input is refused before it is parsed.
+    pub(crate) fn parse_within_limits<'a>(
+        &self,
+        content: &'a str,
+        parse: impl FnOnce(&'a str) -> Vec<Event<'a>>,
+    ) -> Result<Vec<Event<'a>>, MarkdownError> {
+        self.check_input_len(content)?;
+        let events = parse(content);
+        self.check_nesting_depth(&events)?;
+        Ok(events)
+    }
+
+    /// Enforce `max_input_len`
+    pub(crate) fn check_input_len(&self, content: &str) -> Result<(), MarkdownError> {
+        match self.options.max_input_len {
+            Some(max) if content.len() > max => Err(MarkdownError::InputTooLong {
+                len: content.len(),
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Enforce `max_nesting_depth` on parsed events, before the recursive renderer
+    /// descends into them
+    pub(crate) fn check_nesting_depth<'e, 'a: 'e>(
+        &self,
+        events: impl IntoIterator<Item = &'e Event<'a>>,
+    ) -> Result<(), MarkdownError> {
+        let Some(max) = self.options.max_nesting_depth else {
+            return Ok(());
+        };
+        let mut depth = 0usize;
+        for event in events {
+            match event {
+                Event::Start(_) => {
+                    depth += 1;
+                    if depth > max {
+                        return Err(MarkdownError::NestingTooDeep { max });
+                    }
+                }
+                Event::End(_) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the already parsed `events` of `content` as a whole document
+    pub(crate) fn render_parsed(
+        &self,
+        content: &str,
+        events: &[Event],
+        annotate: bool,
+    ) -> (AnyView, Vec<RenderWarning>) {
+        let mut state = self.new_state(events);
+        if annotate {
+            state.block_annotations = Some(self.block_annotations(content, events));
+        }
+        let view = self.render_document(events, &mut state);
+        self.report_sanitized(content, &state.warnings);
+
+        (view, state.warnings)
+    }
+
+    /// Pass the content-altering warnings of one document to `on_sanitize`
+    fn report_sanitized(&self, content: &str, warnings: &[RenderWarning]) {
+        if let Some(handler) = &self.options.on_sanitize {
+            for event in sanitize_events(content, warnings) {
+                handler(&event);
+            }
+        }
+    }
+
+    /// Render `content` as a document of its own, except that heading ids continue from
+    /// `slugger`, which is left holding the ids handed out. Streamed blocks render through
+    /// here so repeated headings across blocks still get distinct ids.
+    pub(crate) fn render_with_slugger(
+        &self,
+        content: &str,
+        slugger: &mut Slugger,
+    ) -> Result<AnyView, MarkdownError> {
+        let events = self.parse_within_limits(content, |content| self.parse_events(content))?;
+        let mut state = self.new_state(&events);
+        if state.slugger.is_some() {
+            slugger.reserve_heading_ids(&events);
+            state.slugger = Some(std::mem::take(slugger));
+        }
+        let view = self.render_document(&events, &mut state);
+        if let Some(used) = state.slugger.take() {
+            *slugger = used;
+        }
+        self.report_sanitized(content, &state.warnings);
+        Ok(view)
+    }
+
+    /// Render `content` together with its warnings and cache metadata, so applications
+    /// get a cache key, ETag or memo key without hashing the source again
+    pub fn render_with_metadata(&self, content: &str) -> Result<RenderedMarkdown, MarkdownError> {
+        let (view, warnings) = self.render_with_warnings(content)?;
+        let content_hash = content_hash(content);
+        Ok(RenderedMarkdown {
+            view,
+            warnings,
+            content_hash,
+            cache_key: self.cache_key_for_hash(content_hash),
+        })
+    }
+
+    /// Fresh per-document state: headings get slug ids under `heading_ids`, footnotes are
+    /// numbered and their definitions collected at the end
+    fn new_state(&self, events: &[Event]) -> RenderState {
+        RenderState {
+            slugger: self
+                .options
+                .heading_ids
+                .then(|| Slugger::for_headings(events)),
+            skip_footnote_definitions: true,
+            footnotes: Some(Footnotes::collect(events)),
+            figures: if self.lof_enabled() {
+                self.collect_figures(events)
+            } else {
+                Vec::new()
+            },
+            ..RenderState::default()
+        }
+    }
+
+    /// Render a whole document, followed by its footnotes section
+    fn render_document(&self, events: &[Event], state: &mut RenderState) -> AnyView {
+        let (events, omitted_blocks, cut) = self.apply_output_cap(events);
+        let table = MatchTable::new(&events);
+        let events = Events::new(&events, &table);
+        let body = match state.block_annotations.take() {
+            Some(annotations) => self.render_annotated_events(events, annotations, state),
+            None => self.render_events(events, state),
+        };
+        let tail = (omitted_blocks > 0 || cut).then(|| {
+            state
+                .warnings
+                .push(RenderWarning::Truncated { omitted_blocks });
+            view! { <p class=self.classes.truncation.clone() aria-hidden="true">"…"</p> }
+        });
+        let bibliography = self.render_bibliography(state);
+        let body = view! { {body}{tail}{bibliography} }.into_any();
+        match self.render_footnote_list(events, state) {
+            Some(list) => view! {
+                {body}
+                <section class=self.classes.footnotes.clone() role="doc-endnotes">{list}</section>
+            }
+            .into_any(),
+            None => body,
+        }
+    }
+
+    /// Bibliography section listing the entries cited so far
+    fn render_bibliography(&self, state: &RenderState) -> Option<AnyView> {
+        if state.citations.is_empty() {
+            return None;
+        }
+        let entries = state
+            .citations
+            .iter()
+            .map(|(key, citation)| {
+                let reference = match &citation.url {
+                    Some(url) => self.plain_link(url.clone(), citation.reference.clone()),
+                    None => citation.reference.clone().into_any(),
+                };
+                view! { <li id=reference_id(key)>{reference}</li> }
+            })
+            .collect_view();
+        Some(
+            view! {
+                <section class=self.classes.bibliography.clone() role="doc-bibliography">
+                    <ol>{entries}</ol>
+                </section>
+            }
+            .into_any(),
+        )
+    }
+
+    /// Top-level blocks fitting `max_blocks` / `max_chars`, how many were left out, and
+    /// whether the first block was cut short. The first block is always kept, its text
+    /// cut to `max_chars` when it alone is longer; footnote definitions don't count
+    /// towards the cap.
+    fn apply_output_cap<'e, 'a>(
+        &self,
+        events: &'e [Event<'a>],
+    ) -> (Cow<'e, [Event<'a>]>, usize, bool) {
+        let (max_blocks, max_chars) = (self.options.max_blocks, self.options.max_chars);
+        if max_blocks.is_none() && max_chars.is_none() {
+            return (Cow::Borrowed(events), 0, false);
+        }
+
+        let table = MatchTable::new(events);
+        let indexed = Events::new(events, &table);
+        let mut kept = Vec::new();
+        let (mut blocks, mut chars, mut omitted) = (0usize, 0usize, 0usize);
+        let mut cut = false;
+        let mut i = 0;
+        while i < events.len() {
+            let consumed = match &events[i] {
+                Event::Start(_) => indexed.end_of(i).1,
+                _ => 1,
+            };
+            let block = indexed.sub(i..i + consumed);
+            i += consumed;
+
+            if let Event::Start(Tag::FootnoteDefinition(_)) = &block[0] {
+                kept.extend_from_slice(&block);
+                continue;
+            }
+            let block_chars = self.extract_text_content(&block).chars().count();
+            let fits = omitted == 0
+                && max_blocks.is_none_or(|max| blocks < max)
+                && max_chars.is_none_or(|max| chars + block_chars <= max);
+            if fits || blocks == 0 {
+                match max_chars {
+                    Some(max) if block_chars > max => {
+                        self.truncate_block(block, max, &mut kept);
+                        cut = true;
+                    }
+                    _ => kept.extend_from_slice(&block),
+                }
+                blocks += 1;
+                chars += block_chars;
+            } else {
+                omitted += 1;
+            }
+        }
+
+        (Cow::Owned(kept), omitted, cut)
+    }
+
+    /// Push `block` with its text cut after `max_chars` characters: markup opened before
+    /// the cut is closed, anything after it dropped
+    fn truncate_block<'a>(
+        &self,
+        block: Events<'_, 'a>,
+        max_chars: usize,
+        kept: &mut Vec<Event<'a>>,
+    ) {
+        let mut remaining = max_chars;
+        let mut i = 0;
+        while i < block.len() {
+            match &block[i] {
+                Event::Start(_) if remaining == 0 => {
+                    i += block.end_of(i).1;
+                    continue;
+                }
+                Event::Text(text) | Event::Code(text) if remaining > 0 => {
+                    let len = text.chars().count();
+                    if len <= remaining {
+                        kept.push(block[i].clone());
+                        remaining -= len;
+                    } else {
+                        let head: String = text.chars().take(remaining).collect();
+                        kept.push(match &block[i] {
+                            Event::Code(_) => Event::Code(head.into()),
+                            _ => Event::Text(head.into()),
+                        });
+                        remaining = 0;
+                    }
+                }
+                Event::End(_) => kept.push(block[i].clone()),
+                _ if remaining == 0 => {}
+                event => kept.push(event.clone()),
+            }
+            i += 1;
+        }
+    }
+
+    /// Footnote definitions as an ordered list, in footnote number order
+    fn render_footnote_list(&self, events: Events, state: &mut RenderState) -> Option<AnyView> {
+        let mut definitions = Vec::new();
+        let mut i = 0;
+        while i < events.len() {
+            if let Event::Start(Tag::FootnoteDefinition(label)) = &events[i] {
+                let number = state.footnotes.as_ref().map_or(0, |f| f.number(label));
+                let (_, consumed) = events.end_of(i);
+                definitions.push((number, events.sub(i..i + consumed)));
+                i += consumed;
+            } else {
+                i += 1;
+            }
+        }
+        if definitions.is_empty() {
+            return None;
+        }
+        definitions.sort_by_key(|(number, _)| *number);
+
+        let skip = std::mem::replace(&mut state.skip_footnote_definitions, false);
+        let items = definitions
+            .into_iter()
+            .map(|(_, definition)| self.render_event(definition, state).0)
+            .collect_view();
+        state.skip_footnote_definitions = skip;
+
+        Some(view! { <ol class=self.classes.footnote_list.clone()>{items}</ol> }.into_any())
+    }
+
+    /// Key identifying `content` rendered under these options in a
+    /// [`MarkdownCacheBackend`](crate::MarkdownCacheBackend)
+    pub fn cache_key(&self, content: &str) -> String {
+        self.cache_key_for_hash(content_hash(content))
+    }
+
+    fn cache_key_for_hash(&self, hash: u64) -> String {
+        // Callbacks only show up as opaque placeholders in the options' `Debug` output,
+        // so `cache_namespace` is what separates renderers differing in them
+        let key = format!(
+            "{:016x}-{:016x}-v{}",
+            hash,
+            content_hash(&format!("{:?}", self.options)),
+            env!("CARGO_PKG_VERSION")
+        );
+        match &self.options.cache_namespace {
+            Some(namespace) => format!("{}:{}", namespace, key),
+            None => key,
+        }
+    }
 
-        if self.options.enable_gfm {
-            parser_options.insert(Options::ENABLE_TABLES);
-            parser_options.insert(Options::ENABLE_FOOTNOTES);
-            parser_options.insert(Options::ENABLE_STRIKETHROUGH);
-            parser_options.insert(Options::ENABLE_TASKLISTS);
+    /// Render `content` to an HTML string with the same markup and classes as the view,
+    /// minus hydration markers, for server functions, feeds and emails. Served from
+    /// `render_cache` when configured.
+    #[cfg(feature = "ssr")]
+    pub fn render_to_html(&self, content: &str) -> Result<String, MarkdownError> {
+        self.cached_html(content, || {
+            self.render_pass(content, false)
+                .map(|(view, _)| static_html(view))
+        })
+    }
+
+    /// HTML of `content` from `render_cache`, produced by `render` and stored on a miss
+    #[cfg(feature = "ssr")]
+    pub(crate) fn cached_html(
+        &self,
+        content: &str,
+        render: impl FnOnce() -> Result<String, MarkdownError>,
+    ) -> Result<String, MarkdownError> {
+        let Some(cache) = &self.options.render_cache else {
+            return render();
+        };
+        let key = self.cache_key(content);
+        if let Some(html) = cache.get(&key) {
+            return Ok(html);
         }
+        let html = render()?;
+        cache.put(&key, html.clone());
+        Ok(html)
+    }
+
+    /// Options this renderer was built with
+    pub fn options(&self) -> &MarkdownOptions {
+        &self.options
+    }
+
+    /// Element classes combined from the options, for rendering outside this module
+    pub(crate) fn classes(&self) -> &ClassBundle {
+        &self.classes
+    }
+
+    /// The `md-scope-*` class for `content` under the `scope_class` option, if enabled.
+    ///
+    /// Use it to write CSS targeting this markdown instance. Hashed classes follow the
+    /// content, so instances with identical content share one; name the scope to keep
+    /// them apart.
+    pub fn scope_class(&self, content: &str) -> Option<String> {
+        self.options.scope_class.as_ref().map(|scope| match scope {
+            ScopeClass::ContentHash => format!("md-scope-{:08x}", content_hash(content) as u32),
+            ScopeClass::Named(name) => format!("md-scope-{}", name),
+        })
+    }
+
+    /// Render many small documents (comments, chat history) with one renderer.
+    ///
+    /// Parser options and the event buffer are reused across documents, so per-item
+    /// setup cost stays flat for list views rendering hundreds of snippets. A document
+    /// over the input limits fails on its own without affecting the others.
+    pub fn render_batch(&self, documents: &[&str]) -> Vec<Result<AnyView, MarkdownError>> {
+        let parser_options = self.parser_options();
+        let mut events: Vec<Event> = Vec::new();
+        let mut views = Vec::with_capacity(documents.len());
+
+        for &content in documents {
+            let parsed = self.parse_within_limits(content, |content| {
+                events.clear();
+                self.push_parsed(content, parser_options, &mut events);
+                self.apply_transforms(std::mem::take(&mut events))
+            });
+            match parsed {
+                Ok(parsed) => {
+                    events = parsed;
+                    let mut state = self.new_state(&events);
+                    views.push(Ok(self.render_document(&events, &mut state)));
+                    self.report_sanitized(content, &state.warnings);
+                }
+                Err(err) => views.push(Err(err)),
+            }
+        }
+
+        views
+    }
+
+    /// Render only the first block-level element of `content`, for notification and toast
+    /// previews. Blocks that render nothing (frontmatter, HTML comments) are skipped, and
+    /// parsing stops at the next visible block; the flag tells whether one follows. Input
+    /// limits apply to the source up to the end of the rendered block.
+    pub fn render_first_block(&self, content: &str) -> Result<(AnyView, bool), MarkdownError> {
+        let parser = Parser::new_ext(content, self.parser_options()).into_offset_iter();
+        let mut chat = ChatSyntax::new(&self.options);
+        let mut first = Vec::new();
+        let mut block = Vec::new();
+        let mut prefix_len = 0;
+        let mut has_more = false;
+        let mut depth = 0usize;
+
+        for (event, range) in parser {
+            match &event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            match chat.as_mut() {
+                Some(chat) => chat.push(event, &content[range.clone()], &mut block),
+                None => block.push(event),
+            }
+            if depth > 0 || renders_nothing(&block) {
+                if depth == 0 {
+                    block.clear();
+                }
+                continue;
+            }
+            if !first.is_empty() {
+                has_more = true;
+                break;
+            }
+            first = std::mem::take(&mut block);
+            prefix_len = range.end;
+        }
+
+        self.check_input_len(&content[..prefix_len])?;
+        let events = self.apply_transforms(first);
+        self.check_nesting_depth(&events)?;
+
+        let mut state = self.new_state(&events);
+        let table = MatchTable::new(&events);
+        let view = self.render_events(Events::new(&events, &table), &mut state);
+        self.report_sanitized(content, &state.warnings);
+        Ok((view, has_more))
+    }
+
+    /// Parse `content` once and split the events into top-level blocks, so callers can
+    /// render them one at a time with [`MarkdownRenderer::render_block`]
+    pub(crate) fn parse_blocks(
+        &self,
+        content: &str,
+    ) -> Result<Vec<Vec<Event<'static>>>, MarkdownError> {
+        let mut blocks = Vec::new();
+        let mut current = Vec::new();
+        let mut depth = 0usize;
+
+        for event in self.parse_within_limits(content, |c| self.parse_events(c))? {
+            match &event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            current.push(event.into_static());
+            if depth == 0 {
+                blocks.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            blocks.push(current);
+        }
+
+        Ok(blocks)
+    }
+
+    /// State shared by `blocks` rendered one at a time with
+    /// [`MarkdownRenderer::render_block`]: heading ids stay unique and footnotes are
+    /// numbered across all of them, with definitions rendered in place
+    pub(crate) fn block_state<'e>(
+        &self,
+        blocks: impl IntoIterator<Item = &'e Vec<Event<'static>>>,
+    ) -> RenderState {
+        let events: Vec<Event> = blocks.into_iter().flatten().cloned().collect();
+        RenderState {
+            skip_footnote_definitions: false,
+            ..self.new_state(&events)
+        }
+    }
+
+    /// Render one block returned by [`MarkdownRenderer::parse_blocks`]. A numbered
+    /// footnote definition gets a list of its own, starting at its number.
+    pub(crate) fn render_block(&self, events: &[Event], state: &mut RenderState) -> AnyView {
+        let table = MatchTable::new(events);
+        let view = self.render_events(Events::new(events, &table), state);
+        match (events.first(), &state.footnotes) {
+            (Some(Event::Start(Tag::FootnoteDefinition(label))), Some(footnotes)) => view! {
+                <ol class=self.classes.footnote_list.clone() start=footnotes.number(label)>
+                    {view}
+                </ol>
+            }
+            .into_any(),
+            _ => view,
+        }
+    }
+
+    /// State for rendering a document section by section with
+    /// [`MarkdownRenderer::render_section`]
+    pub(crate) fn section_state(&self, content: &str) -> SectionState {
+        SectionState {
+            state: RenderState {
+                slugger: self.options.heading_ids.then(Slugger::default),
+                skip_footnote_definitions: true,
+                footnotes: Some(Footnotes::with_prefix(format!(
+                    "{:08x}",
+                    content_hash(content) as u32
+                ))),
+                ..RenderState::default()
+            },
+            footnote_definitions: Vec::new(),
+        }
+    }
+
+    /// Parse and render one section of a document. Heading ids, footnote numbers and
+    /// citations continue from the previous sections; footnote definitions are held back
+    /// for [`MarkdownRenderer::finish_sections`]. The caller checks the whole document's
+    /// length; each section is held to `max_nesting_depth`.
+    pub(crate) fn render_section(
+        &self,
+        source: &str,
+        section: &mut SectionState,
+    ) -> Result<AnyView, MarkdownError> {
+        let events = self.parse_events(source);
+        self.check_nesting_depth(&events)?;
+        let state = &mut section.state;
+        if let Some(footnotes) = &mut state.footnotes {
+            footnotes.extend(&events);
+        }
+        if let Some(slugger) = &mut state.slugger {
+            slugger.reserve_heading_ids(&events);
+        }
+        let table = MatchTable::new(&events);
+        let events = Events::new(&events, &table);
+        let mut i = 0;
+        while i < events.len() {
+            if matches!(events[i], Event::Start(Tag::FootnoteDefinition(_))) {
+                let (_, consumed) = events.end_of(i);
+                section.footnote_definitions.extend(
+                    events[i..i + consumed]
+                        .iter()
+                        .cloned()
+                        .map(Event::into_static),
+                );
+                i += consumed;
+            } else {
+                i += 1;
+            }
+        }
+        Ok(self.render_events(events, state))
+    }
 
-        let parser = Parser::new_ext(content, parser_options);
-        let events: Vec<Event> = parser.collect();
+    /// Bibliography and footnotes of a document rendered with
+    /// [`MarkdownRenderer::render_section`], once its last section is rendered
+    pub(crate) fn finish_sections(&self, section: &mut SectionState) -> AnyView {
+        let definitions = std::mem::take(&mut section.footnote_definitions);
+        let state = &mut section.state;
+        let table = MatchTable::new(&definitions);
+        let bibliography = self.render_bibliography(state);
+        let definitions = Events::new(&definitions, &table);
+        let footnotes = self.render_footnote_list(definitions, state).map(|list| {
+            view! {
+                <section class=self.classes.footnotes.clone() role="doc-endnotes">{list}</section>
+            }
+        });
+        view! { {bibliography}{footnotes} }.into_any()
+    }
+
+    pub(crate) fn parser_options(&self) -> Options {
+        let mut parser_options = Options::empty();
+        // The GFM extensions only apply while GFM itself is on
+        let gfm = self.options.enable_gfm;
 
-        Ok(self.render_events(&events))
+        let flags = [
+            (gfm && self.options.enable_tables, Options::ENABLE_TABLES),
+            (
+                gfm && self.options.enable_footnotes,
+                Options::ENABLE_FOOTNOTES,
+            ),
+            (
+                gfm && self.options.enable_strikethrough,
+                Options::ENABLE_STRIKETHROUGH,
+            ),
+            (
+                gfm && self.options.enable_tasklists,
+                Options::ENABLE_TASKLISTS,
+            ),
+            // Disabled math is still parsed when it is replaced by a placeholder
+            (
+                self.options.enable_math
+                    || self.options.unsupported != UnsupportedContent::Fallback,
+                Options::ENABLE_MATH,
+            ),
+            (
+                self.options.enable_definition_lists,
+                Options::ENABLE_DEFINITION_LIST,
+            ),
+            (self.options.enable_superscript, Options::ENABLE_SUPERSCRIPT),
+            (self.options.enable_subscript, Options::ENABLE_SUBSCRIPT),
+            (
+                self.options.enable_heading_attributes,
+                Options::ENABLE_HEADING_ATTRIBUTES,
+            ),
+            (gfm && self.options.gfm_alerts, Options::ENABLE_GFM),
+            (self.options.wikilinks, Options::ENABLE_WIKILINKS),
+            (
+                self.options.enable_metadata_blocks,
+                Options::ENABLE_YAML_STYLE_METADATA_BLOCKS,
+            ),
+            (
+                self.options.enable_metadata_blocks,
+                Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS,
+            ),
+        ];
+        for (enabled, flag) in flags {
+            parser_options.set(flag, enabled);
+        }
+
+        parser_options
     }
 
-    fn render_events(&self, events: &[Event]) -> AnyView {
+    fn render_events(&self, events: Events, state: &mut RenderState) -> AnyView {
         let mut result = Vec::new();
         let mut i = 0;
 
         while i < events.len() {
-            let (rendered, consumed) = self.render_event(&events[i..]);
+            if let Some((kind, close)) = span_close(&events[i..]) {
+                if self.span_enabled(kind) {
+                    result.push(self.render_span(kind, events.sub(i + 1..i + close), state));
+                    i += close + 1;
+                    continue;
+                }
+            }
+            let (rendered, consumed) = self.render_event(events.sub(i..events.len()), state);
             result.push(rendered);
             i += consumed;
         }
@@ -40,20 +898,137 @@ impl MarkdownRenderer {
         result.into_iter().collect_view().into_any()
     }
 
-    fn render_event(&self, events: &[Event]) -> (AnyView, usize) {
+    fn span_enabled(&self, kind: SpanKind) -> bool {
+        match kind {
+            SpanKind::Mark => self.options.highlight,
+            SpanKind::Spoiler => self.options.spoilers,
+            SpanKind::Underline => self.options.underline,
+            SpanKind::Insert => self.options.insertions,
+        }
+    }
+
+    /// Inline span between paired delimiters, see [`SpanKind`]
+    fn render_span(&self, kind: SpanKind, inner: Events, state: &mut RenderState) -> AnyView {
+        let inner_content = self.render_events(inner, state);
+        match kind {
+            SpanKind::Mark => {
+                view! { <mark class=self.classes.mark.clone()>{inner_content}</mark> }.into_any()
+            }
+            // A checkbox keeps the spoiler revealed after a click, without scripts
+            SpanKind::Spoiler => view! {
+                <label class=self.classes.spoiler.clone()>
+                    <input type="checkbox" class="peer sr-only" aria-label="Reveal spoiler" />
+                    <span class=self.classes.spoiler_text.clone()>{inner_content}</span>
+                </label>
+            }
+            .into_any(),
+            SpanKind::Underline => {
+                view! { <u class=self.classes.underline.clone()>{inner_content}</u> }.into_any()
+            }
+            SpanKind::Insert => {
+                view! { <ins class=self.classes.ins.clone()>{inner_content}</ins> }.into_any()
+            }
+        }
+    }
+
+    /// Top-level events with each annotated block wrapped in its annotation. The output cap
+    /// only drops trailing blocks, so annotations still line up with what is kept.
+    fn render_annotated_events(
+        &self,
+        events: Events,
+        annotations: Vec<BlockAnnotation>,
+        state: &mut RenderState,
+    ) -> AnyView {
+        let mut annotations = annotations.into_iter();
+        let mut result = Vec::new();
+        let mut i = 0;
+
+        while i < events.len() {
+            let (rendered, consumed) = self.render_event(events.sub(i..events.len()), state);
+            // A container spans several top-level blocks; merge their annotations
+            let spanned = annotated_blocks(&events[i..i + consumed]).len();
+            let annotation = annotations
+                .by_ref()
+                .take(spanned)
+                .reduce(|mut merged, next| {
+                    merged.lines = merged
+                        .lines
+                        .zip(next.lines)
+                        .map(|(first, last)| *first.start()..=*last.end());
+                    merged.diagnostics.extend(next.diagnostics);
+                    merged
+                });
+            result.push(match annotation {
+                Some(annotation) if is_annotated(&events[i]) => {
+                    self.render_block_annotation(rendered, annotation)
+                }
+                _ => rendered,
+            });
+            i += consumed;
+        }
+
+        result.into_iter().collect_view().into_any()
+    }
+
+    fn render_block_annotation(&self, block: AnyView, annotation: BlockAnnotation) -> AnyView {
+        let lines = annotation.lines.map(|lines| {
+            if lines.start() == lines.end() {
+                lines.start().to_string()
+            } else {
+                format!("{}-{}", lines.start(), lines.end())
+            }
+        });
+        let label = lines.as_ref().map(|lines| format!("L{}", lines));
+        let badges = annotation
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                view! {
+                    <span
+                        class=self.classes.diagnostic.clone()
+                        data-diagnostic=diagnostic.as_str()
+                        title=diagnostic.detail().to_string()
+                    >
+                        {diagnostic.label()}
+                    </span>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <div class=self.classes.annotated_block.clone() data-source-lines=lines>
+                <div class=self.classes.annotation.clone() aria-hidden="true">
+                    {label}
+                    {badges}
+                </div>
+                {block}
+            </div>
+        }
+        .into_any()
+    }
+
+    fn render_event(&self, events: Events, state: &mut RenderState) -> (AnyView, usize) {
         match &events[0] {
-            Event::Start(tag) => self.render_start_tag(tag, events),
+            Event::Start(tag) => match self.options.tabs.then(|| code_tabs(&events)).flatten() {
+                Some((tabs, consumed)) => (self.render_code_tabs(events, tabs, state), consumed),
+                None => self.render_start_tag(tag, events, state),
+            },
             Event::End(_) => {
                 // End tags are handled by their corresponding start tags
                 ("".into_any(), 1)
             }
-            Event::Text(text) => (text.to_string().into_any(), 1),
+            Event::Text(text) => (self.render_text(text, state), 1),
             Event::Code(code) => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::INLINE_CODE
-                } else {
-                    "inline-code"
-                };
+                if self.options.kbd {
+                    if let Some(keys) = kbd_code_keys(code) {
+                        let keys = keys.into_iter().enumerate().map(|(i, key)| {
+                            let plus = (i > 0).then_some("+");
+                            view! { {plus}{self.render_kbd(key)} }
+                        });
+                        return (keys.collect_view().into_any(), 1);
+                    }
+                }
+                let class = self.classes.inline_code.clone();
                 (
                     view! {
                         <code class=class>{code.to_string()}</code>
@@ -72,26 +1047,39 @@ impl MarkdownRenderer {
                     1,
                 )
             }
-            Event::SoftBreak => (view! { <span>" "</span> }.into_any(), 1),
+            Event::SoftBreak => match self.options.soft_break {
+                SoftBreakMode::Space => (view! { <span>" "</span> }.into_any(), 1),
+                SoftBreakMode::Newline => ("\n".into_any(), 1),
+                SoftBreakMode::LineBreak => (view! { <br /> }.into_any(), 1),
+            },
             Event::HardBreak => (view! { <br /> }.into_any(), 1),
             Event::Rule => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::HR
-                } else {
-                    "markdown-hr"
-                };
+                let class = self.classes.hr.clone();
                 (view! { <hr class=class /> }.into_any(), 1)
             }
             Event::FootnoteReference(reference) => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::FOOTNOTE_REF
-                } else {
-                    "footnote-ref"
-                };
+                let class = self.classes.footnote_ref.clone();
+                if let Some(footnotes) = state.footnotes.as_mut() {
+                    let number = footnotes.number(reference);
+                    let id = footnotes
+                        .mark_referenced(reference)
+                        .then(|| footnotes.ref_id(reference));
+                    return (
+                        view! {
+                            <sup class=class>
+                                <a href=self.anchor_href(&footnotes.id(reference)) id=id role="doc-noteref">
+                                    {number}
+                                </a>
+                            </sup>
+                        }
+                        .into_any(),
+                        1,
+                    );
+                }
                 (
                     view! {
                         <sup class=class>
-                            <a href=format!("#{}", reference)>{reference.to_string()}</a>
+                            <a href=self.anchor_href(reference)>{reference.to_string()}</a>
                         </sup>
                     }
                     .into_any(),
@@ -99,25 +1087,27 @@ impl MarkdownRenderer {
                 )
             }
             Event::TaskListMarker(checked) => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::CHECKBOX
-                } else {
-                    ""
-                };
-                (
-                    view! {
+                let checkbox = match self.classes.checkbox.clone() {
+                    Some(class) => view! {
                         <input type="checkbox" class=class checked=*checked disabled />
                     }
                     .into_any(),
-                    1,
-                )
+                    None => {
+                        view! { <input type="checkbox" checked=*checked disabled /> }.into_any()
+                    }
+                };
+                (checkbox, 1)
             }
+            Event::InlineMath(expr) if !self.options.enable_math => (
+                self.render_disabled_math(format!("${}$", expr), false, state),
+                1,
+            ),
+            Event::DisplayMath(expr) if !self.options.enable_math => (
+                self.render_disabled_math(format!("$${}$$", expr), true, state),
+                1,
+            ),
             Event::InlineMath(expr) => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::MATH_INLINE
-                } else {
-                    "math math-inline"
-                };
+                let class = self.classes.math_inline.clone();
                 (
                     view! {
                         <span class=class>{expr.to_string()}</span>
@@ -127,11 +1117,7 @@ impl MarkdownRenderer {
                 )
             }
             Event::DisplayMath(expr) => {
-                let class = if self.options.use_explicit_classes {
-                    MarkdownClasses::MATH_DISPLAY
-                } else {
-                    "math math-display"
-                };
+                let class = self.classes.math_display.clone();
                 (
                     view! {
                         <div class=class>{expr.to_string()}</div>
@@ -141,519 +1127,1575 @@ impl MarkdownRenderer {
                 )
             }
             Event::InlineHtml(raw) => {
-                if self.options.allow_raw_html {
-                    (
-                        view! {
-                            <span inner_html=raw.to_string()></span>
-                        }
-                        .into_any(),
-                        1,
-                    )
+                let (raw, consumed) = match self.options.raw_html_policy() {
+                    RawHtmlPolicy::Allow | RawHtmlPolicy::Sanitize => {
+                        inline_html_element(&events).unwrap_or_else(|| (raw.to_string(), 1))
+                    }
+                    RawHtmlPolicy::Deny | RawHtmlPolicy::Escape => (raw.to_string(), 1),
+                };
+                let raw = self.filter_html_comments(&raw, state);
+                if raw.is_empty() {
+                    ("".into_any(), consumed)
                 } else {
-                    (raw.to_string().into_any(), 1)
+                    (self.render_raw_html(raw, false, state), consumed)
                 }
             }
         }
     }
 
-    fn render_start_tag(&self, tag: &Tag, events: &[Event]) -> (AnyView, usize) {
-        let (end_index, consumed) = self.find_matching_end(events);
-        let inner_events = &events[1..end_index];
+    /// Render a parsed document's body: headings get slug ids and footnote definitions
+    /// are left for [`MarkdownRenderer::render_footnote_definitions`]
+    pub(crate) fn render_body(&self, events: &[Event]) -> AnyView {
+        let mut state = self.new_state(events);
+        let table = MatchTable::new(events);
+        self.render_events(Events::new(events, &table), &mut state)
+    }
 
-        let use_explicit = self.options.use_explicit_classes;
+    /// Render only the footnote definitions of a parsed document, as a numbered list
+    pub(crate) fn render_footnote_definitions(&self, events: &[Event]) -> AnyView {
+        let mut state = self.new_state(events);
+        let table = MatchTable::new(events);
+        self.render_footnote_list(Events::new(events, &table), &mut state)
+            .unwrap_or_else(|| ().into_any())
+    }
 
-        match tag {
-            Tag::Paragraph => {
-                let inner_content = self.render_events(inner_events);
-                if use_explicit {
-                    (
-                        view! { <p class=MarkdownClasses::PARAGRAPH>{inner_content}</p> }
-                            .into_any(),
-                        consumed,
-                    )
-                } else {
-                    (view! { <p>{inner_content}</p> }.into_any(), consumed)
+    /// Headings that render, with the same ids the body assigns
+    pub(crate) fn collect_headings(&self, events: &[Event]) -> Vec<MarkdownHeading> {
+        let mut slugger = self
+            .options
+            .heading_ids
+            .then(|| Slugger::for_headings(events));
+        let mut headings = Vec::new();
+        // Names of the `:::` containers enclosing the current event
+        let mut containers: Vec<&str> = Vec::new();
+        let table = MatchTable::new(events);
+        let indexed = Events::new(events, &table);
+
+        for (i, event) in events.iter().enumerate() {
+            if self.options.containers && matches!(event, Event::Start(Tag::Paragraph)) {
+                match events.get(i..i + 3).and_then(container_marker) {
+                    Some(ContainerMarker::Open { name, .. }) => containers.push(name),
+                    Some(ContainerMarker::Close) => {
+                        containers.pop();
+                    }
+                    None => {}
                 }
             }
-            Tag::Heading { level, .. } => {
-                let inner_content = self.render_events(inner_events);
-                if use_explicit {
-                    match level {
-                        HeadingLevel::H1 => (
-                            view! { <h1 class=MarkdownClasses::H1>{inner_content}</h1> }.into_any(),
-                            consumed,
-                        ),
-                        HeadingLevel::H2 => (
-                            view! { <h2 class=MarkdownClasses::H2>{inner_content}</h2> }.into_any(),
-                            consumed,
-                        ),
-                        HeadingLevel::H3 => (
-                            view! { <h3 class=MarkdownClasses::H3>{inner_content}</h3> }.into_any(),
-                            consumed,
-                        ),
-                        HeadingLevel::H4 => (
-                            view! { <h4 class=MarkdownClasses::H4>{inner_content}</h4> }.into_any(),
-                            consumed,
-                        ),
-                        HeadingLevel::H5 => (
-                            view! { <h5 class=MarkdownClasses::H5>{inner_content}</h5> }.into_any(),
-                            consumed,
-                        ),
-                        HeadingLevel::H6 => (
-                            view! { <h6 class=MarkdownClasses::H6>{inner_content}</h6> }.into_any(),
-                            consumed,
-                        ),
-                    }
+            if let Event::Start(Tag::Heading { level, id, .. }) = event {
+                let (end_index, _) = indexed.end_of(i);
+                let inner_events = &events[i + 1..i + end_index];
+                let Some(level) = self.effective_heading_level(*level, inner_events) else {
+                    continue;
+                };
+                let text = self.extract_text_content(inner_events);
+                let id = match id {
+                    Some(id) => Some(id.to_string()),
+                    None => slugger.as_mut().map(|slugger| slugger.slug(&text)),
+                };
+                // Excluded headings still take their slug, keeping later ids in step
+                let excluded = containers.iter().any(|name| {
+                    self.options
+                        .toc_excluded_containers
+                        .iter()
+                        .any(|excluded| excluded == name)
+                });
+                if excluded {
+                    continue;
+                }
+                headings.push(MarkdownHeading {
+                    level: heading_number(level),
+                    id,
+                    text,
+                });
+            }
+        }
+
+        headings
+    }
+
+    /// Href reaching an in-document anchor, honoring `anchor_base` and the resolver
+    pub(crate) fn anchor_href(&self, id: &str) -> String {
+        if let Some(resolver) = &self.options.anchor_href_resolver {
+            return resolver(id);
+        }
+        match &self.options.anchor_base {
+            Some(base) => format!("{}#{}", base, id),
+            None => format!("#{}", id),
+        }
+    }
+
+    /// Styled callout with an icon and title, shared by all alert syntaxes
+    fn render_alert(
+        &self,
+        kind: AlertKind,
+        title: Option<String>,
+        body: Events,
+        state: &mut RenderState,
+    ) -> AnyView {
+        let inner_content = self.render_events(body, state);
+        let title = title.unwrap_or_else(|| kind.title().to_string());
+
+        view! {
+            <div class=self.classes.alert(kind).clone() role="note">
+                <p class=self.classes.alert_title.clone()>
+                    <span aria-hidden="true">{kind.icon()}</span>
+                    {title}
+                </p>
+                {inner_content}
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Level a heading renders at under the `h1_policy`, or `None` to drop it
+    fn effective_heading_level(
+        &self,
+        level: HeadingLevel,
+        inner_events: &[Event],
+    ) -> Option<HeadingLevel> {
+        if level != HeadingLevel::H1 {
+            return Some(level);
+        }
+        match &self.options.h1_policy {
+            H1Policy::Keep => Some(level),
+            H1Policy::Demote => Some(HeadingLevel::H2),
+            H1Policy::StripTitle(title) => {
+                let text = self.extract_text_content(inner_events);
+                if text.trim().eq_ignore_ascii_case(title.trim()) {
+                    None
                 } else {
-                    match level {
-                        HeadingLevel::H1 => {
-                            (view! { <h1>{inner_content}</h1> }.into_any(), consumed)
-                        }
-                        HeadingLevel::H2 => {
-                            (view! { <h2>{inner_content}</h2> }.into_any(), consumed)
-                        }
-                        HeadingLevel::H3 => {
-                            (view! { <h3>{inner_content}</h3> }.into_any(), consumed)
-                        }
-                        HeadingLevel::H4 => {
-                            (view! { <h4>{inner_content}</h4> }.into_any(), consumed)
-                        }
-                        HeadingLevel::H5 => {
-                            (view! { <h5>{inner_content}</h5> }.into_any(), consumed)
+                    Some(HeadingLevel::H2)
+                }
+            }
+        }
+    }
+
+    /// Server-side highlighted HTML for a fenced block, when the `syntect` feature and
+    /// option are on and the fence language is known
+    #[cfg(feature = "syntect")]
+    fn highlight(&self, code: &str, language: &str) -> Option<String> {
+        if !self.options.syntect_highlighting || language.is_empty() {
+            return None;
+        }
+        crate::highlight::highlight_code(code, language)
+    }
+
+    #[cfg(not(feature = "syntect"))]
+    fn highlight(&self, _code: &str, _language: &str) -> Option<String> {
+        None
+    }
+
+    fn render_container(
+        &self,
+        name: &str,
+        title: &str,
+        inner: Events,
+        state: &mut RenderState,
+    ) -> AnyView {
+        // `::: warning Custom title` admonitions render like alerts
+        if self.options.admonitions {
+            if let Some(kind) = AlertKind::from_label(name) {
+                // Untitled ones are titled by their name, so `danger` reads "Danger"
+                let title = match title {
+                    "" => name[..1].to_uppercase() + &name[1..].to_lowercase(),
+                    title => title.to_string(),
+                };
+                return self.render_alert(kind, Some(title), inner, state);
+            }
+        }
+        if self.options.details && name == "details" {
+            let summary = match title {
+                "" => "Details",
+                title => title,
+            };
+            return view! {
+                <details class=self.classes.details.clone()>
+                    <summary class=self.classes.summary.clone()>{summary.to_string()}</summary>
+                    {self.render_events(inner, state)}
+                </details>
+            }
+            .into_any();
+        }
+        if self.options.tabs && name == "tabs" {
+            let (intro, tabs) = container_tabs(&inner);
+            if !tabs.is_empty() {
+                let intro = self.render_events(inner.sub(0..intro), state);
+                let tabs = tabs
+                    .into_iter()
+                    .map(|tab| (tab.title, self.render_events(inner.sub(tab.body), state)))
+                    .collect();
+                return view! { {intro}{self.render_tabs(tabs, state)} }.into_any();
+            }
+        }
+        let class = format!("{} markdown-container-{}", self.classes.container, name);
+        view! {
+            <div class=class data-container=name.to_string()>
+                {self.render_events(inner, state)}
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Consecutive `tab=` code fences as one tab group, each block rendered as usual
+    fn render_code_tabs(&self, events: Events, tabs: Vec<Tab>, state: &mut RenderState) -> AnyView {
+        let tabs = tabs
+            .into_iter()
+            .filter_map(|tab| match &events[tab.body.start] {
+                Event::Start(tag) => {
+                    let (block, _) = self.render_start_tag(tag, events.sub(tab.body), state);
+                    Some((tab.title, block))
+                }
+                _ => None,
+            })
+            .collect();
+        self.render_tabs(tabs, state)
+    }
+
+    /// Tab list over one panel per tab, the first selected. Buttons switch panels on the
+    /// client; the other panels are `hidden` until then.
+    fn render_tabs(&self, tabs: Vec<(String, AnyView)>, state: &mut RenderState) -> AnyView {
+        state.tab_groups += 1;
+        let titles: Vec<&str> = tabs.iter().map(|(title, _)| title.as_str()).collect();
+        let id = format!(
+            "md-tabs-{:08x}-{}",
+            content_hash(&titles.join("\n")) as u32,
+            state.tab_groups
+        );
+        let selected = RwSignal::new(0usize);
+        let (buttons, panels): (Vec<_>, Vec<_>) = tabs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (title, panel))| {
+                let tab_id = format!("{}-tab-{}", id, i);
+                let panel_id = format!("{}-panel-{}", id, i);
+                let button = view! {
+                    <button
+                        type="button"
+                        role="tab"
+                        id=tab_id.clone()
+                        class=self.classes.tab.clone()
+                        aria-controls=panel_id.clone()
+                        aria-selected=move || (selected.get() == i).to_string()
+                        on:click=move |_| selected.set(i)
+                    >
+                        {title}
+                    </button>
+                };
+                let panel = view! {
+                    <div
+                        role="tabpanel"
+                        id=panel_id
+                        class=self.classes.tab_panel.clone()
+                        aria-labelledby=tab_id
+                        hidden=move || selected.get() != i
+                    >
+                        {panel}
+                    </div>
+                };
+                (button, panel)
+            })
+            .unzip();
+        view! {
+            <div class=self.classes.tabs.clone() data-tabs="">
+                <div class=self.classes.tab_list.clone() role="tablist">{buttons}</div>
+                {panels}
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Numbered `<figure>` around `content`; listings put the caption first
+    fn figure_view(
+        &self,
+        figure: MarkdownFigure,
+        content: AnyView,
+        caption_first: bool,
+    ) -> AnyView {
+        let caption = view! {
+            <figcaption class=self.classes.figcaption.clone()>{figure.title()}</figcaption>
+        };
+        if caption_first {
+            view! { <figure id=figure.id class=self.classes.figure.clone()>{caption}{content}</figure> }
+                .into_any()
+        } else {
+            view! { <figure id=figure.id class=self.classes.figure.clone()>{content}{caption}</figure> }
+                .into_any()
+        }
+    }
+
+    /// A single code snippet rendered like a fenced block of `language`, with the same
+    /// theming, highlighting, and code actions; component overrides don't apply
+    pub fn render_code(&self, code: &str, language: &str) -> AnyView {
+        let mut state = RenderState::default();
+        let view = self.code_block_view(code.to_string(), language, &[], &mut state);
+        self.clamp_code_block(view, code, &mut state)
+    }
+
+    fn render_code_block(
+        &self,
+        kind: &CodeBlockKind,
+        inner_events: &[Event],
+        state: &mut RenderState,
+    ) -> AnyView {
+        let code_content = self.extract_text_content(inner_events);
+        // The language is the first word of the info string; attributes may follow
+        let (language, line_ranges) = match kind {
+            CodeBlockKind::Fenced(info) => (
+                info.split(|c: char| c.is_whitespace() || c == '{')
+                    .next()
+                    .unwrap_or(""),
+                highlighted_lines(info),
+            ),
+            CodeBlockKind::Indented => ("", Vec::new()),
+        };
+        if self.options.mermaid_diagrams && language == "mermaid" {
+            return view! { <pre class=self.classes.mermaid.clone()>{code_content}</pre> }
+                .into_any();
+        }
+        if let Some(code_block) = &self.options.components.code_block {
+            let props = CodeBlockProps {
+                language: (!language.is_empty()).then(|| language.to_string()),
+                code: code_content,
+                title: match kind {
+                    CodeBlockKind::Fenced(info) => fence_title(info),
+                    CodeBlockKind::Indented => None,
+                },
+            };
+            return code_block(props);
+        }
+        self.code_block_view(code_content, language, &line_ranges, state)
+    }
+
+    /// The built-in `<pre>` for `code_content`, with any copy button and expand modal
+    fn code_block_view(
+        &self,
+        code_content: String,
+        language: &str,
+        line_ranges: &[RangeInclusive<usize>],
+        state: &mut RenderState,
+    ) -> AnyView {
+        let use_explicit = self.options.use_explicit_classes;
+        // Determine language class if syntax_highlighting_language_classes is enabled
+        let language_class = if self.options.syntax_highlighting_language_classes {
+            if language.is_empty() {
+                Some("language-text".to_string())
+            } else {
+                Some(format!("language-{}", language))
+            }
+        } else {
+            None
+        };
+
+        // The <pre> class (base + theme) is precomputed; only the language varies
+        let combined_class: Class = match &language_class {
+            Some(lang) => format!("{} {}", self.classes.code_block, lang).into(),
+            None => self.classes.code_block.clone(),
+        };
+
+        // Build the class for <code>
+        let code_class: Class = if use_explicit {
+            match &language_class {
+                Some(lang) => format!("{} {}", self.classes.code_block_code, lang).into(),
+                None => self.classes.code_block_code.clone(),
+            }
+        } else {
+            language_class.unwrap_or_default().into()
+        };
+
+        let mut highlighted = self.highlight(&code_content, language);
+        let diff = self.options.diff_highlighting && language == "diff";
+        if diff || !line_ranges.is_empty() {
+            highlighted = Some(highlight_lines(
+                &code_content,
+                highlighted.as_deref(),
+                line_ranges,
+                diff,
+                &self.classes,
+            ));
+        }
+
+        let tab_size = format!("tab-size: {}", self.options.code_tab_size);
+        let copy_button = self
+            .options
+            .code_copy_button
+            .then(|| self.copy_button(code_content.clone()));
+        if !self.options.code_expand_action {
+            let pre = view! {
+                <pre class=combined_class style=tab_size>
+                    {code_element(code_class, code_content, highlighted)}
+                </pre>
+            };
+            return match copy_button {
+                Some(button) => view! {
+                    <div class=self.classes.code_block_wrapper.clone()>{pre}{button}</div>
+                }
+                .into_any(),
+                None => pre.into_any(),
+            };
+        }
+
+        // Native popover: opens and closes without any client-side code
+        state.code_blocks += 1;
+        let modal_id = format!(
+            "md-code-{:08x}-{}",
+            content_hash(&code_content) as u32,
+            state.code_blocks
+        );
+        let modal_pre_class = format!("{} {}", combined_class, self.classes.code_modal_pre);
+        let open_target = modal_id.clone();
+        let close_target = modal_id.clone();
+        view! {
+            <div class=self.classes.code_block_wrapper.clone()>
+                <pre class=combined_class style=tab_size.clone()>
+                    {code_element(code_class.clone(), code_content.clone(), highlighted.clone())}
+                </pre>
+                <button
+                    type="button"
+                    class=self.classes.code_expand_button.clone()
+                    popovertarget=open_target
+                    aria-label="Expand code"
+                >
+                    "⤢"
+                </button>
+                {copy_button}
+                <div popover="auto" id=modal_id.clone() class=self.classes.code_modal.clone() role="dialog" aria-label="Expanded code">
+                    <button
+                        type="button"
+                        class=self.classes.code_modal_close.clone()
+                        popovertarget=close_target
+                        popovertargetaction="hide"
+                        aria-label="Close"
+                    >
+                        "✕"
+                    </button>
+                    <pre class=modal_pre_class style=tab_size>
+                        {code_element(code_class, code_content, highlighted)}
+                    </pre>
+                </div>
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Clamp a code block longer than `code_max_lines`, fading it out above an
+    /// Expand/Collapse toggle. A hidden checkbox holds the state, so it works without
+    /// client-side code.
+    fn clamp_code_block(&self, code: AnyView, text: &str, state: &mut RenderState) -> AnyView {
+        let Some(max_lines) = self.options.code_max_lines else {
+            return code;
+        };
+        if text.lines().count() <= max_lines {
+            return code;
+        }
+
+        state.code_blocks += 1;
+        let toggle_id = format!(
+            "md-code-clamp-{:08x}-{}",
+            content_hash(text) as u32,
+            state.code_blocks
+        );
+        // `lh` is the wrapper's line height, close to the code's; 2rem covers the padding
+        let max_height = format!("max-height: calc({}lh + 2rem)", max_lines);
+        view! {
+            <div class=self.classes.code_clamp.clone()>
+                <input type="checkbox" id=toggle_id.clone() class=self.classes.code_clamp_toggle.clone() />
+                <div class=self.classes.code_clamped.clone() style=max_height>{code}</div>
+                <div class=self.classes.code_clamp_fade.clone() aria-hidden="true"></div>
+                <label for=toggle_id class=self.classes.code_clamp_button.clone()>
+                    <span class=self.classes.code_clamp_expand.clone()>"Expand"</span>
+                    <span class=self.classes.code_clamp_collapse.clone()>"Collapse"</span>
+                </label>
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Button writing `code` to the clipboard, labelled "Copied!" for two seconds after
+    fn copy_button(&self, code: String) -> AnyView {
+        let copied = RwSignal::new(false);
+        view! {
+            <button
+                type="button"
+                class=self.classes.code_copy_button.clone()
+                data-copied=move || copied.get().then_some("")
+                aria-label="Copy code"
+                on:click=move |_| {
+                    let _ = window().navigator().clipboard().write_text(&code);
+                    copied.set(true);
+                    set_timeout(move || copied.set(false), Duration::from_secs(2));
+                }
+            >
+                {move || if copied.get() { "Copied!" } else { "Copy" }}
+            </button>
+        }
+        .into_any()
+    }
+
+    /// Href of a `[[wikilink]]` target: the resolver's answer, or the percent-encoded
+    /// target as a relative href when there is no resolver
+    fn wikilink_href(&self, target: &str) -> Option<String> {
+        match &self.options.wikilink_resolver {
+            Some(resolve) => resolve(target),
+            None => relative_wikilink_href(target),
+        }
+    }
+
+    /// Placeholder for an `![[embed]]` of another note, linking to it when it resolves.
+    /// Apps fill it in through `data-embed`.
+    fn render_embed(&self, target: &str) -> AnyView {
+        let link = self
+            .wikilink_href(target)
+            .map(|href| self.plain_link(href, target.to_string()));
+        let label = link.is_none().then(|| target.to_string());
+
+        view! {
+            <span class=self.classes.embed.clone() data-embed=target.to_string()>
+                <span aria-hidden="true">"📄"</span>
+                {link}
+                {label}
+            </span>
+        }
+        .into_any()
+    }
+
+    /// Whether text rewrite passes may run here: with `protect_links_from_rewrites` they
+    /// skip link text, since rewriting inside an existing anchor corrupts it
+    fn text_rewrites_allowed(&self, state: &RenderState) -> bool {
+        !(self.options.protect_links_from_rewrites && state.link_depth > 0)
+    }
+
+    fn render_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        if !self.options.routes.is_empty()
+            && self.text_rewrites_allowed(state)
+            && text.contains("[:route:")
+        {
+            let segments = route_segments(text);
+            if segments
+                .iter()
+                .any(|segment| matches!(segment, RouteSegment::Route(_)))
+            {
+                let mut views = Vec::with_capacity(segments.len());
+                for segment in segments {
+                    views.push(match segment {
+                        RouteSegment::Text(text) => self.render_cited_text(text, state),
+                        RouteSegment::Route(directive) => self.render_route_link(directive, state),
+                    });
+                }
+                return views.into_iter().collect_view().into_any();
+            }
+        }
+        self.render_cited_text(text, state)
+    }
+
+    /// In-app link for a route directive; unknown routes stay text
+    fn render_route_link(&self, directive: RouteDirective, state: &mut RenderState) -> AnyView {
+        let Some(href) = self.options.routes.href(directive.name, &directive.params) else {
+            state.warnings.push(RenderWarning::UnknownRoute {
+                name: directive.name.to_string(),
+            });
+            let source = directive.source.to_string();
+            return self
+                .render_unsupported(UnsupportedKind::Directive, source.clone(), false)
+                .unwrap_or_else(|| source.into_any());
+        };
+        let label = directive.label.map_or_else(|| href.clone(), str::to_string);
+
+        #[cfg(feature = "router")]
+        {
+            let class = self.classes.link.clone();
+            view! { <A href=href attr:class=class>{label}</A> }.into_any()
+        }
+        #[cfg(not(feature = "router"))]
+        {
+            self.plain_link(href, label)
+        }
+    }
+
+    /// `<a>` with the link class, or without a class attribute when it is empty
+    fn plain_link(&self, href: String, label: impl IntoView + 'static) -> AnyView {
+        match self.classes.link.clone() {
+            Some(class) => view! { <a class=class href=href>{label}</a> }.into_any(),
+            None => view! { <a href=href>{label}</a> }.into_any(),
+        }
+    }
+
+    fn render_cited_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        if self.options.citation_resolver.is_some()
+            && self.text_rewrites_allowed(state)
+            && text.contains('@')
+        {
+            let segments = citation_segments(text);
+            if segments
+                .iter()
+                .any(|segment| matches!(segment, TextSegment::Citation(_)))
+            {
+                let mut views = Vec::with_capacity(segments.len());
+                for segment in segments {
+                    views.push(match segment {
+                        TextSegment::Text(text) => self.render_mention_text(text, state),
+                        TextSegment::Citation(items) => self.render_citation(items, state),
+                    });
+                }
+                return views.into_iter().collect_view().into_any();
+            }
+        }
+        self.render_mention_text(text, state)
+    }
+
+    /// Text with resolved `@mentions` and `#hashtags` linked
+    fn render_mention_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        let (mentions, hashtags) = (
+            self.options.mention_resolver.as_ref(),
+            self.options.hashtag_resolver.as_ref(),
+        );
+        let has_sigil = (mentions.is_some() && text.contains('@'))
+            || (hashtags.is_some() && text.contains('#'));
+        if !has_sigil || !self.text_rewrites_allowed(state) {
+            return self.render_reference_text(text, state);
+        }
+        let segments = mention_segments(text, mentions, hashtags);
+        if segments
+            .iter()
+            .all(|segment| matches!(segment, MentionSegment::Text(_)))
+        {
+            return self.render_reference_text(text, state);
+        }
+
+        segments
+            .into_iter()
+            .map(|segment| match segment {
+                MentionSegment::Text(text) => self.render_reference_text(text, state),
+                MentionSegment::Mention {
+                    source,
+                    hashtag,
+                    target,
+                } => {
+                    let label = target.label.unwrap_or_else(|| source.to_string());
+                    let kind = if hashtag { "hashtag" } else { "mention" };
+                    match self.classes.link.clone() {
+                        Some(class) => view! {
+                            <a class=class href=target.href data-mention=kind>{label}</a>
                         }
-                        HeadingLevel::H6 => {
-                            (view! { <h6>{inner_content}</h6> }.into_any(), consumed)
+                        .into_any(),
+                        None => {
+                            view! { <a href=target.href data-mention=kind>{label}</a> }.into_any()
                         }
                     }
                 }
+            })
+            .collect_view()
+            .into_any()
+    }
+
+    /// Text with issue, pull request and commit references linked
+    fn render_reference_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        let Some(linker) = self.options.reference_linker.as_ref() else {
+            return self.render_kbd_text(text, state);
+        };
+        if !self.text_rewrites_allowed(state) {
+            return self.render_kbd_text(text, state);
+        }
+        let segments = reference_segments(text, linker);
+        if segments
+            .iter()
+            .all(|segment| matches!(segment, ReferenceSegment::Text(_)))
+        {
+            return self.render_kbd_text(text, state);
+        }
+
+        segments
+            .into_iter()
+            .map(|segment| match segment {
+                ReferenceSegment::Text(text) => self.render_kbd_text(text, state),
+                ReferenceSegment::Link { label, href, kind } => match self.classes.link.clone() {
+                    Some(class) => view! {
+                        <a class=class href=href data-reference=kind>{label}</a>
+                    }
+                    .into_any(),
+                    None => view! { <a href=href data-reference=kind>{label}</a> }.into_any(),
+                },
+            })
+            .collect_view()
+            .into_any()
+    }
+
+    /// Text with `[[Key]]`s rendered as key caps under `kbd`, unless wikilinks own the syntax
+    fn render_kbd_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        if !self.options.kbd || self.options.wikilinks || !text.contains("[[") {
+            return self.render_emoji_text(text, state);
+        }
+        let segments = kbd_segments(text);
+        if segments
+            .iter()
+            .all(|segment| matches!(segment, KbdSegment::Text(_)))
+        {
+            return self.render_emoji_text(text, state);
+        }
+
+        segments
+            .into_iter()
+            .map(|segment| match segment {
+                KbdSegment::Text(text) => self.render_emoji_text(text, state),
+                KbdSegment::Key(key) => self.render_kbd(key),
+            })
+            .collect_view()
+            .into_any()
+    }
+
+    fn render_kbd(&self, key: &str) -> AnyView {
+        view! { <kbd class=self.classes.kbd.clone()>{key.to_string()}</kbd> }.into_any()
+    }
+
+    /// Text with `:shortcode:`s replaced by emoji under `emoji_shortcodes`
+    fn render_emoji_text(&self, text: &str, state: &mut RenderState) -> AnyView {
+        if !self.options.emoji_shortcodes || !text.contains(':') {
+            return self.render_plain_text(text, state);
+        }
+        let mut unknown = Vec::new();
+        let segments = emoji_segments(text, self.options.emoji_resolver.as_ref(), &mut unknown);
+        state.warnings.extend(
+            unknown
+                .into_iter()
+                .map(|code| RenderWarning::UnknownShortcode {
+                    code: code.to_string(),
+                }),
+        );
+        if segments
+            .iter()
+            .all(|segment| matches!(segment, EmojiSegment::Text(_)))
+        {
+            return self.render_plain_text(text, state);
+        }
+
+        segments
+            .into_iter()
+            .map(|segment| match segment {
+                EmojiSegment::Text(text) => self.render_plain_text(text, state),
+                EmojiSegment::Emoji(emoji) => emoji.into_any(),
+                EmojiSegment::Image { shortcode, src } => view! {
+                    <img
+                        class=self.classes.emoji.clone()
+                        src=src
+                        alt=format!(":{}:", shortcode)
+                        title=format!(":{}:", shortcode)
+                    />
+                }
+                .into_any(),
+            })
+            .collect_view()
+            .into_any()
+    }
+
+    /// `<cite>` linking each resolved key to its bibliography entry
+    fn render_citation(&self, items: Vec<CiteItem>, state: &mut RenderState) -> AnyView {
+        let Some(resolver) = &self.options.citation_resolver else {
+            return ().into_any();
+        };
+        let mut views = Vec::with_capacity(items.len() * 2);
+        for (i, item) in items.into_iter().enumerate() {
+            if i > 0 {
+                views.push("; ".into_any());
             }
-            Tag::BlockQuote(_) => {
-                let inner_content = self.render_events(inner_events);
-                let class = if use_explicit {
-                    MarkdownClasses::BLOCKQUOTE
-                } else {
-                    "markdown-blockquote"
+            let Some(citation) = resolver(item.key) else {
+                state.warnings.push(RenderWarning::UnknownCitation {
+                    key: item.key.to_string(),
+                });
+                let text = match item.locator {
+                    Some(locator) => format!("@{}, {}", item.key, locator),
+                    None => format!("@{}", item.key),
                 };
+                views.push(text.into_any());
+                continue;
+            };
+            let label = match item.locator {
+                Some(locator) => format!("{}, {}", citation.text, locator),
+                None => citation.text.clone(),
+            };
+            if !state.citations.iter().any(|(key, _)| key == item.key) {
+                state.citations.push((item.key.to_string(), citation));
+            }
+            let href = self.anchor_href(&reference_id(item.key));
+            views.push(
+                view! {
+                    <a class=self.classes.citation.clone() href=href role="doc-biblioref">{label}</a>
+                }
+                .into_any(),
+            );
+        }
+        view! { <cite>{views.into_iter().collect_view()}</cite> }.into_any()
+    }
+
+    fn render_plain_text(&self, text: &str, state: &RenderState) -> AnyView {
+        if !self.text_rewrites_allowed(state) {
+            return text.to_string().into_any();
+        }
+        let Some(min_len) = self.options.long_token_breaks else {
+            return text.to_string().into_any();
+        };
+        if !text
+            .split_whitespace()
+            .any(|token| token.chars().count() >= min_len)
+        {
+            return text.to_string().into_any();
+        }
+
+        let mut views = Vec::new();
+        for (i, segment) in long_token_segments(text, min_len).into_iter().enumerate() {
+            if i > 0 {
+                views.push(view! { <wbr /> }.into_any());
+            }
+            views.push(segment.into_any());
+        }
+        views.into_iter().collect_view().into_any()
+    }
+
+    // Each element is rendered by its own method and this dispatcher stays small: its
+    // stack frame is live across every nesting level, so deep documents would otherwise
+    // exhaust the stack in debug builds.
+    fn render_start_tag(
+        &self,
+        tag: &Tag,
+        events: Events,
+        state: &mut RenderState,
+    ) -> (AnyView, usize) {
+        let (end_index, consumed) = events.end_of(0);
+        let inner_events = events.sub(1..end_index);
+
+        match tag {
+            Tag::Paragraph => self.render_paragraph(events, inner_events, consumed, state),
+            Tag::Heading {
+                level, id, classes, ..
+            } => (
+                self.render_heading(*level, id.as_deref(), classes, inner_events, state),
+                consumed,
+            ),
+            Tag::BlockQuote(Some(kind)) if self.options.gfm_alerts => (
+                self.render_alert(alert_kind(*kind), None, inner_events, state),
+                consumed,
+            ),
+            Tag::BlockQuote(_) => (self.render_blockquote(inner_events, state), consumed),
+            Tag::CodeBlock(kind) => (self.render_listing(kind, &inner_events, state), consumed),
+            Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                ..
+            } => (
+                self.render_link(*link_type, dest_url, title, inner_events, state),
+                consumed,
+            ),
+            Tag::Image {
+                link_type: LinkType::WikiLink { .. },
+                dest_url,
+                ..
+            } if !is_image_path(dest_url) => (self.render_embed(dest_url), consumed),
+            Tag::Image {
+                dest_url, title, ..
+            } => (
+                self.render_image(dest_url, title, &inner_events, state),
+                consumed,
+            ),
+            Tag::FootnoteDefinition(_) if state.skip_footnote_definitions => {
+                ("".into_any(), consumed)
+            }
+            Tag::FootnoteDefinition(label) => (
+                self.render_footnote_definition(label, inner_events, state),
+                consumed,
+            ),
+            Tag::HtmlBlock => {
+                let raw_html =
+                    self.filter_html_comments(&self.extract_html_content(&inner_events), state);
+                if raw_html.trim().is_empty() {
+                    ("".into_any(), consumed)
+                } else {
+                    (self.render_raw_html(raw_html, true, state), consumed)
+                }
+            }
+            Tag::MetadataBlock(kind) => {
+                // Frontmatter is data, not content
+                if let Some(handler) = &self.options.on_frontmatter {
+                    handler(&Frontmatter {
+                        kind: *kind,
+                        raw: self.extract_text_content(&inner_events),
+                    });
+                }
+                ("".into_any(), consumed)
+            }
+            _ => {
+                // Cells render as `<th>` while their table head renders
+                let in_table_head = state.in_table_head;
+                let head = matches!(tag, Tag::TableHead);
+                state.in_table_head |= head;
+                let inner_content = self.render_events(inner_events, state);
+                state.in_table_head &= !head;
                 (
-                    view! {
-                        <blockquote class=class>
-                            {inner_content}
-                        </blockquote>
-                    }
-                    .into_any(),
+                    self.wrap_element(tag, inner_content, in_table_head),
                     consumed,
                 )
             }
-            Tag::CodeBlock(kind) => {
-                let code_content = self.extract_text_content(inner_events);
-
-                // Determine language class if syntax_highlighting_language_classes is enabled
-                let language_class = if self.options.syntax_highlighting_language_classes {
-                    match kind {
-                        CodeBlockKind::Indented => Some("language-text".to_string()),
-                        CodeBlockKind::Fenced(lang) => {
-                            if lang.is_empty() {
-                                Some("language-text".to_string())
-                            } else {
-                                Some(format!("language-{}", lang))
-                            }
-                        }
+        }
+    }
+
+    fn render_paragraph(
+        &self,
+        events: Events,
+        inner_events: Events,
+        consumed: usize,
+        state: &mut RenderState,
+    ) -> (AnyView, usize) {
+        if let (true, Some(ContainerMarker::Open { name, title })) = (
+            self.options.containers,
+            container_marker(&events[..consumed]),
+        ) {
+            let body = events.sub(consumed..events.len());
+            // An unclosed container runs to the end of its parent
+            let (inner, consumed) = match container_close(&body) {
+                Some(close) => (body.sub(0..close), consumed + close + 3),
+                None => (body, events.len()),
+            };
+            return (self.render_container(name, title, inner, state), consumed);
+        }
+        if self.lof_enabled() && is_lof_placeholder(&inner_events) {
+            return (self.render_figure_list(&state.figures), consumed);
+        }
+        if self.options.figures {
+            if let Some(caption) = figure_caption(&inner_events) {
+                let figure = state.figure_counter.next(FigureKind::Figure, caption);
+                let image = self.render_events(inner_events, state);
+                return (self.figure_view(figure, image, false), consumed);
+            }
+        }
+        let inner_content = self.render_events(inner_events, state);
+        match self.classes.paragraph.clone() {
+            Some(class) => (
+                view! { <p class=class>{inner_content}</p> }.into_any(),
+                consumed,
+            ),
+            None => (view! { <p>{inner_content}</p> }.into_any(), consumed),
+        }
+    }
+
+    fn render_heading(
+        &self,
+        level: HeadingLevel,
+        explicit_id: Option<&str>,
+        classes: &[CowStr],
+        inner_events: Events,
+        state: &mut RenderState,
+    ) -> AnyView {
+        let Some(level) = self.effective_heading_level(level, &inner_events) else {
+            return "".into_any();
+        };
+        // An explicit `{#id}` wins over the generated slug
+        let id = match explicit_id {
+            Some(id) => Some(id.to_string()),
+            None => state
+                .slugger
+                .as_mut()
+                .map(|slugger| slugger.slug(&self.extract_text_content(&inner_events))),
+        };
+        let mut inner_content = self.render_events(inner_events, state);
+        if let Some(heading) = &self.options.components.heading {
+            let props = HeadingProps {
+                level: heading_number(level),
+                id,
+                children: inner_content,
+            };
+            return heading(props);
+        }
+        let mut class = self.classes.heading(level).cloned();
+        // Custom `{.class}` attributes follow the built-in classes
+        if !classes.is_empty() {
+            let custom = classes
+                .iter()
+                .map(|c| c.as_ref())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            class = Some(match class {
+                Some(class) => format!("{} {}", class, custom).into(),
+                None => custom.into(),
+            });
+        }
+        if id.is_some() && self.options.highlight_anchor_target {
+            let target = &self.classes.anchor_target;
+            class = Some(match class {
+                Some(class) => format!("{} {}", class, target).into(),
+                None => target.clone(),
+            });
+        }
+        // The permalink only shows while hovering the heading (`group`)
+        if let (true, Some(id)) = (self.options.heading_anchors, &id) {
+            class = Some(match class {
+                Some(class) => format!("{} group", class).into(),
+                None => "group".into(),
+            });
+            let anchor_class = self.classes.heading_anchor.clone();
+            let href = self.anchor_href(id);
+            // The glyph is decorative; the label names the section. A heading that
+            // already is a link gets an extra anchor only for pointer users.
+            let duplicative = inner_events
+                .iter()
+                .any(|event| matches!(event, Event::Start(Tag::Link { .. })));
+            let anchor = if duplicative {
+                view! {
+                    <a class=anchor_class href=href aria-hidden="true" tabindex="-1">"#"</a>
+                }
+                .into_any()
+            } else {
+                let label = format!(
+                    "Link to section {}",
+                    self.extract_text_content(&inner_events)
+                );
+                view! {
+                    <a class=anchor_class href=href aria-label=label>
+                        <span aria-hidden="true">"#"</span>
+                    </a>
+                }
+                .into_any()
+            };
+            inner_content = view! { {inner_content}{anchor} }.into_any();
+        }
+        heading_view(level, class, id, inner_content)
+    }
+
+    fn render_blockquote(&self, inner_events: Events, state: &mut RenderState) -> AnyView {
+        // Alert bodies are rebuilt event lists, indexed on their own
+        if self.options.callouts {
+            if let Some((kind, title, body)) = callout(&inner_events) {
+                let table = MatchTable::new(&body);
+                return self.render_alert(kind, title, Events::new(&body, &table), state);
+            }
+        }
+        if self.options.legacy_alerts {
+            if let Some((kind, body)) = legacy_alert(&inner_events) {
+                let table = MatchTable::new(&body);
+                return self.render_alert(kind, None, Events::new(&body, &table), state);
+            }
+        }
+
+        let inner_content = self.render_events(inner_events, state);
+        if let Some(blockquote) = &self.options.components.blockquote {
+            let props = BlockquoteProps {
+                children: inner_content,
+            };
+            return blockquote(props);
+        }
+        let class = self.classes.blockquote.clone();
+        view! {
+            <blockquote class=class>
+                {inner_content}
+            </blockquote>
+        }
+        .into_any()
+    }
+
+    /// A code block with its clamp, title bar and listing caption
+    fn render_listing(
+        &self,
+        kind: &CodeBlockKind,
+        inner_events: &[Event],
+        state: &mut RenderState,
+    ) -> AnyView {
+        let mut code = self.render_code_block(kind, inner_events, state);
+        if self.options.components.code_block.is_none() {
+            let text = self.extract_text_content(inner_events);
+            code = self.clamp_code_block(code, &text, state);
+            let title = match kind {
+                CodeBlockKind::Fenced(info) => fence_title(info),
+                CodeBlockKind::Indented => None,
+            };
+            if let Some(title) = title {
+                code = view! {
+                    <div class=self.classes.code_titled.clone()>
+                        <div class=self.classes.code_title.clone()>{title}</div>
+                        {code}
+                    </div>
+                }
+                .into_any();
+            }
+        }
+        let caption = match kind {
+            CodeBlockKind::Fenced(info) if self.options.captioned_listings => listing_caption(info),
+            _ => None,
+        };
+        match caption {
+            Some(caption) => {
+                let listing = state.figure_counter.next(FigureKind::Listing, caption);
+                self.figure_view(listing, code, true)
+            }
+            None => code,
+        }
+    }
+
+    fn render_link(
+        &self,
+        link_type: LinkType,
+        dest_url: &str,
+        title: &str,
+        inner_events: Events,
+        state: &mut RenderState,
+    ) -> AnyView {
+        state.link_depth += 1;
+        let inner_content = self.render_events(inner_events, state);
+        state.link_depth -= 1;
+        // Wikilink targets are page names; the resolver builds their href
+        let wikilink = matches!(link_type, LinkType::WikiLink { .. });
+        if !wikilink && !self.url_allowed(dest_url, state) {
+            return inner_content;
+        }
+        let dest_url = match link_type {
+            LinkType::WikiLink { .. } => match self.wikilink_href(dest_url) {
+                Some(href) => Cow::Owned(href),
+                None => {
+                    return view! {
+                        <span
+                            class=self.classes.wikilink_unresolved.clone()
+                            data-wikilink=dest_url.to_string()
+                        >
+                            {inner_content}
+                        </span>
                     }
-                } else {
-                    None
-                };
+                    .into_any();
+                }
+            },
+            _ => Cow::Borrowed(dest_url),
+        };
+        let dest_url = match &self.options.link_rewriter {
+            Some(rewrite) => Cow::Owned(rewrite(dest_url.as_ref())),
+            None => dest_url,
+        };
+        let href = match dest_url.strip_prefix('#') {
+            Some(id) => self.anchor_href(id),
+            None => dest_url.into_owned(),
+        };
+        if let Some(link) = &self.options.components.link {
+            let props = LinkProps {
+                href,
+                title: (!title.is_empty()).then(|| title.to_string()),
+                children: inner_content,
+            };
+            return link(props);
+        }
+        let new_tab = self.options.link_opens_in_new_tab(&href);
+        let title = (!title.is_empty()).then(|| title.to_string());
+        let target = new_tab.then_some("_blank");
+        let rel = match &self.options.link_rel {
+            Some(rel) => (!rel.is_empty()).then(|| rel.clone()),
+            None => new_tab.then(|| "noopener noreferrer".to_string()),
+        };
 
-                // Get Tailwind theme classes if a theme is set
-                let theme_classes = self
-                    .options
-                    .code_theme
-                    .as_ref()
-                    .map(|theme| get_code_theme_classes(theme));
+        match self.classes.link.clone() {
+            Some(class) => view! {
+                <a class=class href=href title=title target=target rel=rel>
+                    {inner_content}
+                </a>
+            }
+            .into_any(),
+            None => view! {
+                <a href=href title=title target=target rel=rel>
+                    {inner_content}
+                </a>
+            }
+            .into_any(),
+        }
+    }
 
-                // Base class for <pre>
-                let base_pre_class = if use_explicit {
-                    MarkdownClasses::CODE_BLOCK
-                } else {
-                    "markdown-code-block"
-                };
+    fn render_image(
+        &self,
+        dest_url: &str,
+        title: &str,
+        inner_events: &[Event],
+        state: &mut RenderState,
+    ) -> AnyView {
+        let alt = self.extract_text_content(inner_events);
+        if !self.url_allowed(dest_url, state) {
+            return alt.into_any();
+        }
+        let (width, height) = self
+            .options
+            .image_dimensions
+            .as_ref()
+            .and_then(|dimensions| dimensions(dest_url))
+            .unzip();
+        let ImageSource { src, srcset, sizes } = match &self.options.image_src_transform {
+            Some(transform) => transform(dest_url),
+            None => ImageSource::new(dest_url.to_string()),
+        };
+        let title = (!title.is_empty()).then(|| title.to_string());
+        if let Some(image) = &self.options.components.image {
+            let props = ImageProps {
+                src,
+                srcset,
+                sizes,
+                width,
+                height,
+                lazy: self.options.lazy_images,
+                alt,
+                title,
+            };
+            return image(props);
+        }
+        let img_class = self.classes.image.clone();
+        let lazy = self.options.lazy_images;
 
-                // Build the combined class for <pre>
-                let combined_class = match (&language_class, theme_classes) {
-                    (Some(lang), Some(theme)) => {
-                        format!("{} {} {}", base_pre_class, lang, theme)
-                    }
-                    (Some(lang), None) => format!("{} {}", base_pre_class, lang),
-                    (None, Some(theme)) => format!("{} {}", base_pre_class, theme),
-                    (None, None) => base_pre_class.to_string(),
-                };
+        view! {
+            <img
+                src=src
+                srcset=srcset
+                sizes=sizes
+                alt=alt
+                title=title
+                width=width
+                height=height
+                loading=lazy.then_some("lazy")
+                decoding=lazy.then_some("async")
+                class=img_class
+            />
+        }
+        .into_any()
+    }
 
-                // Build the class for <code>
-                let code_class = if use_explicit {
-                    match &language_class {
-                        Some(lang) => format!("{} {}", MarkdownClasses::CODE_BLOCK_CODE, lang),
-                        None => MarkdownClasses::CODE_BLOCK_CODE.to_string(),
-                    }
-                } else {
-                    language_class.unwrap_or_default()
-                };
+    /// Whether the URL policy lets `url` render, recording a warning when it doesn't
+    fn url_allowed(&self, url: &str, state: &mut RenderState) -> bool {
+        let allowed = self.options.url_policy().allows(url);
+        if !allowed {
+            state.warnings.push(RenderWarning::BlockedUrl {
+                url: url.to_string(),
+            });
+        }
+        allowed
+    }
 
-                (
-                    view! {
-                        <pre class=combined_class>
-                            <code class=code_class>{code_content}</code>
-                        </pre>
-                    }
-                    .into_any(),
-                    consumed,
-                )
+    fn render_footnote_definition(
+        &self,
+        label: &str,
+        inner_events: Events,
+        state: &mut RenderState,
+    ) -> AnyView {
+        let inner_content = self.render_events(inner_events, state);
+        let class = self.classes.footnote_def.clone();
+        if let Some(footnotes) = &state.footnotes {
+            // Definitions nothing refers to have no reference to go back to
+            let backlink = footnotes.is_cited(label).then(|| {
+                let label_text = format!("Back to reference {}", footnotes.number(label));
+                view! {
+                    <a
+                        class=self.classes.footnote_backref.clone()
+                        href=self.anchor_href(&footnotes.ref_id(label))
+                        aria-label=label_text
+                        role="doc-backlink"
+                    >
+                        "↩"
+                    </a>
+                }
+            });
+            return view! {
+                <li class=class id=footnotes.id(label)>
+                    {inner_content}
+                    {backlink}
+                </li>
             }
+            .into_any();
+        }
+        view! {
+            <div class=class id=label.to_string()>
+                {inner_content}
+            </div>
+        }
+        .into_any()
+    }
+
+    /// Element wrapping the already rendered children of `tag`, for tags that are plain
+    /// containers. `in_table_head` tells header cells from body cells.
+    fn wrap_element(&self, tag: &Tag, inner_content: AnyView, in_table_head: bool) -> AnyView {
+        let use_explicit = self.options.use_explicit_classes;
+
+        match tag {
             Tag::List(start_number) => {
-                let inner_content = self.render_events(inner_events);
                 if let Some(start) = start_number {
                     if use_explicit {
-                        (
-                            view! {
-                                <ol class=MarkdownClasses::OL start=start.to_string()>{inner_content}</ol>
-                            }
-                            .into_any(),
-                            consumed,
-                        )
-                    } else {
-                        (
-                            view! {
-                                <ol start=start.to_string()>{inner_content}</ol>
-                            }
-                            .into_any(),
-                            consumed,
-                        )
-                    }
-                } else if use_explicit {
-                    (
                         view! {
-                            <ul class=MarkdownClasses::UL>{inner_content}</ul>
+                            <ol class=self.classes.ol.clone() start=start.to_string()>{inner_content}</ol>
                         }
-                        .into_any(),
-                        consumed,
-                    )
-                } else {
-                    (
+                        .into_any()
+                    } else {
                         view! {
-                            <ul>{inner_content}</ul>
+                            <ol start=start.to_string()>{inner_content}</ol>
                         }
-                        .into_any(),
-                        consumed,
-                    )
+                        .into_any()
+                    }
+                } else if use_explicit {
+                    view! {
+                        <ul class=self.classes.ul.clone()>{inner_content}</ul>
+                    }
+                    .into_any()
+                } else {
+                    view! {
+                        <ul>{inner_content}</ul>
+                    }
+                    .into_any()
                 }
             }
             Tag::Item => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <li class=MarkdownClasses::LI>{inner_content}</li> }.into_any(),
-                        consumed,
-                    )
+                    view! { <li class=self.classes.li.clone()>{inner_content}</li> }.into_any()
                 } else {
-                    (view! { <li>{inner_content}</li> }.into_any(), consumed)
+                    view! { <li>{inner_content}</li> }.into_any()
                 }
             }
             Tag::Emphasis => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <em class=MarkdownClasses::EM>{inner_content}</em> }.into_any(),
-                        consumed,
-                    )
+                    view! { <em class=self.classes.em.clone()>{inner_content}</em> }.into_any()
                 } else {
-                    (view! { <em>{inner_content}</em> }.into_any(), consumed)
+                    view! { <em>{inner_content}</em> }.into_any()
                 }
             }
             Tag::Strong => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <strong class=MarkdownClasses::STRONG>{inner_content}</strong> }
-                            .into_any(),
-                        consumed,
-                    )
+                    view! { <strong class=self.classes.strong.clone()>{inner_content}</strong> }
+                        .into_any()
                 } else {
-                    (
-                        view! { <strong>{inner_content}</strong> }.into_any(),
-                        consumed,
-                    )
+                    view! { <strong>{inner_content}</strong> }.into_any()
                 }
             }
             Tag::Strikethrough => {
-                let inner_content = self.render_events(inner_events);
-                if use_explicit {
-                    (
-                        view! { <del class=MarkdownClasses::DEL>{inner_content}</del> }.into_any(),
-                        consumed,
-                    )
-                } else {
-                    (view! { <del>{inner_content}</del> }.into_any(), consumed)
-                }
-            }
-            Tag::Link {
-                dest_url, title, ..
-            } => {
-                let inner_content = self.render_events(inner_events);
-                let href = dest_url.to_string();
-                let link_class = if use_explicit {
-                    MarkdownClasses::LINK
-                } else {
-                    ""
-                };
-
-                if !title.is_empty() {
-                    if self.options.open_links_in_new_tab {
-                        (
-                            view! {
-                            <a class=link_class href=href title=title.to_string() target="_blank" rel="noopener noreferrer">
-                                {inner_content}
-                            </a>
-                        }
-                            .into_any(),
-                            consumed,
-                        )
-                    } else {
-                        (
-                            view! {
-                                <a class=link_class href=href title=title.to_string()>
-                                    {inner_content}
-                                </a>
-                            }
-                            .into_any(),
-                            consumed,
-                        )
+                let announcement = self
+                    .options
+                    .strikethrough_announcement
+                    .clone()
+                    .map(|text| view! { <span class="sr-only">{text}" "</span> });
+                let class = self.classes.del.clone();
+                match (self.options.strikethrough_element, use_explicit) {
+                    (StrikethroughElement::Del, true) => {
+                        view! { <del class=class>{announcement}{inner_content}</del> }.into_any()
+                    }
+                    (StrikethroughElement::Del, false) => {
+                        view! { <del>{announcement}{inner_content}</del> }.into_any()
+                    }
+                    (StrikethroughElement::S, true) => {
+                        view! { <s class=class>{announcement}{inner_content}</s> }.into_any()
+                    }
+                    (StrikethroughElement::S, false) => {
+                        view! { <s>{announcement}{inner_content}</s> }.into_any()
                     }
-                } else if self.options.open_links_in_new_tab {
-                    (
-                        view! {
-                            <a class=link_class href=href target="_blank" rel="noopener noreferrer">
-                                {inner_content}
-                            </a>
-                        }
-                        .into_any(),
-                        consumed,
-                    )
-                } else {
-                    (
-                        view! {
-                            <a class=link_class href=href>
-                                {inner_content}
-                            </a>
-                        }
-                        .into_any(),
-                        consumed,
-                    )
-                }
-            }
-            Tag::Image {
-                dest_url, title, ..
-            } => {
-                let src = dest_url.to_string();
-                let alt = self.extract_text_content(inner_events);
-                let img_class = if use_explicit {
-                    MarkdownClasses::IMAGE
-                } else {
-                    "markdown-image"
-                };
-
-                if !title.is_empty() {
-                    (
-                        view! {
-                            <img src=src alt=alt title=title.to_string() class=img_class />
-                        }
-                        .into_any(),
-                        consumed,
-                    )
-                } else {
-                    (
-                        view! {
-                            <img src=src alt=alt class=img_class />
-                        }
-                        .into_any(),
-                        consumed,
-                    )
                 }
             }
             Tag::Table(_) => {
-                let inner_content = self.render_events(inner_events);
-                let class = if use_explicit {
-                    MarkdownClasses::TABLE
-                } else {
-                    "markdown-table"
-                };
-                (
-                    view! {
-                        <table class=class>
-                            {inner_content}
-                        </table>
-                    }
-                    .into_any(),
-                    consumed,
-                )
+                if let Some(table) = &self.options.components.table {
+                    let props = TableProps {
+                        children: inner_content,
+                    };
+                    return table(props);
+                }
+                let class = self.classes.table.clone();
+                view! {
+                    <table class=class>
+                        {inner_content}
+                    </table>
+                }
+                .into_any()
             }
+            // pulldown-cmark puts the header cells straight into the head; HTML wants a row
             Tag::TableHead => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <thead class=MarkdownClasses::THEAD>{inner_content}</thead> }
-                            .into_any(),
-                        consumed,
-                    )
+                    view! {
+                        <thead class=self.classes.thead.clone()>
+                            <tr class=self.classes.tr.clone()>{inner_content}</tr>
+                        </thead>
+                    }
+                    .into_any()
                 } else {
-                    (
-                        view! { <thead>{inner_content}</thead> }.into_any(),
-                        consumed,
-                    )
+                    view! {
+                        <thead>
+                            <tr>{inner_content}</tr>
+                        </thead>
+                    }
+                    .into_any()
                 }
             }
             Tag::TableRow => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <tr class=MarkdownClasses::TR>{inner_content}</tr> }.into_any(),
-                        consumed,
-                    )
+                    view! { <tr class=self.classes.tr.clone()>{inner_content}</tr> }.into_any()
                 } else {
-                    (view! { <tr>{inner_content}</tr> }.into_any(), consumed)
+                    view! { <tr>{inner_content}</tr> }.into_any()
                 }
             }
-            Tag::TableCell => {
-                let inner_content = self.render_events(inner_events);
+            Tag::TableCell if in_table_head => {
                 if use_explicit {
-                    (
-                        view! { <td class=MarkdownClasses::TD>{inner_content}</td> }.into_any(),
-                        consumed,
-                    )
+                    view! { <th class=self.classes.th.clone() scope="col">{inner_content}</th> }
+                        .into_any()
                 } else {
-                    (view! { <td>{inner_content}</td> }.into_any(), consumed)
+                    view! { <th scope="col">{inner_content}</th> }.into_any()
                 }
             }
-            Tag::FootnoteDefinition(label) => {
-                let inner_content = self.render_events(inner_events);
-                let class = if use_explicit {
-                    MarkdownClasses::FOOTNOTE_DEF
-                } else {
-                    "footnote-definition"
-                };
-                (
-                    view! {
-                        <div class=class id=label.to_string()>
-                            {inner_content}
-                        </div>
-                    }
-                    .into_any(),
-                    consumed,
-                )
-            }
-            Tag::HtmlBlock => {
-                let raw_html = self.extract_text_content(inner_events);
-                if self.options.allow_raw_html {
-                    (
-                        view! {
-                            <div inner_html=raw_html></div>
-                        }
-                        .into_any(),
-                        consumed,
-                    )
+            Tag::TableCell => {
+                if use_explicit {
+                    view! { <td class=self.classes.td.clone()>{inner_content}</td> }.into_any()
                 } else {
-                    let class = if use_explicit {
-                        MarkdownClasses::RAW_HTML_BLOCK
-                    } else {
-                        "raw-html-block"
-                    };
-                    (
-                        view! {
-                            <pre class=class>{raw_html}</pre>
-                        }
-                        .into_any(),
-                        consumed,
-                    )
+                    view! { <td>{inner_content}</td> }.into_any()
                 }
             }
             Tag::DefinitionList => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <dl class=MarkdownClasses::DL>{inner_content}</dl> }.into_any(),
-                        consumed,
-                    )
+                    view! { <dl class=self.classes.dl.clone()>{inner_content}</dl> }.into_any()
                 } else {
-                    (view! { <dl>{inner_content}</dl> }.into_any(), consumed)
+                    view! { <dl>{inner_content}</dl> }.into_any()
                 }
             }
             Tag::DefinitionListTitle => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <dt class=MarkdownClasses::DT>{inner_content}</dt> }.into_any(),
-                        consumed,
-                    )
+                    view! { <dt class=self.classes.dt.clone()>{inner_content}</dt> }.into_any()
                 } else {
-                    (view! { <dt>{inner_content}</dt> }.into_any(), consumed)
+                    view! { <dt>{inner_content}</dt> }.into_any()
                 }
             }
             Tag::DefinitionListDefinition => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <dd class=MarkdownClasses::DD>{inner_content}</dd> }.into_any(),
-                        consumed,
-                    )
+                    view! { <dd class=self.classes.dd.clone()>{inner_content}</dd> }.into_any()
                 } else {
-                    (view! { <dd>{inner_content}</dd> }.into_any(), consumed)
+                    view! { <dd>{inner_content}</dd> }.into_any()
                 }
             }
             Tag::Superscript => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <sup class=MarkdownClasses::SUP>{inner_content}</sup> }.into_any(),
-                        consumed,
-                    )
+                    view! { <sup class=self.classes.sup.clone()>{inner_content}</sup> }.into_any()
                 } else {
-                    (view! { <sup>{inner_content}</sup> }.into_any(), consumed)
+                    view! { <sup>{inner_content}</sup> }.into_any()
                 }
             }
             Tag::Subscript => {
-                let inner_content = self.render_events(inner_events);
                 if use_explicit {
-                    (
-                        view! { <sub class=MarkdownClasses::SUB>{inner_content}</sub> }.into_any(),
-                        consumed,
-                    )
+                    view! { <sub class=self.classes.sub.clone()>{inner_content}</sub> }.into_any()
                 } else {
-                    (view! { <sub>{inner_content}</sub> }.into_any(), consumed)
+                    view! { <sub>{inner_content}</sub> }.into_any()
                 }
             }
-            Tag::MetadataBlock(_) => {
-                // Metadata blocks are currently ignored. You could expose the data through callbacks if desired.
-                ("".into_any(), consumed)
+            // Tags with their own render method never get here
+            _ => inner_content,
+        }
+    }
+
+    fn extract_html_content(&self, events: &[Event]) -> String {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Html(html) | Event::Text(html) => Some(html.as_ref()),
+                _ => None,
+            })
+            .collect::<Vec<&str>>()
+            .join("")
+    }
+
+    /// Raw HTML from the markdown, rendered, sanitized, escaped or dropped according to
+    /// the raw HTML policy
+    fn render_raw_html(&self, html: String, block: bool, state: &mut RenderState) -> AnyView {
+        match self.options.raw_html_policy() {
+            RawHtmlPolicy::Allow => raw_html_view(html, block),
+            #[cfg(feature = "ammonia")]
+            RawHtmlPolicy::Sanitize => {
+                let clean = sanitize_html(&html);
+                if clean != html {
+                    state
+                        .warnings
+                        .push(RenderWarning::RawHtmlSanitized { html });
+                }
+                raw_html_view(clean, block)
+            }
+            RawHtmlPolicy::Deny => {
+                state
+                    .warnings
+                    .push(RenderWarning::RawHtmlRemoved { html: html.clone() });
+                self.render_unsupported(UnsupportedKind::Embed, html, block)
+                    .unwrap_or_else(|| ().into_any())
+            }
+            // `Sanitize` without the `ammonia` feature falls back to escaping
+            _ => {
+                state
+                    .warnings
+                    .push(RenderWarning::RawHtmlEscaped { html: html.clone() });
+                if block {
+                    let class = self.classes.raw_html_block.clone();
+                    view! { <pre class=class>{html}</pre> }.into_any()
+                } else {
+                    html.into_any()
+                }
             }
         }
     }
 
-    fn find_matching_end(&self, events: &[Event]) -> (usize, usize) {
-        let mut depth = 0;
-        for (i, event) in events.iter().enumerate() {
-            match event {
-                Event::Start(_) => depth += 1,
-                Event::End(_) => {
-                    depth -= 1;
-                    if depth == 0 {
-                        return (i, i + 1);
-                    }
+    /// Math written while `enable_math` is off, only parsed when it is replaced
+    fn render_disabled_math(
+        &self,
+        source: String,
+        block: bool,
+        state: &mut RenderState,
+    ) -> AnyView {
+        state.warnings.push(RenderWarning::MathNotRendered {
+            math: source.clone(),
+        });
+        self.render_unsupported(UnsupportedKind::Math, source, block)
+            .unwrap_or_else(|| ().into_any())
+    }
+
+    /// Placeholder (or nothing, when hidden) for content the renderer deliberately
+    /// leaves unrendered; `None` keeps the caller's fallback
+    fn render_unsupported(
+        &self,
+        kind: UnsupportedKind,
+        source: String,
+        block: bool,
+    ) -> Option<AnyView> {
+        match self.options.unsupported {
+            UnsupportedContent::Fallback => None,
+            UnsupportedContent::Hide => Some(().into_any()),
+            UnsupportedContent::Placeholder => {
+                if let Some(placeholder) = &self.options.components.placeholder {
+                    return Some(placeholder(PlaceholderProps {
+                        kind,
+                        source,
+                        block,
+                    }));
                 }
-                _ => {}
+                let class = self.classes.unsupported.clone();
+                let view = if block {
+                    view! {
+                        <div class=class data-unsupported=kind.as_str() title=source>
+                            {kind.label()}
+                        </div>
+                    }
+                    .into_any()
+                } else {
+                    view! {
+                        <span class=class data-unsupported=kind.as_str() title=source>
+                            {kind.label()}
+                        </span>
+                    }
+                    .into_any()
+                };
+                Some(view)
             }
         }
-        // If no matching end found, consume all remaining events
-        (events.len(), events.len())
     }
 
-    fn extract_text_content(&self, events: &[Event]) -> String {
+    /// Drop HTML comments when `strip_html_comments` is set, reporting each to the handler
+    fn filter_html_comments(&self, html: &str, state: &mut RenderState) -> String {
+        if !self.options.strip_html_comments {
+            return html.to_string();
+        }
+
+        let (remaining, comments) = split_html_comments(html);
+        for comment in comments {
+            if let Some(handler) = &self.options.on_html_comment {
+                handler(&comment);
+            }
+            state
+                .warnings
+                .push(RenderWarning::HtmlCommentRemoved { comment });
+        }
+        remaining
+    }
+
+    pub(crate) fn extract_text_content(&self, events: &[Event]) -> String {
         events
             .iter()
             .filter_map(|event| match event {
@@ -665,3 +2707,336 @@ impl MarkdownRenderer {
             .join("")
     }
 }
+
+/// Whether a top-level block produces no visible output: a frontmatter block or an HTML
+/// block holding only comments
+fn renders_nothing(block: &[Event]) -> bool {
+    match block.first() {
+        Some(Event::Start(Tag::MetadataBlock(_))) => true,
+        Some(Event::Start(Tag::HtmlBlock)) => {
+            let html: String = block
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Html(html) => Some(html.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            split_html_comments(&html).0.trim().is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Split raw HTML into the markup outside `<!-- ... -->` comments and the comment bodies
+fn split_html_comments(html: &str) -> (String, Vec<String>) {
+    let mut remaining = String::with_capacity(html.len());
+    let mut comments = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<!--") {
+        remaining.push_str(&rest[..start]);
+        let after = &rest[start + 4..];
+        match after.find("-->") {
+            Some(end) => {
+                comments.push(after[..end].trim().to_string());
+                rest = &after[end + 3..];
+            }
+            None => {
+                // Unterminated comment swallows the rest of the fragment, like browsers do
+                comments.push(after.trim().to_string());
+                rest = "";
+            }
+        }
+    }
+    remaining.push_str(rest);
+
+    (remaining, comments)
+}
+
+/// Trusted or sanitized HTML injected as-is
+fn raw_html_view(html: String, block: bool) -> AnyView {
+    if block {
+        view! { <div inner_html=html></div> }.into_any()
+    } else {
+        view! { <span inner_html=html></span> }.into_any()
+    }
+}
+
+/// HTML of `view` without Leptos' hydration markers, which only a hydrating client needs
+/// and which would otherwise end up in feeds, emails and the render cache
+#[cfg(feature = "ssr")]
+pub(crate) fn static_html(view: AnyView) -> String {
+    strip_hydration_markers(&view.to_html())
+}
+
+/// Drop the `<!>` placeholders and `<!--<() />-->` empty-view comments `to_html` emits.
+/// Only these exact forms are removed, so comments in raw HTML survive; branch and
+/// suspense markers only appear with `to_html_branching` and streaming, which aren't used.
+#[cfg(feature = "ssr")]
+fn strip_hydration_markers(html: &str) -> String {
+    html.replace("<!--<() />-->", "").replace("<!>", "")
+}
+
+/// FNV-1a hash of the content: deterministic across platforms and processes, so
+/// anything derived from it matches between SSR and hydration
+pub(crate) fn content_hash(content: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    content.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Alert style for a GFM `> [!KIND]` blockquote
+pub(crate) fn alert_kind(kind: BlockQuoteKind) -> AlertKind {
+    match kind {
+        BlockQuoteKind::Note => AlertKind::Note,
+        BlockQuoteKind::Tip => AlertKind::Tip,
+        BlockQuoteKind::Important => AlertKind::Important,
+        BlockQuoteKind::Warning => AlertKind::Warning,
+        BlockQuoteKind::Caution => AlertKind::Caution,
+    }
+}
+
+/// Detect an Obsidian callout: a blockquote whose first line is `[!kind]`, optionally
+/// followed by a fold sign (`+`/`-`) and a title. Returns the kind, the title and the body.
+fn callout<'a>(inner_events: &[Event<'a>]) -> Option<(AlertKind, Option<String>, Vec<Event<'a>>)> {
+    let [Event::Start(Tag::Paragraph), Event::Text(first), rest @ ..] = inner_events else {
+        return None;
+    };
+    let marker = first.strip_prefix("[!")?;
+    let close = marker.find(']')?;
+    let label = &marker[..close];
+    if label.is_empty()
+        || !label
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+    let kind = callout_kind(label);
+
+    // The title runs to the end of the first line
+    let mut title = marker[close + 1..]
+        .trim_start_matches(['+', '-'])
+        .to_string();
+    let mut rest = rest.iter().cloned().peekable();
+    while let Some(event) = rest.next_if(|event| {
+        !matches!(
+            event,
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph)
+        )
+    }) {
+        if let Event::Text(text) | Event::Code(text) = event {
+            title.push_str(&text);
+        }
+    }
+    let title = title.trim();
+    let title = (!title.is_empty()).then(|| title.to_string());
+
+    let mut body = Vec::new();
+    match rest.next() {
+        // A callout without body text
+        Some(Event::End(TagEnd::Paragraph)) | None => {}
+        Some(_) => body.push(Event::Start(Tag::Paragraph)),
+    }
+    body.extend(rest);
+
+    Some((kind, title, body))
+}
+
+/// Alert kind closest to an Obsidian callout type; unknown types look like notes
+fn callout_kind(label: &str) -> AlertKind {
+    AlertKind::from_label(label).unwrap_or_else(|| match label.to_ascii_lowercase().as_str() {
+        "success" | "check" | "done" => AlertKind::Tip,
+        "question" | "help" | "faq" | "attention" => AlertKind::Warning,
+        "failure" | "fail" | "missing" | "error" | "bug" => AlertKind::Caution,
+        _ => AlertKind::Note,
+    })
+}
+
+/// `target` percent-encoded as a relative href, keeping `/` and `#` for paths and
+/// headings. Targets with a URL scheme (`javascript:`) or a leading `//` are left
+/// unresolved rather than linked.
+fn relative_wikilink_href(target: &str) -> Option<String> {
+    let has_scheme = target.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if has_scheme || target.starts_with("//") {
+        return None;
+    }
+
+    let mut href = String::with_capacity(target.len());
+    for byte in target.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b'#' => {
+                href.push(byte as char)
+            }
+            _ => href.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Some(href)
+}
+
+/// Whether an `![[embed]]` target is an image rather than a note
+fn is_image_path(target: &str) -> bool {
+    let path = target.split(['#', '|']).next().unwrap_or(target);
+    let extension = path.rsplit_once('.').map(|(_, extension)| extension);
+    matches!(
+        extension.map(str::to_ascii_lowercase).as_deref(),
+        Some("png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "bmp")
+    )
+}
+
+/// Detect a legacy callout: a blockquote whose first paragraph opens with a bold label
+/// such as `**Note:**` or `**Warning**:`. Returns the kind and the body without the label.
+fn legacy_alert<'a>(inner_events: &[Event<'a>]) -> Option<(AlertKind, Vec<Event<'a>>)> {
+    let [Event::Start(Tag::Paragraph), Event::Start(Tag::Strong), Event::Text(label), Event::End(TagEnd::Strong), rest @ ..] =
+        inner_events
+    else {
+        return None;
+    };
+    let label = label.trim();
+    let colon_inside = label.ends_with(':');
+    let kind = AlertKind::from_label(label.trim_end_matches(':'))?;
+
+    let mut rest = rest.iter().cloned().peekable();
+    let mut body = vec![Event::Start(Tag::Paragraph)];
+
+    // Drop the colon following the label (`**Note**:`) and the whitespace after it.
+    // A bold label without any colon is ordinary emphasis, not a callout.
+    if let Some(Event::Text(text)) = rest.peek() {
+        if !colon_inside && !text.starts_with(':') {
+            return None;
+        }
+        let trimmed = text.trim_start_matches(':').trim_start().to_string();
+        rest.next();
+        if !trimmed.is_empty() {
+            body.push(Event::Text(trimmed.into()));
+        }
+    } else if !colon_inside {
+        return None;
+    }
+    while matches!(rest.peek(), Some(Event::SoftBreak | Event::HardBreak)) && body.len() == 1 {
+        rest.next();
+    }
+    body.extend(rest);
+
+    // A label on its own line leaves an empty paragraph behind
+    if matches!(body.get(1), Some(Event::End(TagEnd::Paragraph))) {
+        body.drain(..2);
+    }
+
+    Some((kind, body))
+}
+
+/// `<h1>`–`<h6>` element for `level`, omitting the class attribute when `class` is `None`
+fn heading_view(
+    level: HeadingLevel,
+    class: Option<Class>,
+    id: Option<String>,
+    inner_content: AnyView,
+) -> AnyView {
+    match class {
+        Some(class) => match level {
+            HeadingLevel::H1 => view! { <h1 class=class id=id>{inner_content}</h1> }.into_any(),
+            HeadingLevel::H2 => view! { <h2 class=class id=id>{inner_content}</h2> }.into_any(),
+            HeadingLevel::H3 => view! { <h3 class=class id=id>{inner_content}</h3> }.into_any(),
+            HeadingLevel::H4 => view! { <h4 class=class id=id>{inner_content}</h4> }.into_any(),
+            HeadingLevel::H5 => view! { <h5 class=class id=id>{inner_content}</h5> }.into_any(),
+            HeadingLevel::H6 => view! { <h6 class=class id=id>{inner_content}</h6> }.into_any(),
+        },
+        None => match level {
+            HeadingLevel::H1 => view! { <h1 id=id>{inner_content}</h1> }.into_any(),
+            HeadingLevel::H2 => view! { <h2 id=id>{inner_content}</h2> }.into_any(),
+            HeadingLevel::H3 => view! { <h3 id=id>{inner_content}</h3> }.into_any(),
+            HeadingLevel::H4 => view! { <h4 id=id>{inner_content}</h4> }.into_any(),
+            HeadingLevel::H5 => view! { <h5 id=id>{inner_content}</h5> }.into_any(),
+            HeadingLevel::H6 => view! { <h6 id=id>{inner_content}</h6> }.into_any(),
+        },
+    }
+}
+
+/// Join adjacent text events, which the parser splits around brackets
+fn merge_text_events(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    /// Replace the run's first text event with the whole run's text
+    fn finish_run<'a>(merged: &mut [Event<'a>], run: Option<String>) {
+        if let (Some(run), Some(last)) = (run, merged.last_mut()) {
+            *last = Event::Text(CowStr::Boxed(run.into_boxed_str()));
+        }
+    }
+
+    let mut merged: Vec<Event> = Vec::with_capacity(events.len());
+    // Text of the current run once it spans more than one event, grown in place so
+    // long runs stay linear
+    let mut run: Option<String> = None;
+    for event in events {
+        if let (Event::Text(text), Some(Event::Text(first))) = (&event, merged.last()) {
+            run.get_or_insert_with(|| first.to_string()).push_str(text);
+            continue;
+        }
+        finish_run(&mut merged, run.take());
+        merged.push(event);
+    }
+    finish_run(&mut merged, run);
+    merged
+}
+
+/// `<code>` element of a code block, holding the highlighted markup when there is one
+fn code_element(class: Class, code: String, highlighted: Option<String>) -> AnyView {
+    match highlighted {
+        Some(html) => view! { <code class=class inner_html=html></code> }.into_any(),
+        None => view! { <code class=class>{code}</code> }.into_any(),
+    }
+}
+
+/// Numeric level (1–6) of a heading
+pub(crate) fn heading_number(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Characters after which a long token may break without hurting readability
+const TOKEN_BREAK_AFTER: &[char] = &['/', '.', '-', '_', '?', '&', '=', '#', ':', ','];
+
+/// Split text into segments so that a `<wbr>` between each pair lets long tokens wrap.
+/// Tokens shorter than `min_len` are left intact; longer ones break after URL punctuation
+/// or, failing that, every `min_len` characters.
+fn long_token_segments(text: &str, min_len: usize) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+
+    for piece in text.split_inclusive(char::is_whitespace) {
+        let token = piece.trim_end();
+        if token.chars().count() < min_len {
+            current.push_str(piece);
+            continue;
+        }
+
+        let token_chars = token.chars().count();
+        let mut run = 0;
+        for (i, c) in token.chars().enumerate() {
+            current.push(c);
+            run += 1;
+            let is_last = i + 1 == token_chars;
+            if !is_last && (TOKEN_BREAK_AFTER.contains(&c) || run >= min_len) {
+                segments.push(std::mem::take(&mut current));
+                run = 0;
+            }
+        }
+        current.push_str(&piece[token.len()..]);
+    }
+    segments.push(current);
+
+    segments
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+/// Named in-app routes that `[:route:name param=value]` directives link to, so content
+/// authors reference pages without hard-coding URLs.
+///
+/// Patterns use the router's `:param` segments:
+///
+/// ```rust,ignore
+/// let routes = RouteMap::new()
+///     .with_route("user_profile", "/users/:id")
+///     .with_route("docs", "/docs/:section/:page");
+/// assert_eq!(routes.href("user_profile", &[("id", "42")]), Some("/users/42".into()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct RouteMap {
+    routes: BTreeMap<String, String>,
+}
+
+impl RouteMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `pattern` (e.g. `/users/:id`) under `name`
+    #[must_use]
+    pub fn with_route(mut self, name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        self.routes.insert(name.into(), pattern.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Href of route `name` with its `:param` segments filled from `params`; parameters
+    /// the pattern doesn't use become the query string. `None` for an unknown route or
+    /// a missing segment parameter.
+    pub fn href(&self, name: &str, params: &[(&str, &str)]) -> Option<String> {
+        let pattern = self.routes.get(name)?;
+        let mut used = vec![false; params.len()];
+
+        let mut path = String::with_capacity(pattern.len());
+        for (i, segment) in pattern.split('/').enumerate() {
+            if i > 0 {
+                path.push('/');
+            }
+            match segment.strip_prefix(':') {
+                Some(key) => {
+                    let index = params.iter().position(|(name, _)| *name == key)?;
+                    used[index] = true;
+                    path.push_str(&encode_component(params[index].1));
+                }
+                None => path.push_str(segment),
+            }
+        }
+
+        let query: Vec<String> = params
+            .iter()
+            .zip(used)
+            .filter(|(_, used)| !used)
+            .map(|((key, value), _)| {
+                format!("{}={}", encode_component(key), encode_component(value))
+            })
+            .collect();
+        if !query.is_empty() {
+            path.push('?');
+            path.push_str(&query.join("&"));
+        }
+        Some(path)
+    }
+}
+
+/// A parsed `[:route:name key=value label="Text"]` directive
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RouteDirective<'a> {
+    /// The directive as written, shown when the route can't be resolved
+    pub(crate) source: &'a str,
+    pub(crate) name: &'a str,
+    pub(crate) params: Vec<(&'a str, &'a str)>,
+    /// Link text; defaults to the generated href
+    pub(crate) label: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RouteSegment<'a> {
+    Text(&'a str),
+    Route(RouteDirective<'a>),
+}
+
+const DIRECTIVE_PREFIX: &str = "[:route:";
+
+/// Split `text` around route directives; malformed directives stay text
+pub(crate) fn route_segments(text: &str) -> Vec<RouteSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut search = 0;
+
+    while let Some(found) = text[search..].find(DIRECTIVE_PREFIX) {
+        let open = search + found;
+        let inner_start = open + DIRECTIVE_PREFIX.len();
+        let Some((mut directive, close)) = parse_directive(&text[inner_start..]) else {
+            search = inner_start;
+            continue;
+        };
+        if open > text_start {
+            segments.push(RouteSegment::Text(&text[text_start..open]));
+        }
+        text_start = inner_start + close + 1;
+        directive.source = &text[open..text_start];
+        segments.push(RouteSegment::Route(directive));
+        search = text_start;
+    }
+
+    if text_start < text.len() {
+        segments.push(RouteSegment::Text(&text[text_start..]));
+    }
+    segments
+}
+
+/// Parse `name key=value ...]`, returning the directive and the index of its `]`
+fn parse_directive(inner: &str) -> Option<(RouteDirective<'_>, usize)> {
+    let name_end = inner.find(|c: char| c.is_whitespace() || c == ']')?;
+    let name = &inner[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    let mut label = None;
+    let mut rest = name_end;
+    loop {
+        let trimmed = inner[rest..].trim_start();
+        rest = inner.len() - trimmed.len();
+        if trimmed.starts_with(']') {
+            return Some((
+                RouteDirective {
+                    source: "",
+                    name,
+                    params,
+                    label,
+                },
+                rest,
+            ));
+        }
+
+        let eq = trimmed.find('=')?;
+        let key = &trimmed[..eq];
+        if key.is_empty() || key.contains(|c: char| c.is_whitespace() || c == ']') {
+            return None;
+        }
+        let value_start = rest + eq + 1;
+        let (value, value_end) = match inner[value_start..].strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], value_start + end + 2)
+            }
+            None => {
+                let end = inner[value_start..]
+                    .find(|c: char| c.is_whitespace() || c == ']')
+                    .map_or(inner.len(), |end| value_start + end);
+                (&inner[value_start..end], end)
+            }
+        };
+        if key == "label" {
+            label = Some(value);
+        } else {
+            params.push((key, value));
+        }
+        rest = value_end;
+    }
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,53 @@
+use pulldown_cmark::Event;
+
+/// Strip scripts, event handlers and other unsafe markup, keeping safe formatting tags
+#[cfg(feature = "ammonia")]
+pub(crate) fn sanitize_html(html: &str) -> String {
+    ammonia::clean(html)
+}
+
+/// An inline element holding only text (`<kbd>Ctrl</kbd>`, `<sup>2</sup>`), which the
+/// parser splits into opening tag, text and closing tag. Joined into one fragment so it
+/// renders (and sanitizes) as a whole; returns the HTML and the number of events used.
+pub(crate) fn inline_html_element(events: &[Event]) -> Option<(String, usize)> {
+    let Event::InlineHtml(open) = events.first()? else {
+        return None;
+    };
+    let tag = open.strip_prefix('<')?.strip_suffix('>')?;
+    if tag.ends_with('/') || !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = tag
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or(tag);
+    let close = format!("</{}>", name);
+
+    let mut html = open.to_string();
+    for (index, event) in events.iter().enumerate().skip(1) {
+        match event {
+            Event::Text(text) => html.push_str(&escape_html(text)),
+            Event::SoftBreak => html.push('\n'),
+            Event::InlineHtml(end) if end.eq_ignore_ascii_case(&close) => {
+                html.push_str(end);
+                return Some((html, index + 1));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,60 @@
+use pulldown_cmark::{Event, Tag};
+use std::collections::{HashMap, HashSet};
+
+/// GitHub-style heading slugs, deduplicated within one document
+/// (`intro`, `intro-1`, `intro-2`, ...)
+#[derive(Clone, Default)]
+pub(crate) struct Slugger {
+    seen: HashMap<String, usize>,
+    /// Ids handed out or reserved so far
+    taken: HashSet<String>,
+}
+
+impl Slugger {
+    /// A slugger for the headings of `events`, with their explicit `{#id}`s reserved so
+    /// generated slugs never collide with them
+    pub(crate) fn for_headings(events: &[Event]) -> Self {
+        let mut slugger = Self::default();
+        slugger.reserve_heading_ids(events);
+        slugger
+    }
+
+    /// Reserve the explicit `{#id}`s of the headings in `events`
+    pub(crate) fn reserve_heading_ids(&mut self, events: &[Event]) {
+        for event in events {
+            if let Event::Start(Tag::Heading { id: Some(id), .. }) = event {
+                self.taken.insert(id.to_string());
+            }
+        }
+    }
+
+    pub(crate) fn slug(&mut self, text: &str) -> String {
+        let base: String = text
+            .trim()
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    Some(c)
+                } else if c.is_whitespace() {
+                    Some('-')
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let count = self.seen.entry(base.clone()).or_insert(0);
+        loop {
+            let slug = if *count == 0 {
+                base.clone()
+            } else {
+                format!("{}-{}", base, count)
+            };
+            *count += 1;
+            if self.taken.insert(slug.clone()) {
+                return slug;
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::compat::*;
+use crate::error::MarkdownError;
+use crate::renderer::MarkdownRenderer;
+
+impl MarkdownRenderer {
+    /// Render `content` to canonical HTML for snapshot tests.
+    ///
+    /// The markup matches [`MarkdownRenderer::render_to_html`] with Leptos' hydration
+    /// markers and comments removed and each element's attributes sorted by name, so
+    /// snapshots stay stable across Leptos releases. Never served from `render_cache`.
+    pub fn render_to_canonical_html(&self, content: &str) -> Result<String, MarkdownError> {
+        self.render_pass(content, false)
+            .map(|(view, _)| canonicalize_html(&view.to_html()))
+    }
+}
+
+/// Drop comments and sort attributes; text and element order are left untouched
+pub(crate) fn canonicalize_html(html: &str) -> String {
+    let mut canonical = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        canonical.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if let Some(after) = rest.strip_prefix("<!>") {
+            rest = after;
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (tag, after) = canonical_start_tag(rest);
+            canonical.push_str(&tag);
+            rest = after;
+        } else {
+            canonical.push('<');
+            rest = &rest[1..];
+        }
+    }
+    canonical.push_str(rest);
+
+    canonical
+}
+
+/// Rebuild the start tag at the beginning of `html` with sorted attributes, returning it
+/// and the remaining input
+fn canonical_start_tag(html: &str) -> (String, &str) {
+    let name_end = html[1..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .map_or(html.len(), |i| i + 1);
+    let name = &html[1..name_end];
+
+    let mut attributes = Vec::new();
+    let mut rest = &html[name_end..];
+    let self_closing = loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            rest = after;
+            break true;
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            rest = after;
+            break false;
+        }
+        if rest.is_empty() {
+            break false;
+        }
+        let (attribute, after) = split_attribute(rest);
+        attributes.push(attribute);
+        rest = after;
+    };
+    attributes.sort_by(|a, b| attribute_name(a).cmp(attribute_name(b)));
+
+    let mut tag = format!("<{}", name);
+    for attribute in attributes {
+        tag.push(' ');
+        tag.push_str(attribute);
+    }
+    tag.push_str(if self_closing { "/>" } else { ">" });
+    (tag, rest)
+}
+
+/// Split one `name`, `name=value` or `name="quoted value"` attribute off `html`
+fn split_attribute(html: &str) -> (&str, &str) {
+    let mut quote = None;
+    for (i, c) in html.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            // `/` only ends an unquoted attribute when it closes the tag
+            None if c.is_whitespace() || c == '>' || html[i..].starts_with("/>") => {
+                return (&html[..i], &html[i..]);
+            }
+            None => {}
+        }
+    }
+    (html, "")
+}
+
+fn attribute_name(attribute: &str) -> &str {
+    attribute.split('=').next().unwrap_or(attribute)
+}
@@ -0,0 +1,135 @@
+use pulldown_cmark::{Event, Tag, TagEnd};
+
+/// Inline span marked by a doubled delimiter, which pulldown-cmark has no syntax for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SpanKind {
+    /// `==text==`, rendered as `<mark>`
+    Mark,
+    /// `||text||`, hidden until clicked
+    Spoiler,
+    /// `__text__` in chat content, rendered as `<u>`
+    Underline,
+    /// `++text++`, rendered as `<ins>`
+    Insert,
+}
+
+impl SpanKind {
+    pub(crate) const ALL: [SpanKind; 4] = [
+        SpanKind::Mark,
+        SpanKind::Spoiler,
+        SpanKind::Underline,
+        SpanKind::Insert,
+    ];
+
+    pub(crate) fn delimiter(self) -> &'static str {
+        match self {
+            SpanKind::Mark => "==",
+            SpanKind::Spoiler => "||",
+            SpanKind::Underline => "__",
+            SpanKind::Insert => "++",
+        }
+    }
+}
+
+/// Give every doubled delimiter of `kinds` its own text event, so the renderer can pair
+/// them around emphasis, links and other inline markup. Runs of three or more delimiter
+/// characters and text in code blocks are left alone.
+pub(crate) fn split_span_delimiters<'a>(
+    events: Vec<Event<'a>>,
+    kinds: &[SpanKind],
+) -> Vec<Event<'a>> {
+    let mut output = Vec::with_capacity(events.len());
+    // Open code, HTML and metadata blocks
+    let mut raw_depth = 0usize;
+
+    for event in events {
+        match &event {
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock | Tag::MetadataBlock(_)) => {
+                raw_depth += 1
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock | TagEnd::MetadataBlock(_)) => {
+                raw_depth = raw_depth.saturating_sub(1)
+            }
+            Event::Text(text) if raw_depth == 0 => {
+                let delimiters = delimiter_positions(text, kinds);
+                if !delimiters.is_empty() {
+                    let mut start = 0;
+                    for at in delimiters {
+                        if at > start {
+                            output.push(Event::Text(text[start..at].to_string().into()));
+                        }
+                        output.push(Event::Text(text[at..at + 2].to_string().into()));
+                        start = at + 2;
+                    }
+                    if start < text.len() {
+                        output.push(Event::Text(text[start..].to_string().into()));
+                    }
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        output.push(event);
+    }
+
+    output
+}
+
+/// Byte offsets of the doubled delimiters in `text`
+fn delimiter_positions(text: &str, kinds: &[SpanKind]) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    let mut positions = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let run = bytes[i..].iter().take_while(|b| **b == bytes[i]).count();
+        if run == 2
+            && kinds
+                .iter()
+                .any(|kind| kind.delimiter().as_bytes()[0] == bytes[i])
+        {
+            positions.push(i);
+        }
+        i += run;
+    }
+    positions
+}
+
+/// The span opened by the delimiter event starting `events`: its kind and the index of
+/// the closing delimiter among the same siblings. Opening delimiters must be followed by
+/// content and closing ones preceded by it, so `a == b` stays text.
+pub(crate) fn span_close(events: &[Event]) -> Option<(SpanKind, usize)> {
+    let Some(Event::Text(open)) = events.first() else {
+        return None;
+    };
+    let kind = SpanKind::ALL
+        .into_iter()
+        .find(|kind| open.as_ref() == kind.delimiter())?;
+    match events.get(1)? {
+        Event::Text(text) if text.starts_with(char::is_whitespace) => return None,
+        Event::Text(text) if text.as_ref() == kind.delimiter() => return None,
+        Event::SoftBreak | Event::HardBreak | Event::End(_) => return None,
+        _ => {}
+    }
+
+    let mut depth = 0usize;
+    for (i, event) in events.iter().enumerate().skip(2) {
+        match event {
+            Event::Start(_) => depth += 1,
+            // End of the enclosing element
+            Event::End(_) if depth == 0 => return None,
+            Event::End(_) => depth -= 1,
+            Event::Text(text) if depth == 0 && text.as_ref() == kind.delimiter() => {
+                let after_content = match &events[i - 1] {
+                    Event::Text(previous) => !previous.ends_with(char::is_whitespace),
+                    Event::SoftBreak | Event::HardBreak => false,
+                    _ => true,
+                };
+                if after_content {
+                    return Some((kind, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
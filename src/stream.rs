@@ -0,0 +1,390 @@
+use crate::compat::*;
+use crate::components::MarkdownOptions;
+use crate::renderer::MarkdownRenderer;
+use crate::slug::Slugger;
+use pulldown_cmark::{Event, Parser};
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+/// Incremental renderer for markdown that arrives piece by piece, such as LLM tokens
+/// from an SSE stream.
+///
+/// Top-level blocks followed by another block can no longer change, so they are
+/// committed and rendered once; only the trailing block is reparsed as text is appended.
+/// While it is incomplete, the trailing block is shown with open code fences and
+/// emphasis closed, so half-written markup doesn't flash as raw syntax.
+///
+/// Heading ids run on across blocks. Otherwise each block renders on its own:
+/// footnotes, link reference definitions and `:::` containers only resolve within the
+/// block that contains them.
+pub struct StreamingMarkdownRenderer {
+    renderer: Arc<MarkdownRenderer>,
+    source: String,
+    /// Source ranges of the committed blocks, in order
+    blocks: Vec<Range<usize>>,
+    /// Heading ids handed out up to and including each committed block rendered so far
+    sluggers: Mutex<Vec<Slugger>>,
+    /// Bumped whenever committed blocks are discarded, so views can tell them apart
+    generation: u64,
+}
+
+impl StreamingMarkdownRenderer {
+    pub fn new(options: MarkdownOptions) -> Self {
+        Self::from_renderer(Arc::new(MarkdownRenderer::new(options)))
+    }
+
+    /// Stream through an already configured, shared renderer
+    pub fn from_renderer(renderer: Arc<MarkdownRenderer>) -> Self {
+        Self {
+            renderer,
+            source: String::new(),
+            blocks: Vec::new(),
+            sluggers: Mutex::default(),
+            generation: 0,
+        }
+    }
+
+    /// Append newly received text
+    pub fn push_str(&mut self, chunk: &str) {
+        self.source.push_str(chunk);
+        self.commit_blocks();
+    }
+
+    /// Replace the text received so far. Committed blocks are kept when `source` extends
+    /// the current text, otherwise the stream starts over.
+    pub fn set_source(&mut self, source: &str) {
+        match source.strip_prefix(self.source.as_str()) {
+            Some(appended) => {
+                if !appended.is_empty() {
+                    self.push_str(appended);
+                }
+            }
+            None => {
+                self.source.clear();
+                self.blocks.clear();
+                self.sluggers
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clear();
+                self.generation += 1;
+                self.push_str(source);
+            }
+        }
+    }
+
+    /// All text received so far
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Number of committed blocks
+    pub fn completed_len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Sources of the committed blocks, which no longer change as text is appended
+    pub fn completed_blocks(&self) -> impl Iterator<Item = &str> + '_ {
+        self.blocks.iter().map(|range| &self.source[range.clone()])
+    }
+
+    /// The trailing, possibly incomplete block
+    pub fn tail(&self) -> &str {
+        &self.source[self.committed_end()..]
+    }
+
+    /// The trailing block as it is displayed: unclosed code fences, inline code and
+    /// emphasis are closed, and a dangling opening marker is left out
+    pub fn display_tail(&self) -> Cow<'_, str> {
+        close_open_markup(self.tail())
+    }
+
+    /// Render committed block `index`, with heading ids continuing from the blocks
+    /// before it. A block that fails to render shows `<Markdown>`'s error card.
+    pub fn render_block(&self, index: usize) -> AnyView {
+        if index >= self.blocks.len() {
+            return ().into_any();
+        }
+        let mut slugger = self.slugger_before(index);
+        let view = self.render_source(&self.source[self.blocks[index].clone()], &mut slugger);
+        let mut sluggers = self.sluggers.lock().unwrap_or_else(|e| e.into_inner());
+        if sluggers.len() == index {
+            sluggers.push(slugger);
+        }
+        view
+    }
+
+    /// Render the trailing block from [`StreamingMarkdownRenderer::display_tail`]
+    pub fn render_tail(&self) -> AnyView {
+        let mut slugger = self.slugger_before(self.blocks.len());
+        self.render_source(&self.display_tail(), &mut slugger)
+    }
+
+    /// Heading ids handed out by the committed blocks before `index`, rendering those
+    /// not rendered yet to find them
+    fn slugger_before(&self, index: usize) -> Slugger {
+        loop {
+            let rendered = {
+                let sluggers = self.sluggers.lock().unwrap_or_else(|e| e.into_inner());
+                match index.checked_sub(1) {
+                    None => return Slugger::default(),
+                    Some(previous) if previous < sluggers.len() => {
+                        return sluggers[previous].clone()
+                    }
+                    Some(_) => sluggers.len(),
+                }
+            };
+            let _ = self.render_block(rendered);
+        }
+    }
+
+    fn render_source(&self, source: &str, slugger: &mut Slugger) -> AnyView {
+        self.renderer
+            .render_with_slugger(source, slugger)
+            .unwrap_or_else(|err| {
+                log_error!("Failed to render markdown: {}", err);
+                crate::error_card(&err)
+            })
+    }
+
+    fn committed_end(&self) -> usize {
+        self.blocks.last().map_or(0, |range| range.end)
+    }
+
+    /// Commit every top-level block of the tail that another block follows
+    fn commit_blocks(&mut self) {
+        let start = self.committed_end();
+        let mut block_starts = Vec::new();
+        let mut depth = 0usize;
+        let parser = Parser::new_ext(&self.source[start..], self.renderer.parser_options());
+
+        for (event, range) in parser.into_offset_iter() {
+            match event {
+                Event::Start(_) => {
+                    if depth == 0 {
+                        block_starts.push(start + range.start);
+                    }
+                    depth += 1;
+                }
+                Event::End(_) => depth = depth.saturating_sub(1),
+                _ => {
+                    if depth == 0 {
+                        block_starts.push(start + range.start);
+                    }
+                }
+            }
+        }
+
+        // The last block may still grow, and until its first line is complete it may
+        // still turn out to continue the block before it (`2` becoming `2. item`)
+        if block_starts
+            .last()
+            .is_some_and(|&last| !self.source[last..].contains('\n'))
+        {
+            block_starts.pop();
+        }
+        let mut end = start;
+        for &next in block_starts.iter().skip(1) {
+            self.blocks.push(end..next);
+            end = next;
+        }
+    }
+}
+
+/// Close the markup a partial block leaves open, so it renders as it will look once
+/// complete rather than as raw syntax
+fn close_open_markup(text: &str) -> Cow<'_, str> {
+    if let Some(fence) = open_fence(text) {
+        let newline = if text.ends_with('\n') { "" } else { "\n" };
+        return Cow::Owned(format!("{}{}{}", text, newline, fence));
+    }
+
+    let paragraph_start = text.trim_end().rfind("\n\n").map_or(0, |i| i + 2);
+    let openers = open_inline_markers(&text[paragraph_start..]);
+    if openers.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut closed = text.to_string();
+    let mut closers = String::new();
+    for (marker, position) in openers.into_iter().rev() {
+        let position = paragraph_start + position;
+        if closers.is_empty() && closed[position + marker.len()..].trim().is_empty() {
+            // Nothing typed after the marker yet
+            closed.truncate(position);
+        } else {
+            closers.push_str(marker);
+        }
+    }
+    closed.truncate(closed.trim_end().len());
+    closed.push_str(&closers);
+    Cow::Owned(closed)
+}
+
+/// The closing fence for a code fence left open in `text`
+fn open_fence(text: &str) -> Option<String> {
+    let mut open: Option<(char, usize)> = None;
+    for line in text.lines() {
+        let line = line.trim_start();
+        let Some(marker) = line.chars().next().filter(|c| matches!(c, '`' | '~')) else {
+            continue;
+        };
+        let run = line.chars().take_while(|&c| c == marker).count();
+        if run < 3 {
+            continue;
+        }
+        open = match open {
+            None => Some((marker, run)),
+            Some((open_marker, open_run))
+                if marker == open_marker && run >= open_run && line[run..].trim().is_empty() =>
+            {
+                None
+            }
+            still_open => still_open,
+        };
+    }
+    open.map(|(marker, run)| marker.to_string().repeat(run))
+}
+
+/// Inline code and emphasis markers opened but not closed in `paragraph`, with their
+/// byte positions
+fn open_inline_markers(paragraph: &str) -> Vec<(&'static str, usize)> {
+    const TICKS: &str = "````````````````";
+    let bytes = paragraph.as_bytes();
+    let mut stack: Vec<(&'static str, usize)> = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if i == 0 || bytes[i - 1] == b'\n' {
+            let line = paragraph[i..].lines().next().unwrap_or_default();
+            if is_break_line(line) {
+                i += line.len();
+                continue;
+            }
+        }
+        match bytes[i] {
+            b'\\' => i += 1 + paragraph[i + 1..].chars().next().map_or(0, char::len_utf8),
+            b'`' => {
+                let run = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+                let ticks = &paragraph[i..i + run];
+                match paragraph[i + run..].find(ticks) {
+                    Some(end) => i += run + end + run,
+                    None => {
+                        // Everything after an unclosed code span is code
+                        stack.push((&TICKS[..run.min(TICKS.len())], i));
+                        break;
+                    }
+                }
+            }
+            b'~' if paragraph[i..].starts_with("~~") => {
+                if stack.last().is_some_and(|(open, _)| *open == "~~") {
+                    stack.pop();
+                } else {
+                    stack.push(("~~", i));
+                }
+                i += 2;
+            }
+            marker @ (b'*' | b'_') => {
+                let run = bytes[i..].iter().take_while(|&&b| b == marker).count();
+                let before = paragraph[..i].chars().next_back();
+                let after = paragraph[i + run..].chars().next();
+
+                // A run closes the innermost openers of the same character first
+                let mut left = run;
+                while let Some((open, _)) = stack.last() {
+                    if open.as_bytes()[0] != marker || open.len() > left {
+                        break;
+                    }
+                    left -= open.len();
+                    stack.pop();
+                }
+
+                let intraword = marker == b'_'
+                    && before.is_some_and(char::is_alphanumeric)
+                    && after.is_some_and(char::is_alphanumeric);
+                let can_open = left > 0 && !intraword && !after.is_some_and(char::is_whitespace);
+                if can_open {
+                    let (double, single) = if marker == b'*' {
+                        ("**", "*")
+                    } else {
+                        ("__", "_")
+                    };
+                    let start = i + run - left;
+                    if left >= 2 {
+                        stack.push((double, start));
+                    }
+                    if left % 2 == 1 {
+                        stack.push((single, start + left - 1));
+                    }
+                }
+                i += run;
+            }
+            _ => i += paragraph[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    stack
+}
+
+/// A thematic break or setext underline (`***`, `- - -`), whose markers aren't emphasis
+fn is_break_line(line: &str) -> bool {
+    let markers = line.trim();
+    markers.len() >= 3
+        && markers
+            .chars()
+            .all(|c| matches!(c, '*' | '-' | '_' | ' ' | '\t'))
+}
+
+/// Markdown component for text that is still arriving, e.g. an LLM reply streamed over
+/// SSE. Append to `content` as tokens come in: finished blocks render once and stay put,
+/// and only the trailing block re-renders on each update.
+#[component]
+pub fn MarkdownStream(
+    /// The text received so far
+    #[prop(into)]
+    content: Signal<String>,
+    /// Optional CSS class for the wrapper (will be combined with Tailwind prose classes)
+    #[prop(optional)]
+    class: Option<String>,
+    /// Markdown rendering options
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+) -> impl IntoView {
+    let renderer = Arc::new(MarkdownRenderer::new(
+        options
+            .or_else(crate::use_markdown_options)
+            .unwrap_or_default(),
+    ));
+    let wrapper_class = crate::wrapper_class(&renderer, &content.get_untracked(), class);
+    let stream = StoredValue::new(StreamingMarkdownRenderer::from_renderer(renderer));
+
+    // (generation, committed blocks, tail): blocks re-render only when the first two change
+    let progress = Memo::new(move |_| {
+        let text = content.get();
+        stream.update_value(|stream| stream.set_source(&text));
+        stream.with_value(|stream| {
+            (
+                stream.generation,
+                stream.completed_len(),
+                stream.tail().to_string(),
+            )
+        })
+    });
+
+    view! {
+        <div class=wrapper_class>
+            <For
+                each=move || {
+                    let (generation, len) = progress.with(|p| (p.0, p.1));
+                    (0..len).map(move |index| (generation, index))
+                }
+                key=|block| *block
+                children=move |(_, index)| stream.with_value(|stream| stream.render_block(index))
+            />
+            {move || {
+                progress.with(|_| ());
+                stream.with_value(|stream| stream.render_tail())
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,104 @@
+use crate::containers::{container_marker, ContainerMarker};
+use crate::fence::fence_tab;
+use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd};
+use std::ops::Range;
+
+/// One tab of a tab group
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Tab {
+    pub(crate) title: String,
+    /// Events shown in the tab's panel
+    pub(crate) body: Range<usize>,
+}
+
+/// Title of a `== Title` line inside a `:::tabs` container
+pub(crate) fn tab_title(line: &[Event]) -> Option<String> {
+    let mut text = String::new();
+    for event in line {
+        match event {
+            Event::Text(part) | Event::Code(part) => text.push_str(part),
+            _ => return None,
+        }
+    }
+    let title = text.strip_prefix("==")?;
+    if !title.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Title and length of a standalone `== Title` paragraph starting `events`
+fn title_paragraph(events: &[Event]) -> Option<(String, usize)> {
+    let [Event::Start(Tag::Paragraph), rest @ ..] = events else {
+        return None;
+    };
+    let end = rest
+        .iter()
+        .position(|event| matches!(event, Event::End(TagEnd::Paragraph)))?;
+    Some((tab_title(&rest[..end])?, end + 2))
+}
+
+/// The tabs of a `:::tabs` container's content, split at its top-level `== Title` lines,
+/// and the length of the content before the first one. Nested containers are skipped.
+pub(crate) fn container_tabs(events: &[Event]) -> (usize, Vec<Tab>) {
+    let mut intro = events.len();
+    let mut tabs: Vec<Tab> = Vec::new();
+    let mut depth = 0usize;
+    let mut containers = 0usize;
+    let mut i = 0;
+
+    while i < events.len() {
+        match &events[i] {
+            Event::Start(Tag::Paragraph) if depth == 0 => {
+                if containers == 0 {
+                    if let Some((title, len)) = title_paragraph(&events[i..]) {
+                        match tabs.last_mut() {
+                            Some(last) => last.body.end = i,
+                            None => intro = i,
+                        }
+                        tabs.push(Tab {
+                            title,
+                            body: i + len..events.len(),
+                        });
+                        i += len;
+                        continue;
+                    }
+                }
+                match events.get(i..i + 3).and_then(container_marker) {
+                    Some(ContainerMarker::Open { .. }) => containers += 1,
+                    Some(ContainerMarker::Close) => containers = containers.saturating_sub(1),
+                    None => {}
+                }
+                depth += 1;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (intro, tabs)
+}
+
+/// Consecutive fenced code blocks with a `tab=` attribute starting `events`, each tab
+/// holding a whole block, and the number of events they span
+pub(crate) fn code_tabs(events: &[Event]) -> Option<(Vec<Tab>, usize)> {
+    let mut tabs = Vec::new();
+    let mut i = 0;
+    while let Some(Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))) = events.get(i) {
+        let Some(title) = fence_tab(info) else {
+            break;
+        };
+        let end = i + events[i..]
+            .iter()
+            .position(|event| matches!(event, Event::End(TagEnd::CodeBlock)))?;
+        tabs.push(Tab {
+            title,
+            body: i..end + 1,
+        });
+        i = end + 1;
+    }
+    (!tabs.is_empty()).then_some((tabs, i))
+}
@@ -0,0 +1,37 @@
+use pulldown_cmark::{Event, Options, Parser};
+
+/// `source` with the `index`th task list box (0-based, in document order) flipped between
+/// `[ ]` and `[x]`; everything else is left byte for byte as written.
+///
+/// Returns `source` unchanged when it has fewer task items. Wire it to the checkboxes of
+/// an editable document with `content.update(|md| *md = toggle_task(md, index))`.
+pub fn toggle_task(source: &str, index: usize) -> String {
+    let options = Options::ENABLE_TASKLISTS
+        | Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH;
+    let marker = Parser::new_ext(source, options)
+        .into_offset_iter()
+        .filter(|(event, _)| matches!(event, Event::TaskListMarker(_)))
+        .nth(index);
+
+    let Some((_, range)) = marker else {
+        return source.to_string();
+    };
+    // The box character follows the opening bracket of the marker
+    let Some(open) = source[range.clone()].find('[') else {
+        return source.to_string();
+    };
+    let at = range.start + open + 1;
+    let flipped = match source.as_bytes().get(at) {
+        Some(b' ') => "x",
+        Some(b'x' | b'X') => " ",
+        _ => return source.to_string(),
+    };
+
+    let mut toggled = String::with_capacity(source.len());
+    toggled.push_str(&source[..at]);
+    toggled.push_str(flipped);
+    toggled.push_str(&source[at + 1..]);
+    toggled
+}
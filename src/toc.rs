@@ -0,0 +1,135 @@
+use crate::compat::*;
+use crate::components::MarkdownOptions;
+use crate::error::MarkdownError;
+use crate::parsed::MarkdownHeading;
+use crate::renderer::MarkdownRenderer;
+
+impl MarkdownRenderer {
+    /// Headings of `content` in document order, with the ids the rendered document gives
+    /// them: explicit `{#id}` attributes, or slugs when `heading_ids` is enabled. Headings
+    /// inside `toc_excluded_containers` are left out.
+    pub fn extract_headings(&self, content: &str) -> Result<Vec<MarkdownHeading>, MarkdownError> {
+        let events = self.parse_within_limits(content, |c| self.parse_events(c))?;
+        Ok(self.collect_headings(&events))
+    }
+}
+
+/// A heading with the headings nested below it
+struct TocNode {
+    heading: MarkdownHeading,
+    children: Vec<TocNode>,
+}
+
+/// Nest each heading under the closest preceding shallower heading
+fn toc_tree(headings: Vec<MarkdownHeading>) -> Vec<TocNode> {
+    fn insert(nodes: &mut Vec<TocNode>, heading: MarkdownHeading) {
+        match nodes.last_mut() {
+            Some(last) if heading.level > last.heading.level => insert(&mut last.children, heading),
+            _ => nodes.push(TocNode {
+                heading,
+                children: Vec::new(),
+            }),
+        }
+    }
+
+    let mut roots = Vec::new();
+    for heading in headings {
+        insert(&mut roots, heading);
+    }
+    roots
+}
+
+fn toc_list(renderer: &MarkdownRenderer, nodes: Vec<TocNode>, nested: bool) -> AnyView {
+    let items = nodes
+        .into_iter()
+        .map(|node| {
+            // Headings rendered without an id have nothing to link to
+            let entry = match node.heading.id {
+                Some(id) => view! {
+                    <a class=renderer.classes().toc_link.clone() href=renderer.anchor_href(&id)>
+                        {node.heading.text}
+                    </a>
+                }
+                .into_any(),
+                None => node.heading.text.into_any(),
+            };
+            let children =
+                (!node.children.is_empty()).then(|| toc_list(renderer, node.children, true));
+            view! {
+                <li class=renderer.classes().toc_item.clone()>
+                    {entry}
+                    {children}
+                </li>
+            }
+        })
+        .collect_view();
+
+    if nested {
+        view! { <ul class=renderer.classes().toc_nested.clone()>{items}</ul> }.into_any()
+    } else {
+        view! { <ul>{items}</ul> }.into_any()
+    }
+}
+
+/// Table of contents `<nav>` for `headings`, nesting each under the closest preceding
+/// shallower heading; shared by [`TableOfContents`] and [`MarkdownToc`]
+///
+/// [`MarkdownToc`]: crate::MarkdownToc
+pub(crate) fn toc_nav(
+    renderer: &MarkdownRenderer,
+    headings: Vec<MarkdownHeading>,
+    class: Option<String>,
+) -> impl IntoView {
+    let nav_class = match class {
+        Some(c) => format!("{} {}", renderer.classes().toc, c),
+        None => renderer.classes().toc.to_string(),
+    };
+
+    view! {
+        <nav class=nav_class aria-label="Table of contents">
+            {toc_list(renderer, toc_tree(headings), false)}
+        </nav>
+    }
+}
+
+/// Nested table of contents for a markdown string.
+///
+/// Links use the same ids as `<Markdown>`, so pass the same options to both and enable
+/// `heading_ids`; headings without an id are listed unlinked. Headings outside
+/// `min_depth..=max_depth` are left out.
+#[component]
+pub fn TableOfContents(
+    /// The markdown content as a string
+    #[prop(into)]
+    content: String,
+    /// Optional CSS class for the `<nav>`
+    #[prop(optional)]
+    class: Option<String>,
+    /// Markdown rendering options (should match the rendered document's)
+    #[prop(optional)]
+    options: Option<MarkdownOptions>,
+    /// Shallowest heading level listed
+    #[prop(default = 1)]
+    min_depth: u8,
+    /// Deepest heading level listed
+    #[prop(default = 6)]
+    max_depth: u8,
+) -> impl IntoView {
+    let renderer = MarkdownRenderer::new(
+        options
+            .or_else(crate::use_markdown_options)
+            .unwrap_or_default(),
+    );
+    let headings = match renderer.extract_headings(&content) {
+        Ok(headings) => headings
+            .into_iter()
+            .filter(|heading| (min_depth..=max_depth).contains(&heading.level))
+            .collect(),
+        Err(err) => {
+            log_error!("Failed to extract headings: {}", err);
+            Vec::new()
+        }
+    };
+
+    toc_nav(&renderer, headings, class)
+}
@@ -0,0 +1,101 @@
+/// Something the renderer altered or withheld while producing the view.
+///
+/// Returned by [`MarkdownRenderer::render_with_warnings`](crate::MarkdownRenderer::render_with_warnings)
+/// so applications can display or log what was changed for security reasons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RenderWarning {
+    /// An HTML comment was dropped (`strip_html_comments`)
+    HtmlCommentRemoved { comment: String },
+    /// Raw HTML was shown as text (`allow_raw_html` off or `RawHtmlPolicy::Escape`)
+    RawHtmlEscaped { html: String },
+    /// Raw HTML was left out under `RawHtmlPolicy::Deny`
+    RawHtmlRemoved { html: String },
+    /// Unsafe markup was stripped from raw HTML under `RawHtmlPolicy::Sanitize`;
+    /// `html` is the original
+    RawHtmlSanitized { html: String },
+    /// Trailing blocks were left out under `max_blocks` / `max_chars`
+    Truncated { omitted_blocks: usize },
+    /// A `[@key]` citation the resolver did not know, left as text
+    UnknownCitation { key: String },
+    /// A `[:route:name]` directive naming an unregistered route or missing one of its
+    /// parameters, left as text
+    UnknownRoute { name: String },
+    /// Math written with `enable_math` off, replaced under `UnsupportedContent::Placeholder`
+    /// or `Hide`
+    MathNotRendered { math: String },
+    /// A `:shortcode:` neither the emoji resolver nor the gemoji table knows, left as text
+    UnknownShortcode { code: String },
+    /// A link or image URL with a scheme `UrlPolicy::SafeSchemes` does not allow; the
+    /// link rendered as its text, the image as its alt text
+    BlockedUrl { url: String },
+}
+
+impl RenderWarning {
+    /// The user content a sanitizing warning removed or escaped; `None` for warnings
+    /// that do not alter user content (truncation, unknown citations)
+    pub fn altered_content(&self) -> Option<&str> {
+        match self {
+            RenderWarning::HtmlCommentRemoved { comment } => Some(comment),
+            RenderWarning::RawHtmlEscaped { html }
+            | RenderWarning::RawHtmlRemoved { html }
+            | RenderWarning::RawHtmlSanitized { html } => Some(html),
+            RenderWarning::BlockedUrl { url } => Some(url),
+            RenderWarning::Truncated { .. }
+            | RenderWarning::UnknownCitation { .. }
+            | RenderWarning::UnknownRoute { .. }
+            | RenderWarning::UnknownShortcode { .. }
+            | RenderWarning::MathNotRendered { .. } => None,
+        }
+    }
+}
+
+/// Content the sanitizer removed or escaped, reported to
+/// [`MarkdownOptions::with_sanitize_handler`](crate::MarkdownOptions::with_sanitize_handler)
+/// for audit logs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanitizeEvent {
+    /// What was altered
+    pub warning: RenderWarning,
+    /// Byte offset in the markdown source where the altered content starts, when it
+    /// could be located (transforms may have rewritten it)
+    pub offset: Option<usize>,
+}
+
+/// Sanitize events for the content-altering `warnings`, located in `source` in order
+pub(crate) fn sanitize_events(source: &str, warnings: &[RenderWarning]) -> Vec<SanitizeEvent> {
+    let mut cursor = 0;
+    warnings
+        .iter()
+        .filter_map(|warning| {
+            let altered = warning.altered_content()?;
+            let offset = locate(source, &mut cursor, altered);
+            let offset = match warning {
+                // Report where the comment's `<!--` starts rather than its body
+                RenderWarning::HtmlCommentRemoved { comment } if !comment.is_empty() => {
+                    offset.and_then(|body| source[..body].rfind("<!--"))
+                }
+                _ => offset,
+            };
+            Some(SanitizeEvent {
+                warning: warning.clone(),
+                offset,
+            })
+        })
+        .collect()
+}
+
+/// Find the first line of `altered` at or after `cursor`, advancing past it.
+///
+/// Only the first line is matched because block HTML nested in lists or quotes loses
+/// its indentation and markers in the parsed events.
+fn locate(source: &str, cursor: &mut usize, altered: &str) -> Option<usize> {
+    let needle = altered
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("<!--");
+    let offset = *cursor + source.get(*cursor..)?.find(needle)?;
+    *cursor = offset + needle.len();
+    Some(offset)
+}
@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod tests {
     use leptos_md::{
-        render_markdown_string, render_markdown_with_options, CodeBlockTheme, MarkdownClasses,
-        MarkdownOptions,
+        render_markdown_string, render_markdown_with_options, CodeBlockTheme, MarkdownClassHooks,
+        MarkdownClasses, MarkdownFlavor, MarkdownOptions,
     };
 
     #[test]
@@ -147,8 +147,9 @@ Term 2
             MarkdownClasses::BLOCKQUOTE.contains("border-l-4"),
             "Blockquote should have border-l-4 class"
         );
+        // Link colors come from `--md-link-color`, falling back to Tailwind's blue-600
         assert!(
-            MarkdownClasses::LINK.contains("text-blue"),
+            MarkdownClasses::LINK.contains("text-[color:var(--md-link-color,#2563eb)]"),
             "Link should have blue text color"
         );
         assert!(
@@ -237,4 +238,3402 @@ Term 2
             "Rendering without code theme should succeed"
         );
     }
+
+    #[test]
+    fn test_github_flavor_preset() {
+        #[cfg(feature = "ssr")]
+        use leptos_md::MarkdownRenderer;
+        use leptos_md::{RawHtmlPolicy, UrlPolicy};
+
+        let options = MarkdownOptions::for_flavor(MarkdownFlavor::GitHub);
+        assert!(options.enable_gfm, "GitHub flavor should enable GFM");
+        assert_eq!(options.code_theme, Some(CodeBlockTheme::GitHub));
+        assert!(
+            !options.open_links_in_new_tab,
+            "GitHub keeps links in the same tab"
+        );
+        assert_eq!(options.emoji_shortcodes, cfg!(feature = "emoji"));
+        assert_eq!(options.raw_html_policy(), RawHtmlPolicy::Sanitize);
+        assert_eq!(options.url_policy, Some(UrlPolicy::SafeSchemes));
+        assert!(options.gfm_alerts);
+
+        #[cfg(feature = "ssr")]
+        {
+            // Raw HTML from a mirrored README never runs scripts
+            let html = MarkdownRenderer::new(options.clone())
+                .render_to_html("<p onclick=\"steal()\">hi</p>\n\n<script>steal()</script>")
+                .unwrap();
+            assert!(!html.contains("<script"));
+            assert!(!html.contains("<p onclick"));
+
+            // So do markdown links and images with unsafe schemes
+            let html = MarkdownRenderer::new(options.clone())
+                .render_to_html("[docs](/docs) [run](javascript:steal()) ![x](data:image/png,AA)")
+                .unwrap();
+            assert!(html.contains("href=\"/docs\""), "{html}");
+            assert!(!html.contains("javascript:"), "{html}");
+            assert!(!html.contains("data:image"), "{html}");
+
+            let markdown = "- [x] done\n\n~single~ and ~~double~~ strikethrough[^1]\n\n[^1]: Note";
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(html.contains(r#"<input type="checkbox" checked disabled"#));
+            assert_eq!(html.matches("<del>").count(), 2);
+            assert!(html.contains(r#"role="doc-noteref""#));
+            assert!(html.contains(r#"role="doc-endnotes""#));
+        }
+    }
+
+    #[test]
+    fn test_html_comments_reported_when_stripped() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let options = MarkdownOptions::new()
+            .with_allow_raw_html(false)
+            .with_html_comment_handler(move |comment| {
+                sink.lock().unwrap().push(comment.to_string());
+            });
+        assert!(
+            options.strip_html_comments,
+            "Comments are stripped by default"
+        );
+
+        let markdown = "Text <!-- inline note --> here\n\n<!-- block note -->\n";
+        let result = render_markdown_with_options(markdown, options);
+        assert!(result.is_ok());
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec!["inline note".to_string(), "block note".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_long_token_breaks() {
+        #[cfg(feature = "ssr")]
+        use leptos_md::MarkdownRenderer;
+
+        let options = MarkdownOptions::new().with_long_token_breaks(24);
+        assert_eq!(options.long_token_breaks, Some(24));
+        assert!(MarkdownOptions::new().long_token_breaks.is_none());
+
+        #[cfg(feature = "ssr")]
+        {
+            let markdown = "See https://example.com/a/very/long/path/that/never/ends?with=query&and=more\n\n| Hash |\n|------|\n| 0123456789abcdef0123456789abcdef0123456789 |";
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            // URLs break after their separators, other tokens every 24 characters
+            assert!(html.contains("https:<wbr>/<wbr>/<wbr>example.<wbr>com/"));
+            assert!(html.contains("0123456789abcdef01234567<wbr>89abcdef0123456789"));
+
+            let html = MarkdownRenderer::new(MarkdownOptions::new())
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(!html.contains("<wbr>"));
+        }
+    }
+
+    #[test]
+    fn test_compact_headings() {
+        #[cfg(feature = "ssr")]
+        use leptos_md::MarkdownRenderer;
+
+        let options = MarkdownOptions::new().with_compact_headings(true);
+        assert!(options.compact_headings);
+        assert!(!MarkdownOptions::new().compact_headings);
+
+        #[cfg(feature = "ssr")]
+        {
+            let markdown = "# Title\n\n## Section\n\n###### Smallest";
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            // Each level steps down one size from the regular scale
+            assert!(html.contains(r#"<h1 class="text-2xl font-semibold"#));
+            assert!(html.contains(r#"<h2 class="text-xl font-semibold"#));
+            assert!(html.contains(r#"<h6 class="text-sm font-medium"#));
+        }
+    }
+
+    #[test]
+    fn test_class_hooks_are_stable_and_utility_free() {
+        let hooks = [
+            MarkdownClassHooks::H1,
+            MarkdownClassHooks::PARAGRAPH,
+            MarkdownClassHooks::CODE_BLOCK,
+            MarkdownClassHooks::TABLE,
+            MarkdownClassHooks::LINK,
+        ];
+        for hook in hooks {
+            assert!(hook.starts_with("md-"), "{hook} should use the md- prefix");
+            assert!(!hook.contains(' '), "{hook} should be a single class");
+        }
+        assert_eq!(MarkdownClassHooks::H1, "md-h1");
+        assert_eq!(MarkdownClassHooks::CODE_BLOCK, "md-code-block");
+    }
+
+    #[test]
+    fn test_anchor_href_options() {
+        let options = MarkdownOptions::new().with_anchor_base("/docs/guide");
+        assert_eq!(options.anchor_base.as_deref(), Some("/docs/guide"));
+        let markdown = "Jump to [setup](#setup)[^note]\n\n[^note]: Footnote";
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+
+        let options =
+            MarkdownOptions::new().with_anchor_href_resolver(|id| format!("/#/page?anchor={id}"));
+        let resolver = options.anchor_href_resolver.clone().unwrap();
+        assert_eq!(resolver("setup"), "/#/page?anchor=setup");
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let html =
+                MarkdownRenderer::new(MarkdownOptions::new().with_anchor_base("/docs/guide"))
+                    .render_to_html(markdown)
+                    .unwrap();
+            assert!(html.contains(r##"href="/docs/guide#setup""##), "{html}");
+            assert!(html.contains(r##"href="/docs/guide#fn-"##), "{html}");
+            assert!(html.contains(r##"href="/docs/guide#fnref-"##), "{html}");
+            assert!(!html.contains(r##"href="#"##), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_render_with_warnings() {
+        use leptos_md::{MarkdownRenderer, RenderWarning};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_allow_raw_html(false));
+        let (_, warnings) = renderer
+            .render_with_warnings("<!-- hidden -->\n\nHello <b>world</b>")
+            .expect("render should succeed");
+
+        assert!(warnings.contains(&RenderWarning::HtmlCommentRemoved {
+            comment: "hidden".to_string()
+        }));
+        assert!(warnings.contains(&RenderWarning::RawHtmlEscaped {
+            html: "<b>".to_string()
+        }));
+
+        let (_, warnings) = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_with_warnings("# Clean")
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        // Blocked URLs alter user content and reach the sanitize handler; unknown
+        // shortcodes are only reported
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&events);
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_raw_html_policy(leptos_md::RawHtmlPolicy::Sanitize)
+                .with_custom_emoji(|_| None)
+                .with_sanitize_handler(move |event| sink.lock().unwrap().push(event.clone())),
+        );
+        let (_, warnings) = renderer
+            .render_with_warnings("[x](javascript:void(0)) :unheard_of:")
+            .unwrap();
+        let blocked = RenderWarning::BlockedUrl {
+            url: "javascript:void(0)".to_string(),
+        };
+        let unknown = RenderWarning::UnknownShortcode {
+            code: "unheard_of".to_string(),
+        };
+        assert_eq!(warnings, [blocked.clone(), unknown.clone()]);
+        assert_eq!(blocked.altered_content(), Some("javascript:void(0)"));
+        assert_eq!(unknown.altered_content(), None);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].warning, blocked);
+        assert_eq!(events[0].offset, Some(4));
+    }
+
+    #[test]
+    fn test_render_batch() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new());
+        let comments = [
+            "**first**",
+            "",
+            "- a\n- b",
+            "`code` and [link](https://example.com)",
+        ];
+        let views = renderer.render_batch(&comments);
+        assert_eq!(views.len(), comments.len());
+        assert!(views.iter().all(Result::is_ok));
+        assert!(renderer.render_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_anchor_target_highlight() {
+        let options = MarkdownOptions::new().with_anchor_target_highlight(true);
+        assert!(options.highlight_anchor_target);
+        assert!(MarkdownClasses::ANCHOR_TARGET.contains("target:"));
+
+        let markdown = "See note[^a]\n\n[^a]: The note";
+        assert!(render_markdown_with_options(markdown, options.clone()).is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            // The highlight is styled through the class map
+            let options = options
+                .with_heading_ids(true)
+                .with_explicit_classes(true)
+                .with_class_map(leptos_md::MarkdownClassMap {
+                    anchor_target: "target:bg-pink-100".into(),
+                    ..Default::default()
+                });
+            let html = leptos_md::MarkdownRenderer::new(options)
+                .render_to_html(&format!("# Intro\n\n{markdown}"))
+                .unwrap();
+            assert_eq!(
+                html.matches("md-anchor-target target:bg-pink-100").count(),
+                2,
+                "{html}"
+            );
+            assert!(!html.contains("target:bg-yellow-100"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_quote_reply() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new());
+        let source = "Hello **bold world** and more\n\nSecond para";
+
+        assert_eq!(
+            renderer
+                .quote_reply(source, "world and")
+                .unwrap()
+                .as_deref(),
+            Some("> **bold world** and")
+        );
+        assert_eq!(
+            renderer
+                .quote_reply(source, "more\nSecond")
+                .unwrap()
+                .as_deref(),
+            Some("> more\n>\n> Second")
+        );
+        assert!(renderer
+            .quote_reply(source, "not in document")
+            .unwrap()
+            .is_none());
+        assert!(renderer.quote_reply(source, "   ").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_h1_policy() {
+        use leptos_md::H1Policy;
+
+        assert_eq!(MarkdownOptions::new().h1_policy, H1Policy::Keep);
+
+        let markdown = "# My Page\n\nIntro\n\n# Another Top Heading";
+        for policy in [
+            H1Policy::Keep,
+            H1Policy::Demote,
+            H1Policy::StripTitle("my page".to_string()),
+        ] {
+            let options = MarkdownOptions::new().with_h1_policy(policy.clone());
+            assert_eq!(options.h1_policy, policy);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let html =
+                MarkdownRenderer::new(MarkdownOptions::new().with_h1_policy(H1Policy::Demote))
+                    .render_to_html(markdown)
+                    .unwrap();
+            assert!(html.contains("<h2>My Page</h2>"), "{html}");
+            assert!(html.contains("<h2>Another Top Heading</h2>"), "{html}");
+            assert!(!html.contains("<h1"), "{html}");
+
+            let options =
+                MarkdownOptions::new().with_h1_policy(H1Policy::StripTitle("my page".to_string()));
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(!html.contains("My Page"), "{html}");
+            // Other H1s are demoted
+            assert!(html.contains("<h2>Another Top Heading</h2>"), "{html}");
+            assert!(html.contains("Intro"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_legacy_alerts() {
+        use leptos_md::AlertKind;
+
+        assert_eq!(AlertKind::from_label("Warning"), Some(AlertKind::Warning));
+        assert_eq!(AlertKind::from_label("danger"), Some(AlertKind::Caution));
+        assert_eq!(AlertKind::from_label("Remember"), None);
+
+        let options = MarkdownOptions::new().with_legacy_alerts(true);
+        assert!(options.legacy_alerts);
+
+        let markdown = "> **Note:** Legacy callout\n\n> **Warning**:\n> Label on its own line\n\n> **Bold** quote";
+        assert!(render_markdown_with_options(markdown, options.clone()).is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(html.contains("markdown-alert-note"), "{html}");
+            assert!(html.contains("markdown-alert-warning"), "{html}");
+            assert!(!html.contains("Note:"), "{html}");
+            assert!(html.contains("Legacy callout"), "{html}");
+            assert_eq!(html.matches("<blockquote").count(), 1, "{html}");
+            assert!(html.contains("<strong>Bold</strong> quote"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_granular_extension_flags() {
+        let defaults = MarkdownOptions::new();
+        assert!(defaults.enable_tables && defaults.enable_footnotes);
+        assert!(defaults.enable_strikethrough && defaults.enable_tasklists);
+        assert!(!defaults.enable_math && !defaults.enable_definition_lists);
+        assert!(!defaults.enable_superscript && !defaults.enable_subscript);
+
+        let no_gfm = MarkdownOptions::new().with_gfm(false);
+        assert!(!no_gfm.enable_tables && !no_gfm.enable_footnotes);
+
+        let options = MarkdownOptions::new()
+            .with_gfm(false)
+            .with_footnotes(true)
+            .with_math(true)
+            .with_definition_lists(true)
+            .with_superscript(true)
+            .with_subscript(true);
+        assert!(options.enable_footnotes && !options.enable_tables);
+        assert!(options.enable_gfm, "Enabling a GFM flag turns GFM back on");
+
+        let markdown = "Note[^1] with $x^2$ and H~2~O and 2^10^\n\nTerm\n: Definition\n\n| not | a table |\n|---|---|\n\n[^1]: Footnote";
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+
+        // `enable_gfm: false` in a struct literal switches the GFM flags off with it
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let options = MarkdownOptions {
+                enable_gfm: false,
+                ..MarkdownOptions::default()
+            };
+            let html = MarkdownRenderer::new(options)
+                .render_to_html("| a | b |\n|---|---|\n| 1 | 2 |\n\n~~gone~~\n\n> [!NOTE]\n> Plain")
+                .unwrap();
+            assert!(!html.contains("<table"), "{html}");
+            assert!(!html.contains("<del"), "{html}");
+            assert!(!html.contains("markdown-alert"), "{html}");
+            assert!(html.contains("[!NOTE]"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_scope_class() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new());
+        assert!(renderer.scope_class("# Doc").is_none());
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_scope_class());
+        let first = renderer.scope_class("# Doc").unwrap();
+        assert!(first.starts_with("md-scope-"));
+        assert_eq!(
+            Some(first.clone()),
+            renderer.scope_class("# Doc"),
+            "Deterministic"
+        );
+        assert_ne!(Some(first), renderer.scope_class("# Other"));
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_named_scope("pricing"));
+        assert_eq!(
+            renderer.scope_class("# Doc").as_deref(),
+            Some("md-scope-pricing")
+        );
+    }
+
+    #[test]
+    fn test_parsed_markdown_shares_one_parse() {
+        use leptos_md::{MarkdownHeading, ParsedMarkdown};
+
+        let markdown =
+            "# Guide\n\n## Setup\n\nText[^1]\n\n## Setup\n\n### Hello, World!\n\n[^1]: A note";
+        let parsed =
+            ParsedMarkdown::new(markdown, MarkdownOptions::new().with_heading_ids(true)).unwrap();
+        assert_eq!(parsed.source(), markdown);
+        assert!(parsed.has_footnotes());
+
+        let headings = parsed.headings();
+        let ids: Vec<&str> = headings.iter().filter_map(|h| h.id.as_deref()).collect();
+        assert_eq!(ids, ["guide", "setup", "setup-1", "hello-world"]);
+        assert_eq!(
+            headings[3],
+            MarkdownHeading {
+                level: 3,
+                text: "Hello, World!".to_string(),
+                id: Some("hello-world".to_string()),
+            }
+        );
+
+        // Without heading ids the body's headings have no ids, and neither do the TOC's
+        let plain = ParsedMarkdown::new(markdown, MarkdownOptions::new()).unwrap();
+        assert!(plain.headings().iter().all(|h| h.id.is_none()));
+
+        let _body = parsed.render_body();
+        let _footnotes = parsed.render_footnotes();
+        assert!(!ParsedMarkdown::new("plain", MarkdownOptions::new())
+            .unwrap()
+            .has_footnotes());
+    }
+
+    #[test]
+    fn test_strikethrough_semantics() {
+        use leptos_md::StrikethroughElement;
+
+        let defaults = MarkdownOptions::new();
+        assert_eq!(defaults.strikethrough_element, StrikethroughElement::Del);
+        assert!(defaults.strikethrough_announcement.is_none());
+
+        let options = MarkdownOptions::new()
+            .with_strikethrough_element(StrikethroughElement::S)
+            .with_strikethrough_announcement("original price:");
+        assert_eq!(options.strikethrough_element, StrikethroughElement::S);
+        assert_eq!(
+            options.strikethrough_announcement.as_deref(),
+            Some("original price:")
+        );
+
+        let markdown = "Now ~~$40~~ $30";
+        assert!(render_markdown_with_options(
+            markdown,
+            options.clone().with_explicit_classes(true)
+        )
+        .is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(
+                html.contains(r#"<s><span class="sr-only">original price: </span>$40</s>"#),
+                "{html}"
+            );
+            let html = MarkdownRenderer::new(defaults)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(html.contains("<del>$40</del>"), "{html}");
+            assert!(!html.contains("sr-only"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_heading_attributes() {
+        use leptos_md::ParsedMarkdown;
+
+        assert!(!MarkdownOptions::new().enable_heading_attributes);
+        let options = MarkdownOptions::new().with_heading_attributes(true);
+        assert!(options.enable_heading_attributes);
+
+        let markdown = "# Install {#install .lead}\n\n## Usage";
+        assert!(render_markdown_with_options(markdown, options.clone()).is_ok());
+
+        let headings = ParsedMarkdown::new(markdown, options.clone())
+            .unwrap()
+            .headings();
+        assert_eq!(headings[0].id.as_deref(), Some("install"));
+        assert_eq!(headings[0].text, "Install");
+        assert_eq!(headings[1].id, None);
+
+        // Explicit ids are reserved before slugs are generated, so none repeats
+        let markdown = "# A\n# A\n# A {#a-1}\n# A-1";
+        let ids: Vec<String> =
+            ParsedMarkdown::new(markdown, options.clone().with_heading_ids(true))
+                .unwrap()
+                .headings()
+                .into_iter()
+                .filter_map(|heading| heading.id)
+                .collect();
+        assert_eq!(ids, ["a", "a-2", "a-1", "a-1-1"]);
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let html = MarkdownRenderer::new(options.with_heading_ids(true))
+                .render_to_html(markdown)
+                .unwrap();
+            assert_eq!(html.matches("id=\"a-1\"").count(), 1, "{html}");
+            assert!(html.contains("id=\"a-2\""), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_protected_links_option() {
+        assert!(MarkdownOptions::new().protect_links_from_rewrites);
+
+        let markdown = "[https://example.com/a/very/long/link/text/that/would/break](https://example.com) and `https://example.com/in/code/spans/stays/intact`";
+        for protect in [true, false] {
+            let options = MarkdownOptions::new()
+                .with_long_token_breaks(16)
+                .with_protected_links(protect);
+            assert_eq!(options.protect_links_from_rewrites, protect);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_heading_ids_option() {
+        assert!(!MarkdownOptions::new().heading_ids);
+        assert!(MarkdownOptions::for_flavor(MarkdownFlavor::GitHub).heading_ids);
+
+        let markdown = "# Intro\n\n## Setup\n\n## Setup\n\n## Setup";
+        let options = MarkdownOptions::new()
+            .with_heading_ids(true)
+            .with_anchor_target_highlight(true);
+        assert!(options.heading_ids);
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+    }
+
+    #[test]
+    fn test_heading_anchors_option() {
+        assert!(!MarkdownOptions::new().heading_anchors);
+        assert!(MarkdownClasses::HEADING_ANCHOR.contains("group-hover:opacity-100"));
+        assert!(MarkdownClasses::HEADING_ANCHOR.contains("focus-visible:opacity-100"));
+
+        let markdown = "# Intro\n\n## Setup\n\n## [Linked](https://example.com) heading";
+        for explicit in [false, true] {
+            let options = MarkdownOptions::new()
+                .with_heading_ids(true)
+                .with_heading_anchors(true)
+                .with_explicit_classes(explicit);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_heading_anchors_accessible() {
+        use leptos_md::MarkdownRenderer;
+
+        let options = MarkdownOptions::new()
+            .with_heading_ids(true)
+            .with_heading_anchors(true);
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("## Setup *guide*\n\n## [Linked](https://example.com) heading")
+            .unwrap();
+        // The anchor follows the heading text, so it's reached right after it when tabbing
+        assert!(html.contains(
+            r##"Setup <em>guide</em><a href="#setup-guide" aria-label="Link to section Setup guide""##
+        ));
+        assert!(html.contains(r#"<span aria-hidden="true">#</span></a></h2>"#));
+
+        // A heading that is already a link keeps its anchor out of the tab order and the
+        // accessibility tree
+        let linked = &html[html.find(r#"id="linked-heading""#).unwrap()..];
+        assert!(linked.contains(r##"<a href="#linked-heading" aria-hidden="true" tabindex="-1""##));
+        assert_eq!(html.matches("tabindex").count(), 1);
+        assert_eq!(html.matches("aria-label=").count(), 1);
+    }
+
+    #[test]
+    fn test_render_cache_backend() {
+        use leptos_md::{MarkdownCacheBackend, MarkdownRenderer, MemoryCache, RenderCache};
+
+        let memory = MemoryCache::new();
+        assert!(memory.is_empty());
+        memory.put("key", "<p>cached</p>".to_string());
+        assert_eq!(memory.get("key").as_deref(), Some("<p>cached</p>"));
+        assert_eq!(memory.get("missing"), None);
+
+        let options =
+            MarkdownOptions::new().with_render_cache(RenderCache::new(MemoryCache::new()));
+        assert!(options.render_cache.is_some());
+
+        let renderer = MarkdownRenderer::new(options);
+        assert_eq!(renderer.cache_key("# A"), renderer.cache_key("# A"));
+        assert_ne!(renderer.cache_key("# A"), renderer.cache_key("# B"));
+        let other = MarkdownRenderer::new(MarkdownOptions::new().with_explicit_classes(true));
+        assert_ne!(renderer.cache_key("# A"), other.cache_key("# A"));
+
+        // Callbacks aren't hashed; namespaces keep such renderers apart
+        let docs = MarkdownRenderer::new(MarkdownOptions::new().with_cache_namespace("docs"));
+        let blog = MarkdownRenderer::new(MarkdownOptions::new().with_cache_namespace("blog"));
+        assert!(docs.cache_key("# A").starts_with("docs:"));
+        assert_ne!(docs.cache_key("# A"), blog.cache_key("# A"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_render_to_html_uses_cache() {
+        use leptos_md::{MarkdownRenderer, MemoryCache, RenderCache};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new().with_render_cache(RenderCache::new(MemoryCache::new())),
+        );
+        let key = renderer.cache_key("# Title");
+        let html = renderer.render_to_html("# Title").unwrap();
+        assert!(html.contains("Title"));
+
+        let cache = renderer.options().render_cache.clone().unwrap();
+        assert_eq!(cache.get(&key), Some(html.clone()));
+        cache.put(&key, "<p>from cache</p>".to_string());
+        assert_eq!(
+            renderer.render_to_html("# Title").unwrap(),
+            "<p>from cache</p>"
+        );
+    }
+
+    #[test]
+    fn test_precomputed_classes_cover_all_elements() {
+        use leptos_md::MarkdownRenderer;
+
+        let markdown = "# H1\n\n## H2\n\n> quote\n\n> **Note:** alert\n\n```rust\nfn main() {}\n```\n\n    indented\n\n1. one\n\n- *em* **strong** ~~del~~ `code` [link](https://example.com) ![img](a.png)[^1]\n- [x] task\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n---\n\n[^1]: Note";
+        for theme in [None, Some(CodeBlockTheme::Monokai)] {
+            for explicit in [false, true] {
+                let mut options = MarkdownOptions::new()
+                    .with_explicit_classes(explicit)
+                    .with_compact_headings(true)
+                    .with_legacy_alerts(true)
+                    .with_anchor_target_highlight(true);
+                options.code_theme = theme.clone();
+                let renderer = MarkdownRenderer::new(options);
+                assert!(renderer.render(markdown).is_ok());
+                assert_eq!(renderer.render_batch(&[markdown, markdown]).len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_headings() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_heading_ids(true));
+        let headings = renderer
+            .extract_headings("# Guide\n\n## Install\n\n### From `cargo`\n\n## Install\n\ntext")
+            .unwrap();
+        let summary: Vec<(u8, &str, &str)> = headings
+            .iter()
+            .map(|h| {
+                (
+                    h.level,
+                    h.text.as_str(),
+                    h.id.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "Guide", "guide"),
+                (2, "Install", "install"),
+                (3, "From cargo", "from-cargo"),
+                (2, "Install", "install-1"),
+            ]
+        );
+
+        // Without heading ids only explicit ids are listed, as in the rendered document
+        let plain = MarkdownRenderer::new(MarkdownOptions::new().with_heading_attributes(true));
+        let ids: Vec<_> = plain
+            .extract_headings(
+                "# Guide {#top}
+
+## Install",
+            )
+            .unwrap()
+            .into_iter()
+            .map(|h| h.id)
+            .collect();
+        assert_eq!(ids, [Some("top".to_string()), None]);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_toc_components_share_markup() {
+        use leptos::prelude::*;
+        use leptos_md::{MarkdownBody, MarkdownToc, ParsedMarkdown, TableOfContents};
+
+        let markdown = "# Guide\n\n## Install\n\n### From cargo\n\n## Usage";
+        for heading_ids in [true, false] {
+            let options = MarkdownOptions::new().with_heading_ids(heading_ids);
+            let parsed = ParsedMarkdown::new(markdown, options.clone()).unwrap();
+            let toc = view! { <MarkdownToc parsed=parsed.clone() /> }.to_html();
+            let nav =
+                view! { <TableOfContents content=markdown options=options.clone() /> }.to_html();
+            assert_eq!(toc, nav);
+            // Nested under the top-level heading rather than indented in one list
+            assert_eq!(toc.matches("<ul").count(), 3);
+
+            let body = view! { <MarkdownBody parsed=parsed /> }.to_html();
+            for id in ["guide", "install", "from-cargo", "usage"] {
+                let target = format!("id=\"{}\"", id);
+                let link = format!("href=\"#{}\"", id);
+                assert_eq!(body.contains(&target), heading_ids);
+                assert_eq!(toc.contains(&link), heading_ids);
+            }
+        }
+
+        // Both are styled through the class map
+        let options = MarkdownOptions::new()
+            .with_heading_ids(true)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                toc: "text-xs".into(),
+                toc_item: "py-1".into(),
+                toc_link: "text-slate-600".into(),
+                toc_nested: "ml-4".into(),
+                ..Default::default()
+            });
+        let parsed = ParsedMarkdown::new(markdown, options.clone()).unwrap();
+        let toc = view! { <MarkdownToc parsed=parsed /> }.to_html();
+        let nav = view! { <TableOfContents content=markdown options=options /> }.to_html();
+        assert_eq!(toc, nav);
+        assert!(toc.contains(r#"class="md-toc text-xs">"#), "{toc}");
+        assert_eq!(
+            toc.matches(r#"<li class="md-toc-item py-1">"#).count(),
+            4,
+            "{toc}"
+        );
+        assert_eq!(
+            toc.matches(r#"class="md-toc-link text-slate-600">"#)
+                .count(),
+            4,
+            "{toc}"
+        );
+        assert_eq!(
+            toc.matches(r#"<ul class="md-toc-nested ml-4">"#).count(),
+            2,
+            "{toc}"
+        );
+    }
+
+    #[test]
+    fn test_table_header_cells() {
+        let markdown = "| Name | Value |\n|------|-------|\n| a | 1 |";
+        for explicit in [false, true] {
+            let options = MarkdownOptions::new().with_explicit_classes(explicit);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_render_first_block() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::default());
+        let (_, has_more) = renderer
+            .render_first_block("New **reply** on your post\n\n> quoted\n\nMore")
+            .unwrap();
+        assert!(has_more);
+        let (_, has_more) = renderer.render_first_block("Only one paragraph").unwrap();
+        assert!(!has_more);
+        let (_, has_more) = renderer.render_first_block("").unwrap();
+        assert!(!has_more);
+
+        // Frontmatter and comment blocks render nothing, so the preview starts after them
+        let frontmatter =
+            MarkdownRenderer::new(MarkdownOptions::new().with_frontmatter_handler(|_| {}));
+        let with_frontmatter = "---\ntitle: x\n---\n\nFirst para";
+        let with_comment = "<!-- draft -->\n\nHello there\n\nMore";
+        let (_, has_more) = frontmatter.render_first_block(with_frontmatter).unwrap();
+        assert!(!has_more);
+        let (_, has_more) = renderer.render_first_block(with_comment).unwrap();
+        assert!(has_more);
+
+        // Parsing stops after the previewed block, so only that prefix counts toward limits
+        let limited = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_max_input_len(100)
+                .with_max_nesting_depth(8),
+        );
+        let long_tail = format!("Short intro\n\n{}\n\n{}", "a".repeat(500), ">".repeat(20));
+        let (_, has_more) = limited.render_first_block(&long_tail).unwrap();
+        assert!(has_more);
+        assert!(limited
+            .render_first_block(&format!("{}\n\nShort", "a".repeat(101)))
+            .is_err());
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos::tachys::view::RenderHtml;
+
+            let html = frontmatter
+                .render_first_block(with_frontmatter)
+                .unwrap()
+                .0
+                .to_html();
+            assert!(html.contains("First para"), "{html}");
+            assert!(!html.contains("title"), "{html}");
+            let html = renderer
+                .render_first_block(with_comment)
+                .unwrap()
+                .0
+                .to_html();
+            assert!(html.contains("Hello there"), "{html}");
+            assert!(!html.contains("More"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_footnotes_numbered_with_backlinks() {
+        use leptos_md::{MarkdownRenderer, ParsedMarkdown};
+
+        let markdown = "Second[^b] then first[^a] and again[^b].\n\n[^a]: Alpha\n\n[^b]: Beta\n\n[^unused]: Gamma";
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_explicit_classes(true));
+        assert!(renderer.render(markdown).is_ok());
+        assert_eq!(renderer.render_batch(&[markdown]).len(), 1);
+
+        let parsed = ParsedMarkdown::new(markdown, MarkdownOptions::default()).unwrap();
+        assert!(parsed.has_footnotes());
+        let _ = parsed.render_body();
+        let _ = parsed.render_footnotes();
+
+        // Ids are slugged labels behind a per-document prefix
+        #[cfg(feature = "ssr")]
+        {
+            let html = renderer
+                .render_to_html("See[^My Note \"x\"].\n\n[^My Note \"x\"]: Text")
+                .unwrap();
+            let id = html
+                .split("id=\"fn-")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .unwrap();
+            assert!(id.ends_with("-my-note-x"), "{html}");
+            assert!(html.contains(&format!("href=\"#fn-{}\"", id)), "{html}");
+            assert!(html.contains(&format!("id=\"fnref-{}\"", id)), "{html}");
+
+            let other = renderer
+                .render_to_html("Other[^My Note \"x\"].\n\n[^My Note \"x\"]: Text")
+                .unwrap();
+            assert!(!other.contains(&format!("\"fn-{}\"", id)), "{other}");
+
+            // Unreferenced definitions are listed without a dead backlink
+            let html = renderer.render_to_html(markdown).unwrap();
+            assert_eq!(html.matches("role=\"doc-backlink\"").count(), 2, "{html}");
+            assert!(html.contains("Gamma"), "{html}");
+            assert!(!html.contains("Back to reference 3"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_gfm_alerts() {
+        assert!(MarkdownOptions::new().gfm_alerts);
+
+        let markdown = "> [!NOTE]\n> Useful information.\n\n> [!WARNING]\n> Careful.\n\n> [!CAUTION]\n> Danger.";
+        for enable in [true, false] {
+            let options = MarkdownOptions::new().with_gfm_alerts(enable);
+            assert_eq!(options.gfm_alerts, enable);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_output_cap_reports_truncation() {
+        use leptos_md::{MarkdownRenderer, RenderWarning};
+
+        let markdown = "First paragraph.\n\nSecond paragraph.\n\nThird paragraph.";
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_max_blocks(2));
+        let (_, warnings) = renderer.render_with_warnings(markdown).unwrap();
+        assert!(warnings.contains(&RenderWarning::Truncated { omitted_blocks: 1 }));
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_max_chars(20));
+        let (_, warnings) = renderer.render_with_warnings(markdown).unwrap();
+        assert!(warnings.contains(&RenderWarning::Truncated { omitted_blocks: 2 }));
+
+        // A first block longer than the cap is cut inside its text
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_max_chars(9));
+        let oversized = "Long **single paragraph** that goes on";
+        let (_, warnings) = renderer.render_with_warnings(oversized).unwrap();
+        assert!(warnings.contains(&RenderWarning::Truncated { omitted_blocks: 0 }));
+
+        #[cfg(feature = "ssr")]
+        {
+            let html = renderer.render_to_html(oversized).unwrap();
+            assert!(html.contains("Long <strong>sing"), "{html}");
+            assert!(
+                !html.contains("single") && !html.contains("goes on"),
+                "{html}"
+            );
+            assert!(html.contains("…"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_component_overrides() {
+        use leptos::prelude::*;
+        use leptos_md::{CodeBlockProps, LinkProps, MarkdownComponents};
+        use std::sync::{Arc, Mutex};
+
+        let languages = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&languages);
+        let components = MarkdownComponents::new()
+            .with_code_block(move |props: CodeBlockProps| {
+                sink.lock().unwrap().push(props.language.clone());
+                view! { <pre class="custom">{props.code}</pre> }.into_any()
+            })
+            .with_link(|props: LinkProps| {
+                view! { <a class="custom-link" href=props.href>{props.children}</a> }.into_any()
+            });
+        assert!(components.image.is_none());
+
+        let markdown = "```rust\nfn main() {}\n```\n\n    indented\n\n[link](https://example.com)";
+        let options = MarkdownOptions::new().with_components(components);
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+        assert_eq!(
+            *languages.lock().unwrap(),
+            vec![Some("rust".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_soft_break_newline_mode() {
+        use leptos_md::SoftBreakMode;
+
+        assert_eq!(MarkdownOptions::new().soft_break, SoftBreakMode::Space);
+
+        let markdown = "Roses are red,\nViolets are blue.";
+        for explicit in [false, true] {
+            let options = MarkdownOptions::new()
+                .with_soft_break(SoftBreakMode::Newline)
+                .with_explicit_classes(explicit);
+            assert_eq!(options.soft_break, SoftBreakMode::Newline);
+            assert!(render_markdown_with_options(markdown, options).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_code_expand_action() {
+        assert!(!MarkdownOptions::new().code_expand_action);
+
+        let markdown = "```rust\nfn main() {}\n```\n\n```rust\nfn main() {}\n```";
+        let options = MarkdownOptions::new().with_code_expand_action(true);
+        assert!(options.code_expand_action);
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+
+            let options = MarkdownOptions::new()
+                .with_code_expand_action(true)
+                .with_explicit_classes(true)
+                .with_class_map(leptos_md::MarkdownClassMap {
+                    code_modal: "modal-box".into(),
+                    ..Default::default()
+                });
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert_eq!(
+                html.matches(r#"class="md-code-modal modal-box""#).count(),
+                2
+            );
+            assert!(html.contains(r#"class="md-code-expand absolute"#), "{html}");
+            assert!(
+                html.contains(r#"class="md-code-modal-close sticky"#),
+                "{html}"
+            );
+            assert!(html.contains("md-code-modal-pre text-base"), "{html}");
+        }
+    }
+
+    #[test]
+    fn test_event_transforms_run_in_order() {
+        use leptos_md::pulldown_cmark::{CowStr, Event};
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::default())
+            .with_transform(|events| {
+                events
+                    .into_iter()
+                    .map(|event| match event {
+                        Event::Text(text) => {
+                            Event::Text(CowStr::from(text.replace("Draft", "Final")))
+                        }
+                        other => other,
+                    })
+                    .collect()
+            })
+            .with_transform(|events| {
+                events
+                    .into_iter()
+                    .filter(|event| !matches!(event, Event::Rule))
+                    .collect()
+            });
+        assert_eq!(renderer.options().event_transforms.len(), 2);
+
+        let headings = renderer
+            .extract_headings("# Draft title\n\n---\n\nBody")
+            .unwrap();
+        assert_eq!(headings[0].text, "Final title");
+        assert!(renderer.render("# Draft\n\n---").is_ok());
+    }
+
+    #[cfg(all(feature = "syntect", feature = "ssr"))]
+    #[test]
+    fn test_syntect_highlighting() {
+        use leptos_md::MarkdownRenderer;
+
+        let markdown = "```rust\nfn main() {}\n```";
+        let html = MarkdownRenderer::new(MarkdownOptions::default())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains("<span class=\"hl-"));
+
+        let plain = MarkdownRenderer::new(MarkdownOptions::new().with_syntect_highlighting(false))
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(!plain.contains("hl-"));
+        assert!(CodeBlockTheme::Monokai.syntect_css().contains(".hl-"));
+    }
+
+    #[test]
+    fn test_figures_and_listings() {
+        use leptos_md::{FigureKind, MarkdownRenderer};
+
+        let markdown = "[[lof]]\n\n![Flow](flow.png \"Request flow\")\n\n![Inline](a.png \"Not alone\") text\n\n```rust caption=\"Server setup\"\nfn main() {}\n```\n\n![Second](b.png \"Deploy\")";
+        let options = MarkdownOptions::new()
+            .with_figures(true)
+            .with_captioned_listings(true);
+        let renderer = MarkdownRenderer::new(options.clone());
+
+        let figures = renderer.extract_figures(markdown).unwrap();
+        assert_eq!(figures.len(), 3);
+        assert_eq!(figures[0].kind, FigureKind::Figure);
+        assert_eq!(figures[0].id, "figure-1");
+        assert_eq!(figures[1].title(), "Listing 1: Server setup");
+        assert_eq!(figures[2].title(), "Figure 2: Deploy");
+
+        assert!(MarkdownRenderer::new(MarkdownOptions::default())
+            .extract_figures(markdown)
+            .unwrap()
+            .is_empty());
+        assert!(render_markdown_with_options(markdown, options.clone()).is_ok());
+
+        #[cfg(feature = "ssr")]
+        {
+            // The list of figures is styled through the class map
+            let options =
+                options
+                    .with_explicit_classes(true)
+                    .with_class_map(leptos_md::MarkdownClassMap {
+                        lof: "my-2".into(),
+                        toc_item: "py-1".into(),
+                        toc_link: "text-slate-600".into(),
+                        ..Default::default()
+                    });
+            let html = MarkdownRenderer::new(options)
+                .render_to_html(markdown)
+                .unwrap();
+            assert!(html.contains(r#"class="md-lof my-2">"#), "{html}");
+            assert_eq!(
+                html.matches(r#"<li class="md-toc-item py-1">"#).count(),
+                3,
+                "{html}"
+            );
+            assert_eq!(
+                html.matches(r#"class="md-toc-link text-slate-600">"#)
+                    .count(),
+                3,
+                "{html}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_citations_resolve_and_warn() {
+        use leptos_md::{Citation, MarkdownRenderer, RenderWarning};
+
+        let options = MarkdownOptions::new().with_citations(|key| {
+            (key == "knuth84")
+                .then(|| Citation::new("Knuth 1984", "Knuth, D. E. Literate Programming."))
+        });
+        assert!(options.citation_resolver.is_some());
+
+        let markdown = "As shown [@knuth84, p. 33; @missing], and again [@knuth84].\n\nPlain [brackets] and me@example.com stay.";
+        let renderer = MarkdownRenderer::new(options);
+        let (_, warnings) = renderer.render_with_warnings(markdown).unwrap();
+        assert_eq!(
+            warnings,
+            vec![RenderWarning::UnknownCitation {
+                key: "missing".to_string()
+            }]
+        );
+
+        // Long runs of bracket-split text are joined back whole
+        let markdown = format!("{}end", "[a] ".repeat(2000));
+        let text = renderer.render_to_plain_text(&markdown).unwrap();
+        assert_eq!(text.matches("[a] ").count(), 2000);
+        assert!(text.ends_with("[a] end"));
+    }
+
+    #[test]
+    fn test_inline_footnotes() {
+        use leptos_md::pulldown_cmark::Event;
+        use leptos_md::MarkdownRenderer;
+
+        let markdown =
+            "Claim[^a] and^[an *inline* note] more^[second].\n\n`^[code]`\n\n[^a]: Regular";
+        let options = MarkdownOptions::new().with_inline_footnotes(true);
+        let labels = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = labels.clone();
+        let renderer = MarkdownRenderer::new(options.with_event_transform(move |events| {
+            for event in &events {
+                if let Event::FootnoteReference(label) = event {
+                    seen.lock().unwrap().push(label.to_string());
+                }
+            }
+            events
+        }));
+        assert!(renderer.render(markdown).is_ok());
+        assert_eq!(*labels.lock().unwrap(), ["a", "inline-1", "inline-2"]);
+    }
+
+    #[test]
+    fn test_mermaid_diagrams() {
+        assert!(!MarkdownOptions::new().mermaid_diagrams);
+
+        let markdown = "```mermaid\ngraph TD\n  A --> B\n```";
+        let options = MarkdownOptions::new().with_mermaid_diagrams(true);
+        assert!(options.mermaid_diagrams);
+        assert!(render_markdown_with_options(markdown, options).is_ok());
+    }
+
+    #[test]
+    fn test_block_filter_drops_and_redacts_sections() {
+        use leptos_md::{BlockAction, MarkdownRenderer};
+
+        let markdown = "# Guide\n\n## Public\n\nText\n\n## Secrets {.internal}\n\n### Keys\n\nhidden\n\n## Draft {status=draft}\n\nwip\n\n## Later\n\nText";
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_heading_attributes(true)
+                .with_block_filter(|block| {
+                    if block.has_class("internal") {
+                        BlockAction::Drop
+                    } else if block.attr("status") == Some(Some("draft")) {
+                        BlockAction::Redact
+                    } else {
+                        BlockAction::Keep
+                    }
+                }),
+        );
+
+        let headings: Vec<_> = renderer
+            .extract_headings(markdown)
+            .unwrap()
+            .into_iter()
+            .map(|heading| heading.text)
+            .collect();
+        assert_eq!(headings, ["Guide", "Public", "Later"]);
+        assert!(renderer.render(markdown).is_ok());
+    }
+
+    #[test]
+    fn test_frontmatter_split_and_handler() {
+        use leptos_md::pulldown_cmark::MetadataBlockKind;
+        use leptos_md::{Frontmatter, MarkdownRenderer};
+        use std::sync::{Arc, Mutex};
+
+        let source = "---\ntitle: Hello\n---\n# Body\n";
+        let (frontmatter, body) = Frontmatter::split(source);
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(frontmatter.kind, MetadataBlockKind::YamlStyle);
+        assert_eq!(frontmatter.raw, "title: Hello\n");
+        assert_eq!(body, "# Body\n");
+
+        let (toml, _) = Frontmatter::split("+++\ntitle = \"Hi\"\n+++\nBody");
+        assert_eq!(toml.unwrap().kind, MetadataBlockKind::PlusesStyle);
+        assert_eq!(Frontmatter::split("---\n\n---\nText").0, None);
+        assert_eq!(Frontmatter::split("No frontmatter").1, "No frontmatter");
+
+        let seen = Arc::new(Mutex::new(None));
+        let sink = seen.clone();
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_frontmatter_handler(
+            move |frontmatter| {
+                *sink.lock().unwrap() = Some(frontmatter.raw.clone());
+            },
+        ));
+        assert!(renderer.render(source).is_ok());
+        assert_eq!(
+            seen.lock().unwrap().as_deref().map(str::trim),
+            Some("title: Hello")
+        );
+        assert!(renderer.options().enable_metadata_blocks);
+
+        // Off by default: a leading `---` stays a thematic break
+        assert!(!MarkdownOptions::new().enable_metadata_blocks);
+        assert!(
+            MarkdownOptions::for_flavor(leptos_md::MarkdownFlavor::Obsidian).enable_metadata_blocks
+        );
+        #[cfg(feature = "ssr")]
+        {
+            let html = MarkdownRenderer::new(MarkdownOptions::new())
+                .render_to_html("---\n\nIntro\n\n---\n\nMore")
+                .unwrap();
+            assert_eq!(html.matches("<hr").count(), 2);
+            assert!(html.contains("Intro"));
+
+            let html = MarkdownRenderer::new(MarkdownOptions::new().with_metadata_blocks(true))
+                .render_to_html(source)
+                .unwrap();
+            assert!(!html.contains("title: Hello"));
+            assert!(html.contains("Body</h1>"));
+        }
+    }
+
+    #[cfg(feature = "frontmatter")]
+    #[test]
+    fn test_parse_frontmatter_typed() {
+        use leptos_md::MarkdownRenderer;
+        use std::collections::BTreeMap;
+
+        let (data, body) = MarkdownRenderer::parse_frontmatter::<BTreeMap<String, String>>(
+            "---\ntitle: Hello\n---\nBody",
+        );
+        assert_eq!(data.unwrap()["title"], "Hello");
+        assert_eq!(body, "Body");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_render_markdown_to_html() {
+        use leptos_md::{render_markdown_to_html, MarkdownRenderer, RawHtmlPolicy};
+
+        let html = render_markdown_to_html(
+            "# Title\n\n**bold**",
+            MarkdownOptions::new().with_explicit_classes(true),
+        )
+        .unwrap();
+        assert!(html.contains("<h1"));
+        assert!(html.contains(MarkdownClassHooks::H1));
+        assert!(html.contains("<strong"));
+        // Hydration markers stay out of plain HTML strings
+        assert!(!html.contains("<!>"), "{html}");
+        assert!(html.ends_with("</p>"), "{html}");
+
+        // Nor do the comments Leptos leaves for empty views, such as denied raw HTML
+        let html =
+            MarkdownRenderer::new(MarkdownOptions::new().with_raw_html_policy(RawHtmlPolicy::Deny))
+                .render_to_html("a <b>x</b> c\n\n<div>blk</div>\n\nz")
+                .unwrap();
+        assert!(!html.contains("<!--"), "{html}");
+        assert_eq!(html.matches("<p").count(), 2, "{html}");
+        assert!(html.ends_with("<p>z</p>"), "{html}");
+
+        // Comments in raw HTML that resemble Leptos markers are the author's and stay
+        let raw = "<div><!--bo-note--><!--s-1-o-->kept<!--bc-note--></div>";
+        let options = MarkdownOptions::new()
+            .with_allow_raw_html(true)
+            .with_strip_html_comments(false);
+        let html = MarkdownRenderer::new(options).render_to_html(raw).unwrap();
+        assert!(html.contains(raw), "{html}");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_render_diff_word_level() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::MarkdownRenderer;
+
+        let old = "# Title\n\nThe quick brown fox.\n\nRemoved block";
+        let new = "# Title\n\nThe quick red fox jumps.\n\n```\nadded\n```";
+        let html = MarkdownRenderer::new(MarkdownOptions::default())
+            .render_diff(old, new)
+            .unwrap()
+            .to_html();
+        assert!(html.contains(">brown</del>"));
+        assert!(html.contains(">red</ins>"));
+        assert!(html.contains(">jumps.</ins>"));
+        assert!(html.contains("Removed block"));
+        assert!(!html.contains(">quick</"));
+
+        // Paragraphs with inline markup are replaced whole, keeping their formatting
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_explicit_classes(true)
+                .with_class_map(leptos_md::MarkdownClassMap {
+                    diff_removed: "bg-rose-50".into(),
+                    diff_added: "bg-emerald-50".into(),
+                    diff_word_removed: "line-through".into(),
+                    diff_word_added: "underline".into(),
+                    ..Default::default()
+                }),
+        );
+        let html = renderer
+            .render_diff("A **bold** claim", "A **bold** statement")
+            .unwrap()
+            .to_html();
+        assert_eq!(html.matches("<strong").count(), 2, "{html}");
+        assert!(
+            html.contains(r#"<del class="md-diff-removed bg-rose-50">"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<ins class="md-diff-added bg-emerald-50">"#),
+            "{html}"
+        );
+        let html = renderer
+            .render_diff("plain old", "plain new")
+            .unwrap()
+            .to_html();
+        assert!(
+            html.contains(r#"<del class="md-diff-word-removed line-through">old</del>"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<ins class="md-diff-word-added underline">new</ins>"#),
+            "{html}"
+        );
+
+        // Rewrites too large for the edit table are replaced as a whole run
+        let old: String = (0..1500).map(|i| format!("old {i}\n\n")).collect();
+        let new: String = (0..1500).map(|i| format!("new {i}\n\n")).collect();
+        let html = renderer
+            .render_diff(&format!("Intro\n\n{old}"), &format!("Intro\n\n{new}"))
+            .unwrap()
+            .to_html();
+        assert_eq!(html.matches("<del").count(), 1500);
+        assert_eq!(html.matches("<ins").count(), 1500);
+        assert!(html.starts_with("<p class=\"md-p"), "{}", &html[..80]);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_render_diff_heading_ids_and_footnotes() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::MarkdownRenderer;
+
+        let old = "## Setup\n\nSee the note[^a].\n\n[^a]: Note";
+        let new = "## Setup\n\nSee the note[^a].\n\n## Setup\n\n[^a]: Note";
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_heading_ids(true))
+            .render_diff(old, new)
+            .unwrap()
+            .to_html();
+        assert!(html.contains(r#"<h2 id="setup""#), "{html}");
+        assert!(html.contains(r#"<h2 id="setup-1""#), "{html}");
+        // Footnotes are numbered, and a definition block is still a list item in a list
+        assert!(html.contains(r##"href="#fn-"##), "{html}");
+        assert!(html.contains(r#"role="doc-noteref">1</a>"#), "{html}");
+        assert!(html.contains(r#"<ol start="1""#), "{html}");
+    }
+
+    #[test]
+    fn test_toc_excludes_container_headings() {
+        use leptos_md::MarkdownRenderer;
+
+        let markdown = "# Guide\n\n## Setup\n\n:::appendix\n## Setup\n\nRaw data\n:::\n\n## Usage";
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_heading_ids(true)
+                .with_toc_excluded_container("appendix"),
+        );
+        assert!(renderer.options().containers);
+
+        let ids: Vec<_> = renderer
+            .extract_headings(markdown)
+            .unwrap()
+            .into_iter()
+            .filter_map(|heading| heading.id)
+            .collect();
+        assert_eq!(ids, ["guide", "setup", "usage"]);
+        assert!(renderer.render(markdown).is_ok());
+
+        let plain = MarkdownRenderer::new(MarkdownOptions::new().with_containers(true));
+        assert_eq!(plain.extract_headings(markdown).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_plain_text_and_excerpt() {
+        use leptos_md::{excerpt, render_to_plain_text, MarkdownRenderer};
+
+        let markdown = "# Hello *World*\n\nSome **bold** text with `code`[^1] and ![a cat](cat.png).\n\n- one\n- two\n\n[^1]: Footnote body";
+        assert_eq!(
+            render_to_plain_text(markdown).unwrap(),
+            "Hello World\n\nSome bold text with code and a cat.\n\none\ntwo"
+        );
+        // Frontmatter is left out with metadata blocks enabled
+        let post = format!("---\ntitle: Post\n---\n{}", markdown);
+        assert_eq!(
+            MarkdownRenderer::new(MarkdownOptions::new().with_metadata_blocks(true))
+                .render_to_plain_text(&post)
+                .unwrap(),
+            render_to_plain_text(markdown).unwrap()
+        );
+
+        assert_eq!(
+            excerpt(markdown, 200).unwrap(),
+            "Hello World Some bold text with code and a cat. one two"
+        );
+        let short = excerpt(markdown, 20).unwrap();
+        assert_eq!(short, "Hello World Some…");
+        assert!(short.chars().count() <= 20);
+        assert_eq!(excerpt("Supercalifragilistic", 6).unwrap(), "Super…");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_markdown_reactive_content() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::Markdown;
+
+        // Hydration markers split the text from its closing tag
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! { <Markdown content="# Static" /> }
+                .to_html()
+                .replace("<!>", "");
+            assert!(html.contains("Static</h1>"));
+
+            let content = RwSignal::new("# First".to_string());
+            let html = view! { <Markdown content=content /> }
+                .to_html()
+                .replace("<!>", "");
+            assert!(html.contains("First</h1>"));
+
+            content.set("# Second".to_string());
+            let html = view! { <Markdown content=content /> }
+                .to_html()
+                .replace("<!>", "");
+            assert!(html.contains("Second</h1>"));
+            assert!(!html.contains("First"));
+        });
+    }
+
+    #[test]
+    fn test_sanitize_handler_reports_offsets() {
+        use leptos_md::{MarkdownRenderer, RenderWarning, SanitizeEvent};
+        use std::sync::{Arc, Mutex};
+
+        let events: Arc<Mutex<Vec<SanitizeEvent>>> = Arc::default();
+        let sink = Arc::clone(&events);
+        let options = MarkdownOptions::new()
+            .with_allow_raw_html(false)
+            .with_sanitize_handler(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let markdown = "Intro <!-- secret --> text\n\n<script>alert(1)</script>\n";
+        let (_, warnings) = MarkdownRenderer::new(options)
+            .render_with_warnings(markdown)
+            .unwrap();
+        let events = events.lock().unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].warning,
+            RenderWarning::HtmlCommentRemoved {
+                comment: "secret".to_string()
+            }
+        );
+        assert_eq!(events[0].offset, markdown.find("<!--"));
+        assert!(matches!(
+            events[1].warning,
+            RenderWarning::RawHtmlEscaped { .. }
+        ));
+        assert_eq!(events[1].offset, markdown.find("<script>"));
+        assert_eq!(warnings.len(), events.len());
+    }
+
+    #[test]
+    fn test_renderer_shared_across_threads() {
+        use leptos_md::{MarkdownComponents, MarkdownRenderer, ParsedMarkdown};
+        use std::sync::Arc;
+        use std::thread;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MarkdownRenderer>();
+        assert_send_sync::<MarkdownOptions>();
+        assert_send_sync::<MarkdownComponents>();
+        assert_send_sync::<CodeBlockTheme>();
+        assert_send_sync::<ParsedMarkdown>();
+
+        let renderer = Arc::new(MarkdownRenderer::new(
+            MarkdownOptions::new().with_heading_ids(true),
+        ));
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let renderer = Arc::clone(&renderer);
+                thread::spawn(move || {
+                    let content = format!("# Request {}\n\nBody", i);
+                    assert!(renderer.render(&content).is_ok());
+                    renderer.extract_headings(&content).unwrap()[0].id.clone()
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), Some(format!("request-{}", i)));
+        }
+    }
+
+    #[test]
+    fn test_streaming_renderer_commits_finished_blocks() {
+        use leptos_md::StreamingMarkdownRenderer;
+
+        let mut stream = StreamingMarkdownRenderer::new(MarkdownOptions::default());
+        stream.push_str("# Ti");
+        stream.push_str("tle\n\nSome **bo");
+        assert_eq!(stream.completed_len(), 0);
+        assert_eq!(stream.display_tail(), "# Title\n\nSome **bo**");
+
+        stream.push_str("ld** text\n\n```rust\nfn main");
+        stream.push_str("() {}\n");
+        let blocks: Vec<&str> = stream.completed_blocks().collect();
+        assert_eq!(blocks, ["# Title\n\n", "Some **bold** text\n\n"]);
+        assert_eq!(stream.tail(), "```rust\nfn main() {}\n");
+        assert_eq!(stream.display_tail(), "```rust\nfn main() {}\n```");
+
+        stream.push_str("```\n\nDangling *");
+        assert_eq!(stream.completed_len(), 2);
+        assert_eq!(
+            stream.display_tail(),
+            "```rust\nfn main() {}\n```\n\nDangling"
+        );
+
+        stream.set_source("2 * 3 = 6 and `code");
+        assert_eq!(stream.completed_len(), 0);
+        assert_eq!(stream.display_tail(), "2 * 3 = 6 and `code`");
+        stream.set_source("2 * 3 = 6 and `code` ***done***");
+        assert_eq!(stream.display_tail(), stream.source());
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_streaming_renderer_shares_heading_ids_and_reports_errors() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::StreamingMarkdownRenderer;
+
+        let mut stream =
+            StreamingMarkdownRenderer::new(MarkdownOptions::new().with_heading_ids(true));
+        stream.push_str("## Setup\n\ntext\n\n## Setup\n\nmore\n\n## Setup");
+        assert_eq!(stream.completed_len(), 3);
+        let first = stream.render_block(0).to_html();
+        assert!(first.contains(r#"id="setup""#), "{first}");
+        // Rendering a later block first still numbers the ids in document order
+        let mut other =
+            StreamingMarkdownRenderer::new(MarkdownOptions::new().with_heading_ids(true));
+        other.set_source(stream.source());
+        let second = other.render_block(2).to_html();
+        assert!(second.contains(r#"id="setup-1""#), "{second}");
+        let tail = stream.render_tail().to_html();
+        assert!(tail.contains(r#"id="setup-2""#), "{tail}");
+
+        let mut deep =
+            StreamingMarkdownRenderer::new(MarkdownOptions::new().with_max_nesting_depth(4));
+        deep.push_str(&format!("{} deep\n\nafter\n\n", ">".repeat(10)));
+        let html = deep.render_block(0).to_html();
+        assert!(html.contains("Failed to render markdown content"), "{html}");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_canonical_html_snapshot() {
+        use leptos_md::{render_markdown_snapshot, MarkdownRenderer};
+
+        let markdown = "# Title\n\nSee [docs](https://example.com \"Docs\").\n\n- [x] done";
+        let options = MarkdownOptions::new()
+            .with_heading_ids(true)
+            .with_new_tab_links(true);
+        let html = render_markdown_snapshot(markdown, options.clone()).unwrap();
+
+        assert_eq!(
+            html,
+            concat!(
+                r#"<h1 id="title">Title</h1>"#,
+                r#"<p>See <a href="https://example.com" rel="noopener noreferrer" target="_blank" title="Docs">docs</a>.</p>"#,
+                r#"<ul><li><input checked disabled type="checkbox">done</li></ul>"#,
+            )
+        );
+        let link = &html[html.find("<a ").unwrap()..];
+        let link = &link[..link.find('>').unwrap()];
+        let names: Vec<&str> = link[3..]
+            .split('"')
+            .step_by(2)
+            .map(|part| part.trim().trim_end_matches('='))
+            .filter(|name| !name.is_empty())
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+
+        let again = MarkdownRenderer::new(options)
+            .render_to_canonical_html(markdown)
+            .unwrap();
+        assert_eq!(html, again);
+    }
+
+    #[test]
+    fn test_raw_html_policy() {
+        use leptos_md::{MarkdownRenderer, RawHtmlPolicy, RenderWarning};
+
+        let options = MarkdownOptions::new().with_allow_raw_html(false);
+        assert_eq!(options.raw_html_policy(), RawHtmlPolicy::Escape);
+        let options = options.with_raw_html_policy(RawHtmlPolicy::Deny);
+        assert_eq!(options.raw_html_policy(), RawHtmlPolicy::Deny);
+        assert_eq!(
+            options.clone().with_allow_raw_html(true).raw_html_policy(),
+            RawHtmlPolicy::Allow
+        );
+
+        let (_, warnings) = MarkdownRenderer::new(options)
+            .render_with_warnings("Press <kbd>Ctrl</kbd>\n\n<script>alert(1)</script>\n")
+            .unwrap();
+        assert_eq!(
+            warnings,
+            [
+                RenderWarning::RawHtmlRemoved {
+                    html: "<kbd>".to_string()
+                },
+                RenderWarning::RawHtmlRemoved {
+                    html: "</kbd>".to_string()
+                },
+                RenderWarning::RawHtmlRemoved {
+                    html: "<script>alert(1)</script>\n".to_string()
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_url_policy() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{MarkdownRenderer, RawHtmlPolicy, RenderWarning, SanitizeEvent, UrlPolicy};
+        use std::sync::{Arc, Mutex};
+
+        let safe = UrlPolicy::SafeSchemes;
+        assert!(safe.allows("https://example.com"));
+        assert!(safe.allows("MAILTO:me@example.com"));
+        assert!(safe.allows("/docs/intro.md#setup"));
+        assert!(safe.allows("../img.png"));
+        assert!(!safe.allows("javascript:alert(1)"));
+        assert!(!safe.allows(" Java\tScript:alert(1)"));
+        assert!(!safe.allows("data:text/html;base64,PHNjcmlwdD4="));
+        assert!(UrlPolicy::Allow.allows("javascript:alert(1)"));
+
+        assert_eq!(MarkdownOptions::new().url_policy(), UrlPolicy::Allow);
+        let options = MarkdownOptions::new().with_raw_html_policy(RawHtmlPolicy::Deny);
+        assert_eq!(options.url_policy(), UrlPolicy::SafeSchemes);
+        assert_eq!(
+            options
+                .clone()
+                .with_url_policy(UrlPolicy::Allow)
+                .url_policy(),
+            UrlPolicy::Allow
+        );
+
+        let events: Arc<Mutex<Vec<SanitizeEvent>>> = Arc::default();
+        let sink = Arc::clone(&events);
+        let options =
+            options.with_sanitize_handler(move |event| sink.lock().unwrap().push(event.clone()));
+        let markdown = "[ok](https://example.com) [click me](javascript:alert(1))\n\n\
+                        ![pixel](data:image/png;base64,AAAA)";
+        let (view, warnings) = MarkdownRenderer::new(options)
+            .render_with_warnings(markdown)
+            .unwrap();
+        let html = view.to_html();
+        assert!(html.contains("href=\"https://example.com\""), "{html}");
+        assert!(html.contains("click me"), "{html}");
+        assert!(!html.contains("javascript:"), "{html}");
+        assert!(html.contains("pixel"), "{html}");
+        assert!(!html.contains("<img"), "{html}");
+        assert_eq!(
+            warnings,
+            [
+                RenderWarning::BlockedUrl {
+                    url: "javascript:alert(1)".to_string()
+                },
+                RenderWarning::BlockedUrl {
+                    url: "data:image/png;base64,AAAA".to_string()
+                },
+            ]
+        );
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].offset, markdown.find("javascript:"));
+        assert_eq!(events[1].offset, markdown.find("data:"));
+
+        // Wikilink targets are page names, resolved to hrefs by the app
+        let options = MarkdownOptions::new()
+            .with_raw_html_policy(RawHtmlPolicy::Deny)
+            .with_wikilink_resolver(|target| Some(format!("/wiki/{}", target.len())));
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("[[Project: Plan]]")
+            .unwrap();
+        assert!(html.contains("<a href=\"/wiki/13\""), "{html}");
+    }
+
+    #[cfg(all(feature = "ammonia", feature = "ssr"))]
+    #[test]
+    fn test_raw_html_sanitized() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{MarkdownRenderer, RawHtmlPolicy, RenderWarning};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new().with_raw_html_policy(RawHtmlPolicy::Sanitize),
+        );
+        let (view, warnings) = renderer
+            .render_with_warnings(
+                "Press <kbd>Ctrl</kbd> and x<sup>2</sup>\n\n<div onclick=\"steal()\">Hi<script>alert(1)</script></div>\n",
+            )
+            .unwrap();
+        let html = view.to_html();
+        assert!(html.contains("<kbd>Ctrl</kbd>"));
+        assert!(html.contains("<sup>2</sup>"));
+        assert!(html.contains("Hi"));
+        assert!(!html.contains("onclick"));
+        assert!(!html.contains("<script"));
+        assert!(matches!(
+            warnings.as_slice(),
+            [RenderWarning::RawHtmlSanitized { .. }]
+        ));
+    }
+
+    // `<A>` needs a router context, so check the plain-link rendering
+    #[cfg(all(feature = "ssr", not(feature = "router")))]
+    #[test]
+    fn test_route_directives() {
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{MarkdownRenderer, RenderWarning, RouteMap};
+
+        let routes = RouteMap::new().with_route("user_profile", "/users/:id");
+        assert_eq!(
+            routes.href("user_profile", &[("id", "42"), ("tab", "posts")]),
+            Some("/users/42?tab=posts".to_string())
+        );
+        assert_eq!(routes.href("user_profile", &[]), None);
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_routes(routes));
+        let (view, warnings) = renderer
+            .render_with_warnings(
+                "Ask [:route:user_profile id=42 label=\"Jane Doe\"] or [:route:team id=1].",
+            )
+            .unwrap();
+        let html = view.to_html();
+        assert!(html.contains(r#"href="/users/42""#));
+        assert!(html.contains(">Jane Doe</a>"));
+        assert!(html.contains("[:route:team id=1]"));
+        assert_eq!(
+            warnings,
+            [RenderWarning::UnknownRoute {
+                name: "team".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_code_theme_pair() {
+        #[cfg(feature = "ssr")]
+        use leptos_md::MarkdownRenderer;
+        use leptos_md::{
+            get_code_theme_classes, get_code_title_theme_classes, get_dark_code_theme_classes,
+            get_dark_code_title_theme_classes,
+        };
+
+        let options = MarkdownOptions::new()
+            .with_code_theme_pair(CodeBlockTheme::GitHub, CodeBlockTheme::Monokai);
+        assert_eq!(options.dark_code_theme, Some(CodeBlockTheme::Monokai));
+
+        // Dark halves are literal strings Tailwind can find, matching each theme's colors
+        assert_eq!(
+            get_dark_code_theme_classes(&CodeBlockTheme::Monokai),
+            "dark:bg-[#272822] dark:text-[#f8f8f2]"
+        );
+        assert_eq!(
+            get_dark_code_title_theme_classes(&CodeBlockTheme::Monokai),
+            "dark:bg-[#1e1f1c] dark:text-[#a59f85]"
+        );
+        for theme in [
+            CodeBlockTheme::Default,
+            CodeBlockTheme::Dark,
+            CodeBlockTheme::Light,
+            CodeBlockTheme::GitHub,
+            CodeBlockTheme::Monokai,
+        ] {
+            for (classes, dark) in [
+                (
+                    get_code_theme_classes(&theme),
+                    get_dark_code_theme_classes(&theme),
+                ),
+                (
+                    get_code_title_theme_classes(&theme),
+                    get_dark_code_title_theme_classes(&theme),
+                ),
+            ] {
+                let expected: Vec<String> = if classes.contains("dark:") {
+                    classes
+                        .split_whitespace()
+                        .filter(|class| class.starts_with("dark:"))
+                        .map(str::to_string)
+                        .collect()
+                } else {
+                    classes
+                        .split_whitespace()
+                        .map(|class| format!("dark:{}", class))
+                        .collect()
+                };
+                assert_eq!(dark, expected.join(" "), "{:?}", theme);
+            }
+        }
+
+        #[cfg(feature = "ssr")]
+        {
+            let html = MarkdownRenderer::new(options.clone())
+                .render_to_html("```\nplain\n```")
+                .unwrap();
+            assert!(
+                html.contains("bg-[#f6f8fa] text-[#24292f] dark:bg-[#272822] dark:text-[#f8f8f2]")
+            );
+            assert!(!html.contains("dark:bg-[#0d1117]"));
+
+            let html = MarkdownRenderer::new(options.clone())
+                .render_to_html("```rust title=\"main.rs\"\nfn main() {}\n```")
+                .unwrap();
+            assert!(
+                html.contains("bg-[#eaeef2] text-[#57606a] dark:bg-[#1e1f1c] dark:text-[#a59f85]")
+            );
+        }
+
+        #[cfg(feature = "syntect")]
+        {
+            let css = options.syntect_css();
+            assert!(css.contains(".dark .hl-"));
+            assert!(css.starts_with(&CodeBlockTheme::GitHub.syntect_css()));
+        }
+
+        assert_eq!(
+            MarkdownOptions::new().without_code_theme().dark_code_theme,
+            None
+        );
+    }
+
+    #[test]
+    fn test_link_target_policy() {
+        use leptos_md::LinkTargetPolicy;
+
+        let policy = LinkTargetPolicy::ExternalOnly {
+            site_origin: "https://example.com/".to_string(),
+        };
+        assert!(!policy.opens_in_new_tab("/docs/intro"));
+        assert!(!policy.opens_in_new_tab("#section"));
+        assert!(!policy.opens_in_new_tab("https://Example.com/blog"));
+        assert!(!policy.opens_in_new_tab("mailto:team@example.com"));
+        assert!(policy.opens_in_new_tab("https://rust-lang.org"));
+        assert!(policy.opens_in_new_tab("//cdn.other.net/file"));
+
+        let options = MarkdownOptions::new().with_link_target_policy(policy);
+        assert!(options.link_opens_in_new_tab("https://rust-lang.org"));
+        assert!(!options.link_opens_in_new_tab("/docs"));
+        let options = options.with_new_tab_links(false);
+        assert_eq!(options.link_target, None);
+        assert!(!options.link_opens_in_new_tab("https://rust-lang.org"));
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos_md::MarkdownRenderer;
+            let html = MarkdownRenderer::new(MarkdownOptions::new().with_link_target_policy(
+                LinkTargetPolicy::ExternalOnly {
+                    site_origin: "https://example.com".to_string(),
+                },
+            ))
+            .render_to_html("[docs](/docs) and [rust](https://rust-lang.org)")
+            .unwrap();
+            assert!(html.contains(r#"href="/docs">"#));
+            assert!(!html.contains(r#"class="""#), "{html}");
+            assert_eq!(html.matches(r#"target="_blank""#).count(), 1);
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_link_rel_option() {
+        use leptos_md::MarkdownRenderer;
+
+        let markdown = "[a](https://example.com \"Title\")";
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains(r#"title="Title" target="_blank" rel="noopener noreferrer""#));
+
+        let html = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_new_tab_links(false)
+                .with_link_rel("nofollow ugc"),
+        )
+        .render_to_html(markdown)
+        .unwrap();
+        assert!(html.contains(r#"rel="nofollow ugc""#));
+        assert!(!html.contains("target="));
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_link_rel(""))
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains(r#"target="_blank""#));
+        assert!(!html.contains("rel="));
+    }
+
+    #[test]
+    fn test_render_with_metadata() {
+        use leptos_md::MarkdownRenderer;
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::default());
+        let first = renderer.render_with_metadata("# Hello").unwrap();
+        let again = renderer.render_with_metadata("# Hello").unwrap();
+        let other = renderer.render_with_metadata("# Bye").unwrap();
+
+        assert_eq!(first.content_hash, again.content_hash);
+        assert_ne!(first.content_hash, other.content_hash);
+        assert_eq!(first.cache_key, renderer.cache_key("# Hello"));
+        assert!(first
+            .cache_key
+            .starts_with(&format!("{:016x}", first.content_hash)));
+        assert_eq!(first.etag(), format!("\"{}\"", first.cache_key));
+        assert!(first.warnings.is_empty());
+
+        let compact = MarkdownRenderer::new(MarkdownOptions::new().with_compact_headings(true))
+            .render_with_metadata("# Hello")
+            .unwrap();
+        assert_eq!(compact.content_hash, first.content_hash);
+        assert_ne!(compact.cache_key, first.cache_key);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_link_rewriter() {
+        use leptos_md::MarkdownRenderer;
+
+        let options = MarkdownOptions::new()
+            .with_anchor_base("/post")
+            .with_link_rewriter(|href| match href.strip_prefix("wiki:") {
+                Some(slug) => format!("/wiki/{}", slug),
+                None if href.starts_with("https://") => format!("{}?utm_source=blog", href),
+                None => href.to_string(),
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("[a](wiki:Rust) [b](https://example.com) [c](#intro)")
+            .unwrap();
+        assert!(html.contains(r#"href="/wiki/Rust""#));
+        assert!(html.contains(r#"href="https://example.com?utm_source=blog""#));
+        assert!(html.contains(r#"href="/post#intro""#));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_media_layout() {
+        use leptos_md::{MarkdownRenderer, MediaLayout};
+
+        let markdown = "![cat](cat.png)\n\n| a |\n|---|\n| 1 |";
+        let options = MarkdownOptions::new()
+            .with_image_layout(
+                MediaLayout::new()
+                    .with_max_width("max-w-2xl")
+                    .with_centered(true),
+            )
+            .with_table_layout(MediaLayout::new().with_centered(true));
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains(r#"class="markdown-image max-w-2xl block mx-auto""#));
+        assert!(html.contains(r#"class="markdown-table mx-auto""#));
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(!html.contains("mx-auto"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_unsupported_content_placeholders() {
+        use leptos_md::{MarkdownRenderer, RawHtmlPolicy, UnsupportedContent};
+
+        let markdown = "Energy $E = mc^2$ and [:route:missing].\n\n<iframe src=\"x\"></iframe>";
+        let options = MarkdownOptions::new()
+            .with_raw_html_policy(RawHtmlPolicy::Deny)
+            .with_route("home", "/");
+
+        let html = MarkdownRenderer::new(options.clone())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains("$E = mc^2$"));
+        assert!(html.contains("[:route:missing]"));
+        assert!(!html.contains("data-unsupported"));
+
+        let html = MarkdownRenderer::new(
+            options
+                .clone()
+                .with_unsupported_content(UnsupportedContent::Placeholder),
+        )
+        .render_to_html(markdown)
+        .unwrap();
+        assert!(html.contains(r#"data-unsupported="math""#));
+        assert!(html.contains(r#"title="$E = mc^2$""#));
+        assert!(html.contains(r#"data-unsupported="directive""#));
+        assert!(html.contains(r#"data-unsupported="embed""#));
+        assert!(!html.contains("<iframe"));
+
+        let html =
+            MarkdownRenderer::new(options.with_unsupported_content(UnsupportedContent::Hide))
+                .render_to_html(markdown)
+                .unwrap();
+        assert!(!html.contains("mc^2"));
+        assert!(!html.contains("[:route:missing]"));
+        assert!(!html.contains("data-unsupported"));
+    }
+
+    #[test]
+    fn test_author_mode_annotations() {
+        use leptos_md::{Diagnostic, MarkdownRenderer};
+
+        let markdown = "# Intro\n\n![](diagram.png)\nSee [setup](#setup).\n\n- one\n- two\n";
+        let annotations = MarkdownRenderer::new(MarkdownOptions::new())
+            .annotate(markdown)
+            .unwrap();
+
+        assert_eq!(annotations.len(), 3);
+        assert_eq!(annotations[0].lines, Some(1..=1));
+        assert!(annotations[0].diagnostics.is_empty());
+        assert_eq!(annotations[1].lines, Some(3..=4));
+        assert_eq!(
+            annotations[1].diagnostics,
+            vec![
+                Diagnostic::MissingAlt {
+                    src: "diagram.png".to_string()
+                },
+                Diagnostic::BrokenLink {
+                    href: "#setup".to_string()
+                },
+            ]
+        );
+        assert_eq!(annotations[2].lines, Some(6..=7));
+
+        #[cfg(feature = "ssr")]
+        {
+            use leptos::tachys::view::RenderHtml;
+
+            let html = MarkdownRenderer::new(MarkdownOptions::new())
+                .render_annotated(markdown)
+                .unwrap()
+                .to_html();
+            assert!(html.contains(r#"data-source-lines="3-4""#));
+            assert!(html.contains(r#"data-diagnostic="missing-alt""#));
+            assert!(html.contains(r#"data-diagnostic="broken-link""#));
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_image_src_transform() {
+        use leptos_md::{ImageSource, MarkdownRenderer};
+
+        let options = MarkdownOptions::new().with_image_src_transform(|src| {
+            if src.starts_with("https://") {
+                return ImageSource::new(src);
+            }
+            ImageSource::new(format!("https://cdn.test/w_960/{}", src))
+                .with_srcset(format!("https://cdn.test/w_480/{} 480w", src))
+                .with_sizes("100vw")
+        });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("![a](cat.png) ![b](https://example.com/dog.png)")
+            .unwrap();
+
+        assert!(html.contains(r#"src="https://cdn.test/w_960/cat.png""#));
+        assert!(html.contains(r#"srcset="https://cdn.test/w_480/cat.png 480w""#));
+        assert!(html.contains(r#"sizes="100vw""#));
+        assert!(html.contains(r#"src="https://example.com/dog.png""#));
+        assert_eq!(html.matches("srcset=").count(), 1);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_lazy_images_and_dimensions() {
+        use leptos_md::MarkdownRenderer;
+
+        let markdown = "![a](known.png) ![b](unknown.png)";
+        let options = MarkdownOptions::new()
+            .with_lazy_images(true)
+            .with_image_dimensions(|src| (src == "known.png").then_some((640, 480)));
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+
+        assert_eq!(html.matches(r#"loading="lazy""#).count(), 2);
+        assert_eq!(html.matches(r#"decoding="async""#).count(), 2);
+        assert!(html.contains(r#"width="640""#));
+        assert!(html.contains(r#"height="480""#));
+        assert_eq!(html.matches("width=").count(), 1);
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(!html.contains("loading="));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_code_copy_button() {
+        use leptos_md::{MarkdownClasses, MarkdownRenderer};
+
+        let markdown = "```rust\nfn main() {}\n```";
+        let options = MarkdownOptions::new().with_code_copy_button(true);
+        let html = MarkdownRenderer::new(options.clone())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains(MarkdownClasses::CODE_BLOCK_WRAPPER));
+        assert!(html.contains(r#"aria-label="Copy code""#));
+        assert!(html.contains(MarkdownClasses::CODE_COPY_ALONE));
+
+        let html = MarkdownRenderer::new(options.with_code_expand_action(true))
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(html.contains(MarkdownClasses::CODE_COPY_BESIDE_EXPAND));
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(!html.contains("Copy code"));
+
+        let options = MarkdownOptions::new()
+            .with_code_copy_button(true)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                code_copy_button: "btn btn-xs absolute top-2".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(
+            html.contains(r#"class="md-code-block-wrapper relative group""#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"class="md-code-copy btn btn-xs absolute top-2 right-2""#),
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_fence_line_highlighting() {
+        use leptos_md::{MarkdownClasses, MarkdownRenderer};
+
+        let markdown = "```text {1,3-4}\none\ntwo\nthree\nfour\nfive\n```";
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_syntect_highlighting(false))
+            .render_to_html(markdown)
+            .unwrap();
+        let highlight = format!(r#"<span class="{}">"#, MarkdownClasses::CODE_LINE_HIGHLIGHT);
+
+        assert_eq!(html.matches(&highlight).count(), 3);
+        assert!(html.contains(&format!("{}one</span>\ntwo\n", highlight)));
+        assert!(html.contains(&format!("{}four</span>\nfive\n", highlight)));
+        assert!(html.contains("language-text"));
+        assert!(!html.contains("{1,3-4}"));
+
+        let options = MarkdownOptions::new()
+            .with_syntect_highlighting(false)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                code_line_highlight: "bg-sky-100".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+        assert_eq!(
+            html.matches(r#"<span class="md-code-line-highlight bg-sky-100">"#)
+                .count(),
+            3,
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_code_block_title() {
+        use leptos_md::{get_code_title_theme_classes, CodeBlockTheme, MarkdownRenderer};
+
+        let renderer =
+            MarkdownRenderer::new(MarkdownOptions::new().with_code_theme(CodeBlockTheme::Monokai));
+        let html = renderer
+            .render_to_html("```rust title=\"src/main.rs\"\nfn main() {}\n```")
+            .unwrap();
+        assert!(html.contains(">src/main.rs</div>"));
+        assert!(html.contains(get_code_title_theme_classes(&CodeBlockTheme::Monokai)));
+        assert!(html.contains("language-rust"));
+
+        let html = renderer
+            .render_to_html("```toml file=Cargo.toml\n[package]\n```")
+            .unwrap();
+        assert!(html.contains(">Cargo.toml</div>"));
+
+        let html = renderer
+            .render_to_html("```rust\nfn main() {}\n```")
+            .unwrap();
+        assert!(!html.contains("code-title"));
+
+        let options = MarkdownOptions::new()
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                code_titled: "my-8".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("```rust title=\"src/main.rs\"\nfn main() {}\n```")
+            .unwrap();
+        assert!(
+            html.contains(r#"<div class="md-code-titled my-8">"#),
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_diff_highlighting() {
+        use leptos_md::{MarkdownClasses, MarkdownRenderer};
+
+        let markdown = "```diff\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n+new\n same\n```";
+        let options = MarkdownOptions::new()
+            .with_syntect_highlighting(false)
+            .with_diff_highlighting(true);
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+
+        let added = format!(
+            r#"<span class="{}">+new</span>"#,
+            MarkdownClasses::CODE_DIFF_ADDED
+        );
+        let removed = format!(
+            r#"<span class="{}">-old</span>"#,
+            MarkdownClasses::CODE_DIFF_REMOVED
+        );
+        assert!(html.contains(&added));
+        assert!(html.contains(&removed));
+        assert!(html.contains(MarkdownClasses::CODE_DIFF_HUNK));
+        assert_eq!(html.matches(MarkdownClasses::CODE_DIFF_ADDED).count(), 1);
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(!html.contains(MarkdownClasses::CODE_DIFF_ADDED));
+
+        let options = MarkdownOptions::new()
+            .with_syntect_highlighting(false)
+            .with_diff_highlighting(true)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                code_diff_added: "bg-emerald-50".into(),
+                code_diff_removed: "bg-rose-50".into(),
+                code_diff_hunk: "text-violet-700".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(markdown)
+            .unwrap();
+        assert!(
+            html.contains(r#"<span class="md-code-diff-added bg-emerald-50">+new</span>"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<span class="md-code-diff-removed bg-rose-50">-old</span>"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<span class="md-code-diff-hunk text-violet-700">@@"#),
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_code_max_lines() {
+        use leptos_md::{MarkdownClasses, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_code_max_lines(3));
+        let html = renderer.render_to_html("```\n1\n2\n3\n4\n5\n```").unwrap();
+        assert!(html.contains(MarkdownClasses::CODE_CLAMP));
+        assert!(html.contains("max-height: calc(3lh + 2rem)"));
+        assert!(html.contains(">Expand</span>"));
+
+        let html = renderer.render_to_html("```\n1\n2\n3\n```").unwrap();
+        assert!(!html.contains(MarkdownClasses::CODE_CLAMP));
+
+        let options = MarkdownOptions::new()
+            .with_code_max_lines(3)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                code_clamp_fade: "hidden".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("```\n1\n2\n3\n4\n5\n```")
+            .unwrap();
+        assert!(
+            html.contains(r#"class="md-code-clamp relative group/clamp""#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"class="md-code-clamp-fade hidden""#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"class="md-code-clamp-button block"#),
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_code_wrap_and_tab_size() {
+        use leptos_md::{MarkdownClasses, MarkdownRenderer};
+
+        let md = "```\n\tindented\n```";
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(md)
+            .unwrap();
+        assert!(html.contains(MarkdownClasses::CODE_SCROLL));
+        assert!(html.contains("tab-size: 4"));
+
+        let options = MarkdownOptions::new()
+            .with_code_wrap(true)
+            .with_code_tab_size(2);
+        let html = MarkdownRenderer::new(options).render_to_html(md).unwrap();
+        assert!(html.contains(MarkdownClasses::CODE_WRAP));
+        assert!(html.contains("tab-size: 2"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_standalone_code_block() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{CodeBlock, CodeBlockTheme, MarkdownClasses};
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let options = MarkdownOptions::new()
+                .with_explicit_classes(true)
+                .with_syntect_highlighting(false);
+            let html = view! {
+                <CodeBlock code="let x = 1;" lang="rust" theme=CodeBlockTheme::Monokai options=options />
+            }
+            .to_html();
+            assert!(html.contains("language-rust"));
+            // With class-merge the theme background replaces the default one
+            assert!(MarkdownClasses::CODE_BLOCK
+                .split_whitespace()
+                .filter(|class| !class.starts_with("bg-"))
+                .all(|class| html.contains(class)));
+            assert!(html.contains("let x = 1;"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_class_map_overrides() {
+        use leptos_md::{MarkdownClassMap, MarkdownClasses, MarkdownRenderer};
+
+        let classes = MarkdownClassMap {
+            blockquote: "border-l-2 border-amber-500".into(),
+            ..Default::default()
+        };
+
+        let options = MarkdownOptions::new().with_class_map(classes.clone());
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("> quote\n\ntext")
+            .unwrap();
+        assert!(html.contains(r#"class="markdown-blockquote border-l-2 border-amber-500""#));
+        assert!(html.contains("<p>text</p>"));
+
+        let options = MarkdownOptions::new()
+            .with_explicit_classes(true)
+            .with_class_map(classes);
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("> quote\n\ntext")
+            .unwrap();
+        assert!(html.contains(r#"class="md-blockquote border-l-2 border-amber-500""#));
+        assert!(html.contains(MarkdownClasses::PARAGRAPH));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_theme_presets() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{Markdown, MarkdownTheme};
+
+        let options = MarkdownOptions::new().with_theme(MarkdownTheme::Daisy);
+        assert!(options.use_explicit_classes);
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! { <Markdown content="[home](/)" options=options /> }.to_html();
+            assert!(html.contains("leptos-mdx-content text-base-content"));
+            assert!(!html.contains("prose"));
+            assert!(html.contains("link link-primary"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_markdown_wrapper_element() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{Markdown, MarkdownWrapper};
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! {
+                <Markdown content="Hallo" wrapper=MarkdownWrapper::Article id="post" lang="de" />
+            }
+            .to_html();
+            assert!(html.contains("<article "));
+            assert!(html.contains(r#"id="post""#));
+            assert!(html.contains(r#"lang="de""#));
+            assert!(!html.contains("dir="));
+
+            let html =
+                view! { <Markdown content="Hallo" wrapper=MarkdownWrapper::Fragment /> }.to_html();
+            assert!(!html.contains("<div"));
+            assert!(!html.contains("prose"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_without_prose() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::Markdown;
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let options = MarkdownOptions::new().without_prose();
+            let html =
+                view! { <Markdown content="text" options=options class="docs".to_string() /> }
+                    .to_html();
+            assert!(html.contains(r#"class="leptos-mdx-content docs""#));
+
+            let options = MarkdownOptions::new().with_base_class("my-markdown");
+            let html = view! { <Markdown content="text" options=options /> }.to_html();
+            assert!(html.contains(r#"class="my-markdown""#));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_markdown_styles_css() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{get_markdown_css, MarkdownStyles};
+
+        assert!(get_markdown_css().contains(":where(.leptos-mdx-content)"));
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! { <MarkdownStyles /> }.to_html();
+            assert!(html.starts_with("<style>"));
+            assert!(html.contains(":where(.leptos-mdx-content) :where(pre code)"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_css_variable_colors() {
+        use leptos_md::{get_enhanced_prose_classes, get_markdown_css, MarkdownRenderer};
+
+        let options = MarkdownOptions::new().with_explicit_classes(true);
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("[link](/) and `code`")
+            .unwrap();
+        assert!(html.contains("text-[color:var(--md-link-color,#2563eb)]"));
+        assert!(html.contains("bg-[color:var(--md-code-bg,#f3f4f6)]"));
+
+        assert!(get_enhanced_prose_classes().contains("var(--md-link-color"));
+        assert!(get_markdown_css().contains("var(--md-link-color"));
+    }
+
+    #[cfg(feature = "class-merge")]
+    #[test]
+    fn test_merge_classes() {
+        use leptos_md::merge_classes;
+
+        assert_eq!(
+            merge_classes("px-2 text-red-500 text-sm p-4 text-blue-600"),
+            "text-sm p-4 text-blue-600"
+        );
+        assert_eq!(
+            merge_classes(
+                "text-gray-700 dark:text-gray-300 hover:dark:text-white dark:text-gray-100"
+            ),
+            "text-gray-700 hover:dark:text-white dark:text-gray-100"
+        );
+        assert_eq!(
+            merge_classes("border border-gray-200 border-l-4 md-code block block"),
+            "border border-gray-200 border-l-4 md-code block"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_markdown_error_view_prop() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{Markdown, MarkdownError};
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! {
+                <Markdown
+                    content="# Fine"
+                    error_view=|err: MarkdownError| view! { <p class="oops">{err.to_string()}</p> }.into_any()
+                />
+            }
+            .to_html()
+            .replace("<!>", "");
+            assert!(html.contains("Fine</h1>"));
+            assert!(!html.contains("oops"));
+        });
+    }
+
+    #[cfg(all(feature = "serde", feature = "frontmatter"))]
+    #[test]
+    fn test_options_serde_round_trip() {
+        use leptos_md::{CodeBlockTheme, MarkdownClassMap, MarkdownOptions};
+
+        let options = MarkdownOptions::new()
+            .with_code_theme(CodeBlockTheme::Monokai)
+            .with_class_map(MarkdownClassMap {
+                blockquote: "border-l-4 border-amber-500".into(),
+                ..Default::default()
+            });
+        let yaml = serde_yaml::to_string(&options).unwrap();
+        let restored: MarkdownOptions = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(restored.code_theme, options.code_theme);
+        assert_eq!(restored.class_map, options.class_map);
+
+        let partial: MarkdownOptions = serde_yaml::from_str("code_wrap: true").unwrap();
+        assert!(partial.code_wrap);
+        assert_eq!(partial.code_tab_size, 4);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_context_default_options() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{
+            provide_markdown_options, use_markdown_options, Markdown, MarkdownOptions,
+        };
+
+        let owner = Owner::new();
+        owner.with(|| {
+            assert!(use_markdown_options().is_none());
+            provide_markdown_options(MarkdownOptions::new().with_base_class("site-md"));
+
+            let html = view! { <Markdown content="# Hi" /> }.to_html();
+            assert!(html.contains("site-md"));
+
+            let explicit = MarkdownOptions::new().with_base_class("one-off");
+            let html = view! { <Markdown content="# Hi" options=explicit /> }.to_html();
+            assert!(html.contains("one-off"));
+            assert!(!html.contains("site-md"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_deeply_nested_and_large_documents() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new());
+
+        let nested: String = (0..40)
+            .map(|depth| format!("{}- level {}\n", "  ".repeat(depth), depth))
+            .collect();
+        let html = renderer.render_to_html(&nested).unwrap();
+        assert_eq!(html.matches("<ul").count(), 40);
+        assert!(html.contains("level 39"));
+
+        // `- - - item` nests on one line
+        let html = renderer
+            .render_to_html(&format!("{}item", "- ".repeat(40)))
+            .unwrap();
+        assert_eq!(html.matches("<ul").count(), 40);
+        assert_eq!(html.matches("</li>").count(), 40);
+        assert_eq!(html.matches("item").count(), 1);
+
+        let mut table = String::from("| a | b |\n|---|---|\n");
+        for row in 0..4000 {
+            table.push_str(&format!("| **{row}** | `{row}` |\n"));
+        }
+        let html = renderer.render_to_html(&table).unwrap();
+        assert_eq!(html.matches("<tr").count(), 4001);
+        assert!(html.contains("<strong>3999</strong>"));
+        assert!(html.contains("3999</code>"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_parse_once_render_many() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+        use std::sync::Arc;
+
+        let content = "# Guide\n\nSome **bold** text[^1].\n\n[^1]: A note.";
+        let renderer = Arc::new(MarkdownRenderer::new(MarkdownOptions::new()));
+        let parsed = renderer.parse(content).unwrap();
+
+        assert_eq!(
+            parsed.render_html().unwrap(),
+            renderer.render_to_html(content).unwrap()
+        );
+        assert_eq!(
+            parsed.plain_text(),
+            renderer.render_to_plain_text(content).unwrap()
+        );
+        assert_eq!(parsed.excerpt(12), renderer.excerpt(content, 12).unwrap());
+        assert_eq!(parsed.headings()[0].text, "Guide");
+        assert!(!parsed.events().is_empty());
+    }
+
+    #[test]
+    fn test_typed_ast_and_visitor() {
+        use leptos_md::{walk, MarkdownOptions, MarkdownRenderer, MdNode, MdVisitor};
+
+        struct Collector {
+            headings: Vec<(u8, String)>,
+            code_langs: Vec<Option<String>>,
+            tasks: Vec<Option<bool>>,
+        }
+
+        impl MdVisitor for Collector {
+            fn enter(&mut self, node: &MdNode) -> bool {
+                match node {
+                    MdNode::Heading { level, .. } => self.headings.push((*level, node.text())),
+                    MdNode::CodeBlock { lang, .. } => self.code_langs.push(lang.clone()),
+                    MdNode::ListItem { checked, .. } => self.tasks.push(*checked),
+                    _ => {}
+                }
+                true
+            }
+        }
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new());
+        let ast = renderer.parse_ast(
+            "# Intro *here*\n\n- [x] done\n- [ ] todo\n\n```rust\nfn main() {}\n```\n\n| a |\n|---|\n| 1 |",
+        ).unwrap();
+        let mut collector = Collector {
+            headings: Vec::new(),
+            code_langs: Vec::new(),
+            tasks: Vec::new(),
+        };
+        walk(&ast, &mut collector);
+
+        assert_eq!(collector.headings, vec![(1, "Intro here".to_string())]);
+        assert_eq!(collector.code_langs, vec![Some("rust".to_string())]);
+        assert_eq!(collector.tasks, vec![Some(true), Some(false)]);
+        let MdNode::Table(rows) = &ast[3] else {
+            panic!("expected a table, got {:?}", ast[3]);
+        };
+        assert!(matches!(rows[0], MdNode::TableRow { header: true, .. }));
+        assert_eq!(rows[1].text(), "1");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_input_and_nesting_limits() {
+        use leptos_md::{MarkdownError, MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_max_input_len(200)
+                .with_max_nesting_depth(8),
+        );
+
+        assert_eq!(
+            renderer.render_to_html(&"a".repeat(201)),
+            Err(MarkdownError::InputTooLong { len: 201, max: 200 })
+        );
+        assert_eq!(
+            renderer.render_to_html(&format!("{} deep", ">".repeat(20))),
+            Err(MarkdownError::NestingTooDeep { max: 8 })
+        );
+        assert!(renderer.render_to_html("> quoted *text*").is_ok());
+
+        // Every entry point that renders or keeps the parse is held to the same limits
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{MarkdownChunked, ParsedMarkdown};
+        use std::sync::Arc;
+
+        let long = "a".repeat(201);
+        let deep = format!("{} deep", ">".repeat(20));
+        let too_long = MarkdownError::InputTooLong { len: 201, max: 200 };
+        let too_deep = MarkdownError::NestingTooDeep { max: 8 };
+
+        assert!(matches!(
+            renderer.render(&long),
+            Err(MarkdownError::InputTooLong { len: 201, max: 200 })
+        ));
+        assert!(matches!(
+            renderer.render_with_warnings(&deep),
+            Err(MarkdownError::NestingTooDeep { max: 8 })
+        ));
+        assert!(matches!(
+            renderer.render_annotated(&deep),
+            Err(MarkdownError::NestingTooDeep { max: 8 })
+        ));
+        assert_eq!(
+            renderer.render_with_metadata(&long).err(),
+            Some(too_long.clone())
+        );
+        assert!(matches!(
+            leptos_md::render_markdown_with_options(&deep, renderer.options().clone()),
+            Err(MarkdownError::NestingTooDeep { max: 8 })
+        ));
+
+        let batch = renderer.render_batch(&[&long, &deep, "fine"]);
+        assert_eq!(batch[0].as_ref().err(), Some(&too_long));
+        assert_eq!(batch[1].as_ref().err(), Some(&too_deep));
+        assert!(batch[2].is_ok());
+
+        assert_eq!(
+            renderer.render_first_block(&long).err(),
+            Some(too_long.clone())
+        );
+        assert_eq!(
+            renderer.render_first_block(&deep).err(),
+            Some(too_deep.clone())
+        );
+
+        assert_eq!(
+            renderer.render_diff("a", &deep).err(),
+            Some(too_deep.clone())
+        );
+        assert_eq!(
+            renderer.render_diff(&long, "a").err(),
+            Some(too_long.clone())
+        );
+        assert_eq!(renderer.render_to_plain_text(&deep), Err(too_deep.clone()));
+        assert_eq!(renderer.excerpt(&long, 20), Err(too_long.clone()));
+        assert_eq!(renderer.parse_ast(&deep).err(), Some(too_deep.clone()));
+        assert_eq!(
+            renderer.extract_headings(&deep).err(),
+            Some(too_deep.clone())
+        );
+        assert_eq!(
+            renderer.extract_figures(&deep).err(),
+            Some(too_deep.clone())
+        );
+        assert_eq!(renderer.annotate(&deep).err(), Some(too_deep.clone()));
+        assert_eq!(renderer.quote_reply(&deep, "deep"), Err(too_deep.clone()));
+        assert_eq!(renderer.quote_reply(&long, "a"), Err(too_long.clone()));
+
+        let options = renderer.options().clone();
+        let renderer = Arc::new(renderer);
+        assert_eq!(renderer.parse(&long).err(), Some(too_long.clone()));
+        assert_eq!(renderer.parse(&deep).err(), Some(too_deep.clone()));
+        assert_eq!(
+            ParsedMarkdown::new(&deep, options.clone()).err(),
+            Some(too_deep)
+        );
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! { <MarkdownChunked content=long.clone() options=options.clone() /> }
+                .to_html();
+            assert!(html.contains(&too_long.to_string()), "{html}");
+            let html = view! { <MarkdownChunked content=deep.clone() options=options.clone() /> }
+                .to_html();
+            assert!(html.contains("Failed to render markdown content"), "{html}");
+            assert!(!html.contains("<blockquote"), "{html}");
+            let html = view! {
+                <leptos_md::TableOfContents content=format!("# Title\n\n{deep}") options=options.clone() />
+            }
+            .to_html();
+            assert!(!html.contains("Title"), "{html}");
+        });
+    }
+
+    #[test]
+    fn test_toggle_task() {
+        use leptos_md::toggle_task;
+
+        let source = "Todo:\n\n- [ ] write\n- [X] test\n  - [ ] nested\n\n`- [ ] not a task`";
+        assert_eq!(
+            toggle_task(source, 0),
+            "Todo:\n\n- [x] write\n- [X] test\n  - [ ] nested\n\n`- [ ] not a task`"
+        );
+        assert_eq!(
+            toggle_task(source, 1),
+            "Todo:\n\n- [ ] write\n- [ ] test\n  - [ ] nested\n\n`- [ ] not a task`"
+        );
+        assert_eq!(
+            toggle_task(source, 2),
+            "Todo:\n\n- [ ] write\n- [X] test\n  - [x] nested\n\n`- [ ] not a task`"
+        );
+        assert_eq!(toggle_task(source, 3), source);
+    }
+
+    #[cfg(all(feature = "ssr", feature = "editor"))]
+    #[test]
+    fn test_markdown_editor() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{EditorLayout, MarkdownEditor};
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let value = RwSignal::new("# Draft".to_string());
+            let html = view! {
+                <MarkdownEditor value=value toolbar=|| view! { <button>"B"</button> } />
+            }
+            .to_html()
+            .replace("<!>", "");
+            assert!(html.contains("<textarea"));
+            assert!(html.contains("Draft</h1>"));
+            assert!(html.contains("role=\"toolbar\""));
+
+            let html = view! { <MarkdownEditor value=value layout=EditorLayout::Tabs /> }
+                .to_html()
+                .replace("<!>", "");
+            assert!(html.contains("role=\"tablist\""));
+            assert!(html.contains("<textarea"));
+            assert!(!html.contains("Draft</h1>"));
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_inline_only_rendering() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::{
+            provide_markdown_options, MarkdownInline, MarkdownOptions, MarkdownRenderer,
+        };
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_inline_only(true));
+        let html = renderer.render_to_html("**Bold** and\n[link](/x)").unwrap();
+        assert!(!html.contains("<p"));
+        assert!(html.contains("Bold</strong>"));
+        assert!(html.contains("href=\"/x\""));
+
+        for literal in [
+            "# Not a heading",
+            "1. Not a list",
+            "- Not an item",
+            "> Not a quote",
+        ] {
+            let html = renderer.render_to_html(literal).unwrap();
+            assert!(!html.contains("<h1") && !html.contains("<ol") && !html.contains("<ul"));
+            assert!(!html.contains("<blockquote"));
+            assert!(html.contains(&literal[literal.len() - 5..]), "{}", html);
+        }
+
+        let owner = Owner::new();
+        owner.with(|| {
+            let html = view! { <MarkdownInline content="*Alice*" class="name".to_string() /> }
+                .to_html()
+                .replace("<!>", "");
+            assert!(html.contains("<span class=\"leptos-md-inline name\""));
+            assert!(html.contains("Alice</em>"));
+
+            // Options provided through context apply when none are passed
+            provide_markdown_options(
+                MarkdownOptions::new()
+                    .with_explicit_classes(true)
+                    .with_class_map(leptos_md::MarkdownClassMap {
+                        em: "italic text-sky-700".into(),
+                        ..Default::default()
+                    }),
+            );
+            let html = view! { <MarkdownInline content="*Alice*" /> }.to_html();
+            assert!(
+                html.contains("class=\"md-em italic text-sky-700\""),
+                "{html}"
+            );
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_emoji_shortcodes() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer, RenderWarning};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_custom_emoji(|code| (code == "party_ferris").then(|| "/ferris.png".into())),
+        );
+        let html = renderer
+            .render_to_html("Ship it :party_ferris: at 10:30 :nope: `:party_ferris:`")
+            .unwrap();
+        assert!(html.contains("src=\"/ferris.png\""));
+        assert!(html.contains("alt=\":party_ferris:\""));
+        assert!(html.contains("at 10:30 :nope:"));
+        assert!(html.contains(":party_ferris:</code>"));
+
+        // Unknown shortcodes are reported; times are not shortcodes
+        let (_, warnings) = renderer
+            .render_with_warnings("Meet at 10:30:00 :notreal: :party_ferris:")
+            .unwrap();
+        assert_eq!(
+            warnings,
+            [RenderWarning::UnknownShortcode {
+                code: "notreal".to_string()
+            }]
+        );
+
+        // Custom emoji images are styled through the class map
+        let options = MarkdownOptions::new()
+            .with_custom_emoji(|_| Some("/ferris.png".into()))
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                emoji: "h-4 w-4".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(":party_ferris:")
+            .unwrap();
+        assert!(html.contains(r#"class="md-emoji h-4 w-4""#), "{html}");
+
+        #[cfg(feature = "emoji")]
+        {
+            let html = renderer.render_to_html("Done :white_check_mark:").unwrap();
+            assert!(html.contains('\u{2705}'));
+            assert!(!html.contains(":white_check_mark:"));
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_mentions_and_hashtags() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer, MentionTarget};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_mentions(|name| {
+                    (name == "jane_doe").then(|| MentionTarget::new("/u/1").with_label("Jane Doe"))
+                })
+                .with_hashtags(|topic| Some(MentionTarget::new(format!("/tags/{}", topic)))),
+        );
+        let html = renderer
+            .render_to_html("Thanks @jane_doe. Ping @ghost or mail a@jane_doe.dev #leptos #123")
+            .unwrap();
+        assert!(html.contains("href=\"/u/1\""));
+        assert!(html.contains("Jane Doe</a>"));
+        assert!(html.contains("@ghost"));
+        assert!(html.contains("a@jane_doe.dev"));
+        assert!(html.contains("href=\"/tags/leptos\""));
+        assert!(!html.contains("/tags/123"));
+
+        let html = renderer
+            .render_to_html("[see @jane_doe](/profile)")
+            .unwrap();
+        assert!(!html.contains("/u/1"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_repo_references() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::new().with_repo_references("https://github.com/acme/app/"),
+        );
+        let html = renderer
+            .render_to_html(
+                "Fixes #12 and leptos-rs/leptos#3400 in 3f2a9c1d8e, not 1234567, C#7 or `#9`",
+            )
+            .unwrap();
+        assert!(html.contains("href=\"https://github.com/acme/app/issues/12\""));
+        assert!(html.contains("href=\"https://github.com/leptos-rs/leptos/issues/3400\""));
+        assert!(html.contains("leptos-rs/leptos#3400</a>"));
+        assert!(html.contains("href=\"https://github.com/acme/app/commit/3f2a9c1d8e\""));
+        assert!(html.contains(">3f2a9c1</a>"));
+        assert!(!html.contains("commit/1234567\""));
+        assert!(!html.contains("issues/7"));
+        assert!(!html.contains("issues/9"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_bare_url_autolinks() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_autolinks(true));
+        let html = renderer
+            .render_to_html(
+                "See https://leptos.dev/docs. (or www.rust-lang.org) mail team@example.com \
+                 but not `https://in.code` or [https://a.dev](https://b.dev)",
+            )
+            .unwrap();
+        assert!(html.contains("href=\"https://leptos.dev/docs\""));
+        assert!(html.contains("href=\"http://www.rust-lang.org\""));
+        assert!(html.contains("href=\"mailto:team@example.com\""));
+        assert!(!html.contains("href=\"https://in.code\""));
+        assert!(!html.contains("href=\"https://a.dev\""));
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html("See https://leptos.dev")
+            .unwrap();
+        assert!(!html.contains("<a"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_wikilinks() {
+        use leptos_md::{MarkdownClasses, MarkdownOptions, MarkdownRenderer};
+
+        let renderer =
+            MarkdownRenderer::new(MarkdownOptions::new().with_wikilink_resolver(|target| {
+                (target == "Home").then(|| "/notes/home".to_string())
+            }));
+        let html = renderer
+            .render_to_html("Back to [[Home|the start]] or [[Missing Page]]")
+            .unwrap();
+        assert!(html.contains("href=\"/notes/home\""));
+        assert!(html.contains("the start</a>"));
+        assert!(html.contains(MarkdownClasses::WIKILINK_UNRESOLVED));
+        assert!(html.contains("data-wikilink=\"Missing Page\""));
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_wikilinks(true))
+            .render_to_html("[[Getting Started]]")
+            .unwrap();
+        assert!(html.contains("href=\"Getting%20Started\""));
+
+        // Without a resolver targets are encoded as relative paths and schemes never link
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_wikilinks(true))
+            .render_to_html(
+                "[[javascript:alert(1)|click]] [[//evil.example]] [[Q&A \"Notes\"#Intro]]",
+            )
+            .unwrap();
+        assert!(!html.contains("href=\"javascript"));
+        assert!(html.contains("data-wikilink=\"javascript:alert(1)\""));
+        assert!(html.contains("data-wikilink=\"//evil.example\""));
+        assert!(html.contains("href=\"Q%26A%20%22Notes%22#Intro\""));
+
+        // Unresolved links and embeds are styled through the class map
+        let options = MarkdownOptions::new()
+            .with_wikilink_resolver(|_| None)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                wikilink_unresolved: "text-red-600".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("[[Missing]] and ![[Note]]")
+            .unwrap();
+        assert!(
+            html.contains(r#"class="md-wikilink-unresolved text-red-600""#),
+            "{html}"
+        );
+        assert!(html.contains(r#"class="md-embed inline-flex"#), "{html}");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_obsidian_flavor() {
+        use leptos_md::{MarkdownFlavor, MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::for_flavor(MarkdownFlavor::Obsidian));
+        let html = renderer
+            .render_to_html(
+                "> [!faq]- Why *Leptos*?\n> Because it is ==fast *and* small==.\n\n\
+                 ![[Daily Note]] links [[Home]] and a == b\n\n![[diagram.png]]",
+            )
+            .unwrap();
+        assert!(html.contains("role=\"note\""));
+        assert!(html.contains("Why Leptos?"));
+        assert!(html.contains("<mark class="));
+        assert!(html.contains("small</mark>"));
+        assert!(html.contains("data-embed=\"Daily Note\""));
+        assert!(html.contains("href=\"Home\""));
+        assert_eq!(html.matches("<mark").count(), 1);
+        assert!(html.contains("src=\"diagram.png\""));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_chat_flavor() {
+        use leptos_md::{MarkdownFlavor, MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::for_flavor(MarkdownFlavor::Chat));
+        let html = renderer
+            .render_to_html("the ending is ||they all *lived*||\n__really__ **bold**")
+            .unwrap();
+        assert!(html.contains("<br"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("lived</em>"));
+        assert!(html.contains("really</u>"));
+        assert!(html.contains("bold</strong>"));
+
+        let renderer = MarkdownRenderer::new(
+            MarkdownOptions::for_flavor(MarkdownFlavor::Chat).with_headings(false),
+        );
+        let html = renderer.render_to_html("# 1 fan here").unwrap();
+        assert!(!html.contains("<h1"));
+        assert!(html.contains("# "));
+
+        // Spoilers and underlines are styled through the class map
+        let options = MarkdownOptions::for_flavor(MarkdownFlavor::Chat)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                spoiler: "bg-black".into(),
+                underline: "".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("||secret|| and __this__")
+            .unwrap();
+        assert!(
+            html.contains(r#"<label class="md-spoiler bg-black""#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"class="md-spoiler-text blur-[5px]"#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<u class="md-underline">this</u>"#),
+            "{html}"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_highlight_mark() {
+        use leptos_md::{MarkdownClassMap, MarkdownOptions, MarkdownRenderer};
+
+        let content = "a ==key *point*== but a == b and ===";
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_highlight(true))
+            .render_to_html(content)
+            .unwrap();
+        assert!(html.contains("<mark class=\"markdown-mark bg-[color:var(--md-mark-bg"));
+        assert!(html.contains("point</em>"));
+        assert_eq!(html.matches("<mark").count(), 1);
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(content)
+            .unwrap();
+        assert!(!html.contains("<mark"));
+
+        let options = MarkdownOptions::new()
+            .with_highlight(true)
+            .with_explicit_classes(true)
+            .with_class_map(MarkdownClassMap {
+                mark: "bg-pink-200".into(),
+                ..MarkdownClassMap::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html(content)
+            .unwrap();
+        assert!(html.contains("<mark class=\"md-mark bg-pink-200\""));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_inserted_text() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let content = "The fee is ~~$10~~ ++$12 *net*++ in C++ docs";
+        let html = MarkdownRenderer::new(MarkdownOptions::new().with_insertions(true))
+            .render_to_html(content)
+            .unwrap();
+        assert!(html.contains("<ins class=\"markdown-ins "));
+        assert!(html.contains("net</em>"));
+        assert!(html.contains("</del>"));
+        assert_eq!(html.matches("<ins").count(), 1);
+
+        let html = MarkdownRenderer::new(MarkdownOptions::new())
+            .render_to_html(content)
+            .unwrap();
+        assert!(!html.contains("<ins"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_kbd_keys() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_kbd(true));
+        let html = renderer
+            .render_to_html("Press `kbd:Ctrl+Shift+P`, then [[Esc]]; zoom `kbd:Ctrl++` `ls`")
+            .unwrap();
+        assert!(html.contains(">Ctrl</kbd>"));
+        assert!(html.contains(">Shift</kbd>"));
+        assert!(html.contains(">Esc</kbd>"));
+        assert!(html.contains(">+</kbd>"));
+        assert_eq!(html.matches("<kbd").count(), 6);
+        assert!(html.contains("ls</code>"));
+
+        // Wikilinks take the `[[...]]` form; `kbd:` code spans still render as keys
+        let html =
+            MarkdownRenderer::new(MarkdownOptions::new().with_kbd(true).with_wikilinks(true))
+                .render_to_html("[[Esc]] or `kbd:Ctrl+C`")
+                .unwrap();
+        assert!(html.contains("href=\"Esc\""), "{html}");
+        assert!(html.contains(">Esc</a>"), "{html}");
+        assert!(!html.contains(">Esc</kbd>"), "{html}");
+        assert_eq!(html.matches("<kbd").count(), 2, "{html}");
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_admonition_containers() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_admonitions(true));
+        let html = renderer
+            .render_to_html(
+                "::: warning Before you upgrade\nBack up first:\n\n- dump\n- verify\n:::\n\n\
+                 :::danger\nNo undo.\n:::\n\n:::aside\nSide note\n:::",
+            )
+            .unwrap();
+        assert!(html.contains("markdown-alert-warning"));
+        assert!(html.contains("Before you upgrade"));
+        assert!(html.contains("verify</li>"));
+        assert!(html.contains("markdown-alert-caution"));
+        assert!(html.contains("Danger"));
+        assert!(html.contains("data-container=\"aside\""));
+        assert!(!html.contains(":::"));
+
+        // A closing marker right after a list or quote is lazy continuation text to pulldown
+        let html = renderer
+            .render_to_html(
+                "::: warning\n- verify\n:::\nAfter the list\n\n::: note\n1. one\n\n   two\n:::\n\n\
+                 ::: tip\n> quoted\n:::",
+            )
+            .unwrap();
+        assert!(html.contains("verify</li></ul></div>"));
+        assert!(html.contains("<p>After the list</p>"));
+        assert!(html.contains("two</p></li></ol></div>"));
+        assert!(html.contains("quoted</p></blockquote></div>"));
+        assert!(!html.contains(":::"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_tab_groups() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_tabs(true));
+        let html = renderer
+            .render_to_html(
+                ":::tabs\n== Cargo\n```sh\ncargo add leptos-md\n```\n== npm\nRun this:\n```sh\nnpm install\n```\n:::",
+            )
+            .unwrap();
+        assert_eq!(html.matches("role=\"tablist\"").count(), 1);
+        assert_eq!(html.matches("role=\"tab\"").count(), 2);
+        assert_eq!(html.matches("role=\"tabpanel\"").count(), 2);
+        assert_eq!(html.matches("aria-selected=\"true\"").count(), 1);
+        assert!(html.contains(">Cargo</button>"));
+        assert!(html.contains(">npm</button>"));
+        assert!(html.contains("Run this:</p>"));
+        // Highlighting splits the command into spans
+        assert!(html.contains("add leptos-md"));
+        assert!(!html.contains("=="));
+        assert!(!html.contains(":::"));
+
+        let html = renderer
+            .render_to_html(
+                "```sh tab=Cargo\ncargo build\n```\n```sh tab=\"Cargo (release)\"\ncargo build -r\n```\n\n```sh\nls\n```",
+            )
+            .unwrap();
+        assert_eq!(html.matches("role=\"tablist\"").count(), 1);
+        assert!(html.contains(">Cargo (release)</button>"));
+        assert_eq!(html.matches("role=\"tabpanel\"").count(), 2);
+        assert!(html.contains("ls"));
+        assert!(html.contains(r#"class="markdown-tabs my-6""#), "{html}");
+
+        let options = MarkdownOptions::new()
+            .with_tabs(true)
+            .with_explicit_classes(true)
+            .with_class_map(leptos_md::MarkdownClassMap {
+                tab: "tab tab-bordered".into(),
+                ..Default::default()
+            });
+        let html = MarkdownRenderer::new(options)
+            .render_to_html("```sh tab=A\na\n```\n```sh tab=B\nb\n```")
+            .unwrap();
+        assert!(html.contains(r#"class="md-tabs my-6""#), "{html}");
+        assert!(html.contains(r#"class="md-tab-list "#), "{html}");
+        assert_eq!(
+            html.matches(r#"class="md-tab tab tab-bordered""#).count(),
+            2
+        );
+        assert_eq!(html.matches(r#"class="md-tab-panel pt-2""#).count(), 2);
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_details_containers() {
+        use leptos_md::{MarkdownOptions, MarkdownRenderer};
+
+        let renderer = MarkdownRenderer::new(MarkdownOptions::new().with_details(true));
+        let html = renderer
+            .render_to_html(
+                ":::details Does it need JavaScript?\nNo, `<details>` opens natively.\n:::\n\n:::details\n- one\n- two\n:::",
+            )
+            .unwrap();
+        assert_eq!(html.matches("<details").count(), 2);
+        assert!(html.contains("markdown-details"));
+        assert!(html.contains(">Does it need JavaScript?</summary>"));
+        assert!(html.contains(">Details</summary>"));
+        assert!(html.contains("two</li>"));
+        assert!(!html.contains(":::"));
+
+        let html = MarkdownRenderer::new(
+            MarkdownOptions::new()
+                .with_details(true)
+                .with_explicit_classes(true),
+        )
+        .render_to_html(":::details FAQ\nAnswer\n:::")
+        .unwrap();
+        assert!(html.contains("md-details"));
+        assert!(html.contains("md-summary"));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_markdown_chunked_sections() {
+        use leptos::prelude::*;
+        use leptos::tachys::view::RenderHtml;
+        use leptos_md::MarkdownChunked;
+
+        let content = "See [the docs][docs] and a note[^a].\n\n\
+                       ```\ncode\n\nstill code\n```\n\n\
+                       # Later\n\n[^a]: The note.\n\n[docs]: https://example.com/docs";
+        let owner = Owner::new();
+        owner.with(|| {
+            // Only the first section renders before hydration; it already resolves links
+            // defined further down
+            let html = view! { <MarkdownChunked content=content blocks_per_frame=1 /> }.to_html();
+            assert!(html.contains(r#"href="https://example.com/docs""#));
+            assert!(!html.contains("still code"));
+            assert!(!html.contains("Later"));
+
+            let html = view! { <MarkdownChunked content=content blocks_per_frame=100 /> }.to_html();
+            assert!(html.contains("still code"));
+            assert!(html.contains(">1</a></sup>"));
+            assert!(html.contains(r#"role="doc-endnotes""#));
+            assert!(html.contains("The note."));
+        });
+    }
 }